@@ -285,8 +285,9 @@ class EncoderfileConfig:
         name: Model identifier as specified during the build.
         version: Model version string (e.g. ``"1.0.0"``).
         model_type: Encoderfile model type (``"embedding"``,
-            ``"sequence_classification"``, ``"token_classification"``, or
-            ``"sentence_embedding"``).
+            ``"sequence_classification"``, ``"token_classification"``,
+            ``"sentence_embedding"``, ``"sparse_embedding"``, or
+            ``"late_interaction"``).
         transform: Inline Lua post-processing script, or ``None`` if no
             transform was embedded.
         lua_libs: Additional Lua library paths available to the transform,
@@ -6,6 +6,8 @@ class ModelType(StrEnum):
     SequenceClassification = "sequence_classification"
     TokenClassification = "token_classification"
     SentenceEmbedding = "sentence_embedding"
+    SparseEmbedding = "sparse_embedding"
+    LateInteraction = "late_interaction"
 
 
 class TokenizerTruncationSide(StrEnum):
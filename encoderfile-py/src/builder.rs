@@ -211,6 +211,25 @@ impl PyEncoderfileBuilder {
                 })
                 .transpose()?
                 .map(|t| t.0),
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
         Ok(PyEncoderfileBuilder(EncoderfileBuilder {
             config: BuildConfig { encoderfile },
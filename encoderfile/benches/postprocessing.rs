@@ -1,5 +1,6 @@
 use divan::Bencher;
 use encoderfile::{
+    common::InferenceOptions,
     dev_utils::*,
     inference::{embedding, sequence_classification, token_classification},
     runtime::TokenizerService,
@@ -29,7 +30,13 @@ fn embedding_postprocess(b: Bencher, dim: (usize, usize, usize)) {
     // Dummy encodings
     let encodings = generate_dummy_encodings(tokenizer, batch, tokens);
 
-    b.bench(|| embedding::postprocess(outputs.clone(), encodings.clone()));
+    b.bench(|| {
+        embedding::postprocess(
+            outputs.clone(),
+            encodings.clone(),
+            &InferenceOptions::default(),
+        )
+    });
 }
 
 #[divan::bench(args = [8, 16, 64])]
@@ -45,7 +52,7 @@ fn sequence_classification_postprocess(b: Bencher, batch: usize) {
 
     let outputs = Array::from_shape_vec((batch, n_labels), data).unwrap();
 
-    b.bench(|| sequence_classification::postprocess(outputs.clone(), config));
+    b.bench(|| sequence_classification::postprocess(outputs.clone(), outputs.clone(), config, 0.5));
 }
 
 #[divan::bench(args = [(8, 16), (16, 128), (64, 512)])]
@@ -67,7 +74,14 @@ fn token_classification_postprocess(b: Bencher, dim: (usize, usize)) {
     // Dummy encodings
     let encodings = generate_dummy_encodings(tokenizer, batch, tokens);
 
-    b.bench(|| token_classification::postprocess(outputs.clone(), encodings.clone(), config));
+    b.bench(|| {
+        token_classification::postprocess(
+            outputs.clone(),
+            encodings.clone(),
+            config,
+            &InferenceOptions::default(),
+        )
+    });
 }
 
 fn generate_dummy_encodings(
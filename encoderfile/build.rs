@@ -12,18 +12,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/sequence_classification.proto",
                 "proto/token_classification.proto",
                 "proto/sentence_embedding.proto",
+                "proto/sparse_embedding.proto",
+                "proto/late_interaction.proto",
                 "proto/image_classification.proto",
+                "proto/image_embedding.proto",
+                "proto/object_detection.proto",
+                "proto/image_segmentation.proto",
+                "proto/audio_classification.proto",
+                "proto/audio_embedding.proto",
+                "proto/multimodal_embedding.proto",
                 "proto/manifest.proto",
                 "proto/image_types.proto",
+                "proto/audio_types.proto",
             ],
             &[
                 "proto/embedding",
                 "proto/sequence_classification",
                 "proto/token_classification",
                 "proto/sentence_embedding",
+                "proto/sparse_embedding",
+                "proto/late_interaction",
                 "proto/image_classification",
+                "proto/image_embedding",
+                "proto/object_detection",
+                "proto/image_segmentation",
+                "proto/audio_classification",
+                "proto/audio_embedding",
+                "proto/multimodal_embedding",
                 "proto/manifest",
                 "proto/image_types",
+                "proto/audio_types",
             ],
         )?;
 
@@ -0,0 +1,124 @@
+use crate::format::assets::{AssetKind, AssetSource, PlannedAsset};
+use anyhow::Result;
+
+use super::config::EncoderfileConfig;
+use crate::runtime::AudioFeatureExtractor;
+
+pub fn validate_audio_feature_extractor<'a>(
+    encoderfile_config: &'a EncoderfileConfig,
+) -> Result<PlannedAsset<'a>> {
+    let config = match encoderfile_config.path.preprocessor_config_path()? {
+        Some(preprocessor_config_path) => {
+            let contents = std::fs::read_to_string(preprocessor_config_path)?;
+            let feature_extractor: AudioFeatureExtractor = serde_json::from_str(contents.as_str())?;
+            feature_extractor
+        }
+        None => {
+            anyhow::bail!("FATAL: No preprocessor_config.json provided");
+        }
+    };
+
+    if config.sampling_rate == 0 {
+        anyhow::bail!("sampling_rate must be greater than 0");
+    }
+
+    if config.num_mel_bins == Some(0) {
+        anyhow::bail!("num_mel_bins must be greater than 0");
+    }
+
+    if config.max_length == Some(0) {
+        anyhow::bail!("max_length must be greater than 0");
+    }
+
+    if config.std == Some(0.0) {
+        anyhow::bail!("std must not be 0");
+    }
+
+    let serialized = serde_json::to_vec(&config)?;
+
+    PlannedAsset::from_asset_source(
+        AssetSource::InMemory(std::borrow::Cow::Owned(serialized)),
+        AssetKind::AudioFeatureExtractor,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::config::ModelPath;
+    use crate::common::model_type::ModelType;
+
+    use super::*;
+
+    fn test_config(dir: &std::path::Path) -> EncoderfileConfig {
+        EncoderfileConfig {
+            name: "my-model".into(),
+            version: "0.0.1".into(),
+            path: ModelPath::Directory(dir.to_path_buf()),
+            model_type: ModelType::AudioClassification,
+            output_path: None,
+            cache_dir: None,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            validate_transform: false,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_ast_feature_extractor() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("preprocessor_config.json"),
+            r#"{
+                "do_normalize": true,
+                "feature_extractor_type": "ASTFeatureExtractor",
+                "feature_size": 1,
+                "max_length": 1024,
+                "mean": -4.2677393,
+                "num_mel_bins": 128,
+                "padding_side": "right",
+                "padding_value": 0.0,
+                "return_attention_mask": false,
+                "sampling_rate": 16000,
+                "std": 4.5689974
+            }"#,
+        )
+        .unwrap();
+
+        validate_audio_feature_extractor(&test_config(dir.path()))
+            .expect("Failed to validate audio feature extractor config");
+    }
+
+    #[test]
+    fn test_validate_missing_sampling_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("preprocessor_config.json"),
+            r#"{"feature_extractor_type": "Wav2Vec2FeatureExtractor", "feature_size": 1}"#,
+        )
+        .unwrap();
+
+        assert!(validate_audio_feature_extractor(&test_config(dir.path())).is_err());
+    }
+}
@@ -75,12 +75,16 @@ impl EncoderfileBuilder {
 
         // validate model
         let model_weights_path = self.config.encoderfile.path.model_weights_path()?;
+        let vision_model_weights_path = match self.config.encoderfile.model_type.input_type() {
+            Input::Multimodal => self.config.encoderfile.path.vision_model_weights_path()?,
+            _ => None,
+        };
 
-        let model_asset = self
-            .config
-            .encoderfile
-            .model_type
-            .validate_model(&model_weights_path)?;
+        let model_asset = self.config.encoderfile.model_type.validate_model(
+            &model_weights_path,
+            &model_config.outputs.clone().unwrap_or_default(),
+            &model_config.inputs.clone().unwrap_or_default(),
+        )?;
 
         planned_assets.push(model_asset);
         terminal::success("Model weights validated");
@@ -93,6 +97,20 @@ impl EncoderfileBuilder {
             terminal::success("Transform validated");
         }
 
+        // validate projection
+        if let Some(asset) =
+            crate::builder::projection::validate_projection(&self.config.encoderfile)?
+        {
+            planned_assets.push(asset);
+            terminal::success("Projection validated");
+        }
+
+        // embed corpus
+        if let Some(asset) = crate::builder::corpus::validate_corpus(&self.config.encoderfile)? {
+            planned_assets.push(asset);
+            terminal::success("Corpus embedded");
+        }
+
         // validate tokenizer
         match self.config.encoderfile.model_type.input_type() {
             Input::Text => {
@@ -109,6 +127,38 @@ impl EncoderfileBuilder {
                 planned_assets.push(image_preprocessor_asset);
                 terminal::success("Image preprocessor validated");
             }
+            Input::Audio => {
+                let audio_feature_extractor_asset =
+                    crate::builder::audio_feature_extractor::validate_audio_feature_extractor(
+                        &self.config.encoderfile,
+                    )?;
+                planned_assets.push(audio_feature_extractor_asset);
+                terminal::success("Audio feature extractor validated");
+            }
+            Input::Multimodal => {
+                let tokenizer_asset =
+                    crate::builder::tokenizer::validate_tokenizer(&self.config.encoderfile)?;
+                planned_assets.push(tokenizer_asset);
+                terminal::success("Tokenizer validated");
+
+                let image_preprocessor_asset =
+                    crate::builder::image_preprocessor::validate_image_preprocessor(
+                        &self.config.encoderfile,
+                    )?;
+                planned_assets.push(image_preprocessor_asset);
+                terminal::success("Image preprocessor validated");
+
+                let vision_model_weights_path =
+                    vision_model_weights_path.as_deref().ok_or_else(|| {
+                        anyhow::anyhow!("Missing required path: vision model weights")
+                    })?;
+                let vision_model_asset = self.config.encoderfile.model_type.validate_vision_model(
+                    vision_model_weights_path,
+                    &model_config.outputs.clone().unwrap_or_default(),
+                )?;
+                planned_assets.push(vision_model_asset);
+                terminal::success("Vision model weights validated");
+            }
         }
 
         // initialize final binary
@@ -0,0 +1,605 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use clap_derive::ValueEnum;
+use ndarray::{Array2, ArrayView1, Axis};
+use parking_lot::Mutex;
+use serde::Deserialize;
+
+use super::{config::EncoderfileConfig, tokenizer::tokenizer_service};
+use crate::{
+    common::{
+        Calibration, DEFAULT_MULTI_LABEL_THRESHOLD, IsotonicCurve, ModelInputs, ModelOutputs,
+        ProblemType, TruncationMode, model_type::ModelType,
+    },
+    inference::{
+        self,
+        inputs::InputBinder,
+        sequence_classification::{calibrate, probabilities},
+    },
+    runtime::{ClassifierState, ORTSessionBuilder},
+    transforms::{DEFAULT_LIBS, SequenceClassificationTransform},
+};
+
+const BATCH_SIZE: usize = 32;
+
+/// Bounds of the temperatures searched by `temperature` and `per-class-temperature`.
+const MIN_TEMPERATURE: f64 = 0.05;
+const MAX_TEMPERATURE: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CalibrationMethod {
+    Temperature,
+    PerClassTemperature,
+    Platt,
+    Isotonic,
+}
+
+/// A labelled example of the dev set. Single-label models take a `label`, multi-label
+/// models a list of `labels`.
+#[derive(Debug, Deserialize)]
+struct CalibrationExample {
+    text: String,
+    label: Option<String>,
+    labels: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct FittedCalibration {
+    pub calibration: Calibration,
+    pub num_examples: usize,
+    /// Mean log loss of the uncalibrated scores on the dev set.
+    pub log_loss: f64,
+    /// Mean log loss of the calibrated scores on the dev set.
+    pub calibrated_log_loss: f64,
+}
+
+/// Fits `method` to the logits the model being packaged returns on the dev set at `path`.
+pub fn fit_calibration(
+    encoderfile_config: &EncoderfileConfig,
+    path: &Path,
+    method: CalibrationMethod,
+) -> Result<FittedCalibration> {
+    if encoderfile_config.model_type != ModelType::SequenceClassification {
+        bail!("calibration is only supported for sequence_classification models");
+    }
+
+    // calibration is fit on the uncalibrated scores of the model's own labels
+    let mut model_config = encoderfile_config.model_config()?;
+    model_config.calibration = None;
+    model_config.label_overrides = None;
+
+    let state = ClassifierState::from_model_config(&model_config)?;
+
+    let problem_type = state.problem_type();
+    if problem_type == ProblemType::Regression {
+        bail!("calibration is not supported for regression models");
+    }
+
+    let (texts, targets) = read_examples(path, &state, problem_type)?;
+    let model_outputs = model_config.outputs.clone().unwrap_or_default();
+    let model_inputs = model_config.inputs.clone().unwrap_or_default();
+    let logits = classify(
+        encoderfile_config,
+        &model_outputs,
+        &model_inputs,
+        &state,
+        texts,
+    )?;
+
+    if logits.ncols() != targets.ncols() {
+        bail!(
+            "Model returns {} logits for {} labels",
+            logits.ncols(),
+            targets.ncols()
+        );
+    }
+
+    let calibration = match method {
+        CalibrationMethod::Temperature => Calibration::Temperature {
+            temperature: fit_temperature(&logits, &targets, problem_type),
+        },
+        CalibrationMethod::PerClassTemperature => {
+            fit_per_class_temperature(&logits, &targets, problem_type)
+        }
+        CalibrationMethod::Platt => fit_platt(&logits, &targets),
+        CalibrationMethod::Isotonic => fit_isotonic(&logits, &targets, problem_type),
+    };
+
+    Ok(FittedCalibration {
+        num_examples: logits.nrows(),
+        log_loss: log_loss(&logits, &targets, problem_type, None),
+        calibrated_log_loss: log_loss(&logits, &targets, problem_type, Some(&calibration)),
+        calibration,
+    })
+}
+
+/// Texts of the dev set and their one-hot targets of shape `[num_examples, num_labels]`.
+fn read_examples(
+    path: &Path,
+    state: &ClassifierState,
+    problem_type: ProblemType,
+) -> Result<(Vec<String>, Array2<f32>)> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read calibration data at {:?}", path))?;
+
+    let labels = state.labels_by_id();
+    let mut texts = Vec::new();
+    let mut targets = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let example: CalibrationExample = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse calibration example on line {}", i + 1))?;
+
+        let example_labels = match (example.label, example.labels) {
+            (Some(label), None) => vec![label],
+            (None, Some(labels)) if problem_type == ProblemType::MultiLabelClassification => labels,
+            _ => bail!(
+                "Calibration example on line {} must set a label{}",
+                i + 1,
+                match problem_type {
+                    ProblemType::MultiLabelClassification => " or a list of labels",
+                    _ => "",
+                }
+            ),
+        };
+
+        let mut target = vec![0.0; labels.len()];
+        for label in example_labels {
+            let id = labels
+                .iter()
+                .position(|l| *l == label)
+                .with_context(|| format!("Unknown label {:?} on line {}", label, i + 1))?;
+            target[id] = 1.0;
+        }
+
+        texts.push(example.text);
+        targets.extend(target);
+    }
+
+    if texts.is_empty() {
+        bail!("Calibration data at {:?} has no examples", path);
+    }
+
+    let targets = Array2::from_shape_vec((texts.len(), labels.len()), targets)?;
+
+    Ok((texts, targets))
+}
+
+/// Logits of every text, computed with the model being packaged. Calibration maps the
+/// model's logits, so the transform is not applied.
+fn classify(
+    encoderfile_config: &EncoderfileConfig,
+    model_outputs: &ModelOutputs,
+    model_inputs: &ModelInputs,
+    state: &ClassifierState,
+    texts: Vec<String>,
+) -> Result<Array2<f32>> {
+    let session =
+        ORTSessionBuilder::default().from_file(encoderfile_config.path.model_weights_path()?)?;
+    let inputs = InputBinder::new(&session.inputs, model_inputs)?;
+    let session = Mutex::new(session);
+    let tokenizer = tokenizer_service(encoderfile_config)?;
+    let transform = SequenceClassificationTransform::new(DEFAULT_LIBS.to_vec(), None)?;
+
+    let mut logits = Vec::new();
+
+    for batch in texts.chunks(BATCH_SIZE) {
+        let encodings = tokenizer.encode_text_with_truncation(
+            batch.to_vec(),
+            None,
+            TruncationMode::Truncate,
+        )?;
+
+        let results = inference::sequence_classification::sequence_classification(
+            session.lock(),
+            &transform,
+            model_outputs,
+            &inputs,
+            state,
+            DEFAULT_MULTI_LABEL_THRESHOLD,
+            encodings,
+        )
+        .with_context(|| "Failed to classify calibration data")?;
+
+        logits.extend(results.into_iter().map(|r| r.logits));
+    }
+
+    let num_labels = logits[0].len();
+
+    Ok(Array2::from_shape_vec(
+        (logits.len(), num_labels),
+        logits.into_iter().flatten().collect(),
+    )?)
+}
+
+/// Mean negative log-likelihood of the targets under the (calibrated) probabilities.
+fn log_loss(
+    logits: &Array2<f32>,
+    targets: &Array2<f32>,
+    problem_type: ProblemType,
+    calibration: Option<&Calibration>,
+) -> f64 {
+    let total: f64 = logits
+        .axis_iter(Axis(0))
+        .zip(targets.axis_iter(Axis(0)))
+        .map(|(logits, targets)| {
+            let probs = match calibration {
+                Some(calibration) => calibrate(logits, calibration, problem_type),
+                None => probabilities(logits, problem_type),
+            };
+
+            probs
+                .iter()
+                .zip(targets.iter())
+                .map(|(p, t)| {
+                    let p = (*p as f64).clamp(1e-12, 1.0 - 1e-12);
+
+                    match problem_type {
+                        ProblemType::MultiLabelClassification => {
+                            -(*t as f64 * p.ln() + (1.0 - *t as f64) * (1.0 - p).ln())
+                        }
+                        _ => -(*t as f64) * p.ln(),
+                    }
+                })
+                .sum::<f64>()
+        })
+        .sum();
+
+    total / logits.nrows() as f64
+}
+
+fn fit_temperature(logits: &Array2<f32>, targets: &Array2<f32>, problem_type: ProblemType) -> f32 {
+    best_temperature(|temperature| {
+        let calibration = Calibration::Temperature { temperature };
+        log_loss(logits, targets, problem_type, Some(&calibration))
+    })
+}
+
+/// Coordinate descent over the temperature of each class, starting from the best single
+/// temperature.
+fn fit_per_class_temperature(
+    logits: &Array2<f32>,
+    targets: &Array2<f32>,
+    problem_type: ProblemType,
+) -> Calibration {
+    const PASSES: usize = 5;
+
+    let temperature = fit_temperature(logits, targets, problem_type);
+    let mut temperatures = vec![temperature; logits.ncols()];
+
+    for _ in 0..PASSES {
+        for class in 0..temperatures.len() {
+            temperatures[class] = best_temperature(|temperature| {
+                let mut temperatures = temperatures.clone();
+                temperatures[class] = temperature;
+
+                let calibration = Calibration::PerClassTemperature { temperatures };
+                log_loss(logits, targets, problem_type, Some(&calibration))
+            });
+        }
+    }
+
+    Calibration::PerClassTemperature { temperatures }
+}
+
+/// The temperature minimizing `loss`, searched on a log scale.
+fn best_temperature(loss: impl Fn(f32) -> f64) -> f32 {
+    let log_temperature = minimize(
+        |log_temperature| loss(log_temperature.exp() as f32),
+        MIN_TEMPERATURE.ln(),
+        MAX_TEMPERATURE.ln(),
+    );
+
+    log_temperature.exp() as f32
+}
+
+/// One-vs-rest logistic regression of each class on its logit, with Platt's smoothed
+/// targets to avoid overfitting separable classes.
+fn fit_platt(logits: &Array2<f32>, targets: &Array2<f32>) -> Calibration {
+    let (slopes, intercepts): (Vec<f32>, Vec<f32>) = logits
+        .axis_iter(Axis(1))
+        .zip(targets.axis_iter(Axis(1)))
+        .map(|(logits, targets)| platt_scaling(logits, targets))
+        .unzip();
+
+    Calibration::Platt { slopes, intercepts }
+}
+
+/// Newton's method with backtracking on the log loss of `sigmoid(slope * logit + intercept)`.
+fn platt_scaling(logits: ArrayView1<f32>, targets: ArrayView1<f32>) -> (f32, f32) {
+    const MAX_ITERATIONS: usize = 100;
+
+    let num_positive = targets.iter().filter(|t| **t > 0.5).count() as f64;
+    let num_negative = targets.len() as f64 - num_positive;
+
+    let high = (num_positive + 1.0) / (num_positive + 2.0);
+    let low = 1.0 / (num_negative + 2.0);
+
+    let points: Vec<(f64, f64)> = logits
+        .iter()
+        .zip(targets.iter())
+        .map(|(z, t)| (*z as f64, if *t > 0.5 { high } else { low }))
+        .collect();
+
+    let loss = |a: f64, b: f64| -> f64 {
+        points
+            .iter()
+            .map(|(z, t)| {
+                let p = sigmoid(a * z + b).clamp(1e-12, 1.0 - 1e-12);
+                -(t * p.ln() + (1.0 - t) * (1.0 - p).ln())
+            })
+            .sum()
+    };
+
+    let (mut a, mut b) = (0.0, ((num_positive + 1.0) / (num_negative + 1.0)).ln());
+    let mut current = loss(a, b);
+
+    for _ in 0..MAX_ITERATIONS {
+        let (mut g_a, mut g_b, mut h_aa, mut h_ab, mut h_bb) = (0.0, 0.0, 1e-12, 0.0, 1e-12);
+
+        for (z, t) in &points {
+            let p = sigmoid(a * z + b);
+            let w = p * (1.0 - p);
+
+            g_a += (p - t) * z;
+            g_b += p - t;
+            h_aa += w * z * z;
+            h_ab += w * z;
+            h_bb += w;
+        }
+
+        if g_a.abs() < 1e-6 && g_b.abs() < 1e-6 {
+            break;
+        }
+
+        let det = h_aa * h_bb - h_ab * h_ab;
+        let d_a = (h_bb * g_a - h_ab * g_b) / det;
+        let d_b = (h_aa * g_b - h_ab * g_a) / det;
+
+        let mut step = 1.0;
+        while step > 1e-10 {
+            let next = loss(a - step * d_a, b - step * d_b);
+
+            if next < current {
+                a -= step * d_a;
+                b -= step * d_b;
+                current = next;
+                break;
+            }
+
+            step /= 2.0;
+        }
+
+        if step <= 1e-10 {
+            break;
+        }
+    }
+
+    (a as f32, b as f32)
+}
+
+fn fit_isotonic(
+    logits: &Array2<f32>,
+    targets: &Array2<f32>,
+    problem_type: ProblemType,
+) -> Calibration {
+    let scores: Vec<_> = logits
+        .axis_iter(Axis(0))
+        .map(|logits| probabilities(logits, problem_type))
+        .collect();
+
+    let curves = (0..logits.ncols())
+        .map(|class| {
+            isotonic_regression(
+                scores
+                    .iter()
+                    .map(|s| s[class])
+                    .zip(targets.column(class).iter().copied())
+                    .collect(),
+            )
+        })
+        .collect();
+
+    Calibration::Isotonic { curves }
+}
+
+/// Pool-adjacent-violators fit of a non-decreasing curve to `(score, target)` points.
+fn isotonic_regression(mut points: Vec<(f32, f32)>) -> IsotonicCurve {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // (sum of scores, sum of targets, count) of each block
+    let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+
+    for (x, y) in points {
+        match blocks.last_mut() {
+            // equal scores share a block
+            Some(last) if (last.0 / last.2) as f32 == x => {
+                last.0 += x as f64;
+                last.1 += y as f64;
+                last.2 += 1.0;
+            }
+            _ => blocks.push((x as f64, y as f64, 1.0)),
+        }
+
+        while blocks.len() > 1 {
+            let last = blocks[blocks.len() - 1];
+            let previous = blocks[blocks.len() - 2];
+
+            if previous.1 / previous.2 <= last.1 / last.2 {
+                break;
+            }
+
+            blocks.pop();
+            let merged = blocks.last_mut().expect("Blocks are not empty");
+            merged.0 += last.0;
+            merged.1 += last.1;
+            merged.2 += last.2;
+        }
+    }
+
+    IsotonicCurve {
+        x: blocks.iter().map(|b| (b.0 / b.2) as f32).collect(),
+        y: blocks.iter().map(|b| (b.1 / b.2) as f32).collect(),
+    }
+}
+
+/// Golden-section search for the minimum of a unimodal `f` on `[low, high]`.
+fn minimize(f: impl Fn(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+    const INV_PHI: f64 = 0.618_033_988_749_895;
+    const ITERATIONS: usize = 40;
+
+    let mut a = high - INV_PHI * (high - low);
+    let mut b = low + INV_PHI * (high - low);
+    let (mut f_a, mut f_b) = (f(a), f(b));
+
+    for _ in 0..ITERATIONS {
+        if f_a < f_b {
+            high = b;
+            b = a;
+            f_b = f_a;
+            a = high - INV_PHI * (high - low);
+            f_a = f(a);
+        } else {
+            low = a;
+            a = b;
+            f_a = f_b;
+            b = low + INV_PHI * (high - low);
+            f_b = f(b);
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::config::ModelPath;
+    use ndarray::array;
+
+    fn test_config() -> EncoderfileConfig {
+        EncoderfileConfig {
+            name: "my-model".into(),
+            version: "0.0.1".into(),
+            path: ModelPath::Directory("../models/sequence_classification".into()),
+            model_type: ModelType::SequenceClassification,
+            output_path: None,
+            cache_dir: None,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            validate_transform: false,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        }
+    }
+
+    fn write_examples(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "encoderfile-calibration-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dev.jsonl");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const EXAMPLES: &str = r#"{"text": "This is great!", "label": "good"}
+{"text": "This is terrible.", "label": "bad"}
+
+{"text": "I loved every minute of it.", "label": "good"}
+{"text": "A waste of time.", "label": "bad"}
+"#;
+
+    #[test]
+    fn test_fit_calibration() {
+        let path = write_examples("fit", EXAMPLES);
+
+        for method in [
+            CalibrationMethod::Temperature,
+            CalibrationMethod::PerClassTemperature,
+            CalibrationMethod::Platt,
+            CalibrationMethod::Isotonic,
+        ] {
+            let fit = fit_calibration(&test_config(), &path, method).expect("Failed to fit");
+
+            assert_eq!(fit.num_examples, 4);
+            assert!(fit.calibration.validate(2).is_ok());
+        }
+
+        let fit = fit_calibration(&test_config(), &path, CalibrationMethod::Temperature).unwrap();
+        assert!(fit.calibrated_log_loss <= fit.log_loss + 1e-6);
+    }
+
+    #[test]
+    fn test_fit_calibration_invalid_data() {
+        let path = write_examples("unknown", "{\"text\": \"Meh.\", \"label\": \"neutral\"}\n");
+        assert!(fit_calibration(&test_config(), &path, CalibrationMethod::Temperature).is_err());
+
+        let path = write_examples("empty", "\n");
+        assert!(fit_calibration(&test_config(), &path, CalibrationMethod::Temperature).is_err());
+
+        let mut config = test_config();
+        config.model_type = ModelType::TokenClassification;
+        let path = write_examples("model_type", EXAMPLES);
+        assert!(fit_calibration(&config, &path, CalibrationMethod::Temperature).is_err());
+    }
+
+    #[test]
+    fn test_fit_temperature() {
+        // confident logits that are right only half the time call for a high temperature
+        let logits = array![[4.0, -4.0], [-4.0, 4.0], [4.0, -4.0], [-4.0, 4.0]];
+        let targets = array![[1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [1.0, 0.0]];
+
+        let temperature =
+            fit_temperature(&logits, &targets, ProblemType::SingleLabelClassification);
+
+        assert!(temperature > 10.0);
+    }
+
+    #[test]
+    fn test_isotonic_regression() {
+        let curve = isotonic_regression(vec![
+            (0.1, 0.0),
+            (0.4, 1.0),
+            (0.3, 0.0),
+            (0.5, 0.0),
+            (0.9, 1.0),
+        ]);
+
+        assert!(curve.x.windows(2).all(|w| w[0] <= w[1]));
+        assert!(curve.y.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(curve.y.first(), Some(&0.0));
+        assert_eq!(curve.y.last(), Some(&1.0));
+    }
+}
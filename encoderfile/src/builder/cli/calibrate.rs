@@ -0,0 +1,65 @@
+use crate::builder::{
+    calibration::{CalibrationMethod, fit_calibration},
+    terminal,
+};
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use clap_derive::Args;
+
+#[derive(Debug, Args)]
+pub struct CalibrateArgs {
+    #[arg(short = 'f', help = "Path to config file. Required.")]
+    pub config: PathBuf,
+    #[arg(
+        short = 'd',
+        long = "data",
+        help = "Path to a labelled JSONL dev set, with one `{\"text\": ..., \"label\": ...}` example per line. Required."
+    )]
+    pub data: PathBuf,
+    #[arg(
+        short = 'm',
+        long = "method",
+        help = "Calibration method.",
+        default_value = "temperature"
+    )]
+    pub method: CalibrationMethod,
+    #[arg(
+        short = 'o',
+        long = "output-path",
+        help = "Output path, e.g., `./calibration.json`. Optional"
+    )]
+    pub output_path: Option<PathBuf>,
+}
+
+impl CalibrateArgs {
+    pub fn run(&self) -> Result<()> {
+        terminal::info("Loading config...");
+        let config = crate::builder::config::BuildConfig::load(&self.config)?;
+
+        terminal::info("Fitting calibration...");
+        let fit = fit_calibration(&config.encoderfile, &self.data, self.method)?;
+
+        terminal::info_kv("Examples:", fit.num_examples);
+        terminal::info_kv(
+            "Log loss:",
+            format!("{:.4} -> {:.4}", fit.log_loss, fit.calibrated_log_loss),
+        );
+
+        let output_path = self
+            .output_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("calibration.json"));
+
+        std::fs::write(
+            &output_path,
+            serde_json::to_string_pretty(&fit.calibration)?,
+        )
+        .with_context(|| format!("Failed to write calibration to {:?}", output_path))?;
+
+        terminal::success_kv("Calibration written to", output_path.display());
+
+        Ok(())
+    }
+}
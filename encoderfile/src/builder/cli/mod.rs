@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use clap_derive::{Args, Parser, Subcommand};
 
 mod build;
+mod calibrate;
 pub mod inspect;
 mod runtime;
 
@@ -51,6 +52,8 @@ impl GlobalArguments {
 pub enum Commands {
     #[command(about = "Build an encoderfile.")]
     Build(build::BuildArgs),
+    #[command(about = "Fit the score calibration of a classifier on a labelled dev set.")]
+    Calibrate(calibrate::CalibrateArgs),
     #[command(about = "Get Encoderfile version.")]
     Version(()),
     #[command(subcommand, about = "Manage Encoderfile runtimes.")]
@@ -71,6 +74,7 @@ impl Commands {
     pub fn run(self, global: &GlobalArguments) -> Result<()> {
         match self {
             Self::Build(args) => args.run(global),
+            Self::Calibrate(args) => args.run(),
             Self::Version(_) => {
                 println!("Encoderfile {}", env!("CARGO_PKG_VERSION"));
                 Ok(())
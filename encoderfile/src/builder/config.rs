@@ -1,8 +1,14 @@
-use crate::common::{Config as EmbeddedConfig, LuaLibs, ModelConfig, model_type::ModelType};
+use crate::common::{
+    AggregationStrategy, Calibration, Config as EmbeddedConfig, ImageEmbeddingConfig, InputRole,
+    LabelConfig, LateInteractionConfig, LuaLibs, ModelConfig, ModelInputs, ModelOutputs,
+    MultimodalEmbeddingConfig, ObjectDetectionConfig, PoolingConfig, QuantizationRanges,
+    TruncationMode, model_type::ModelType,
+};
 use anyhow::{Context, Result, bail};
 use schemars::JsonSchema;
 use std::string::String;
 use std::{
+    collections::HashMap,
     fs::File,
     io::{BufReader, Read},
     path::PathBuf,
@@ -17,7 +23,7 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use sha2::{Digest, Sha256};
 
-use super::base_binary::TargetSpec;
+use super::{base_binary::TargetSpec, model::ModelTypeExt as _};
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BuildConfig {
@@ -55,6 +61,52 @@ pub struct EncoderfileConfig {
     #[serde(default = "default_validate_transform")]
     pub validate_transform: bool,
     pub target: Option<TargetSpec>,
+    /// Default entity aggregation strategy (token classification only).
+    pub aggregation_strategy: Option<AggregationStrategy>,
+    /// Default entity groups dropped from aggregated entities (token classification only).
+    pub ignore_labels: Option<Vec<String>>,
+    /// Query/document encoding options (late interaction only).
+    pub late_interaction: Option<LateInteractionConfig>,
+    /// Path to a JSON projection matrix applied to token vectors (late interaction only).
+    pub projection: Option<PathBuf>,
+    /// Output selection and normalization (image embedding only).
+    pub image_embedding: Option<ImageEmbeddingConfig>,
+    /// Score threshold and non-maximum suppression (object detection only).
+    pub object_detection: Option<ObjectDetectionConfig>,
+    /// Image-text logit scale (multimodal embedding only).
+    pub multimodal_embedding: Option<MultimodalEmbeddingConfig>,
+    /// Matryoshka embedding sizes accepted by the `dimensions` request option
+    /// (embedding and sentence embedding only).
+    pub matryoshka_dimensions: Option<Vec<usize>>,
+    /// Ranges of int8/uint8 embeddings (sentence embedding only).
+    pub embedding_quantization: Option<EmbeddingQuantizationConfig>,
+    /// Documents embedded at build time and served by `/search` (sentence embedding only).
+    pub corpus: Option<CorpusConfig>,
+    /// Named prefixes prepended to inputs, selected per request with the `prompt_name`
+    /// option (embedding, sentence embedding and sparse embedding only).
+    pub prompts: Option<HashMap<String, String>>,
+    /// Prompt used when a request sets no `prompt_name`.
+    pub default_prompt_name: Option<String>,
+    /// Pooling of token embeddings (sentence embedding only). Defaults to the model's
+    /// sentence-transformers pooling config, or to mean pooling.
+    pub pooling: Option<PoolingConfig>,
+    /// Handling of inputs longer than the truncation length when a request sets no
+    /// `truncation` option (text models only). Defaults to `truncate`.
+    pub truncation: Option<TruncationMode>,
+    /// Calibration of scores (sequence classification only).
+    pub calibration: Option<CalibrationConfig>,
+    /// Renamed, merged, hidden and described labels, by label of the model (sequence, token,
+    /// image and audio classification only).
+    pub labels: Option<HashMap<String, LabelConfig>>,
+    /// Graph output names by the role inference reads them for, e.g. `last_hidden_state:
+    /// token_embeddings`. Defaults to the graph output named after the role.
+    pub outputs: Option<HashMap<String, String>>,
+    /// Additional graph outputs returned with each result (embedding, sentence embedding,
+    /// sequence classification and token classification only).
+    pub extra_outputs: Option<Vec<String>>,
+    /// Graph input names by the tensor bound to them, e.g. `input_ids: ids` (text models
+    /// only). Defaults to the graph input named after the tensor.
+    pub inputs: Option<HashMap<InputRole, String>>,
 }
 
 impl EncoderfileConfig {
@@ -81,7 +133,212 @@ impl EncoderfileConfig {
 
         let reader = BufReader::new(file);
 
-        serde_json::from_reader(reader).with_context(|| "Failed to deserialize model config")
+        let mut model_config: ModelConfig = serde_json::from_reader(reader)
+            .with_context(|| "Failed to deserialize model config")?;
+
+        if self.aggregation_strategy.is_some() || self.ignore_labels.is_some() {
+            if self.model_type != ModelType::TokenClassification {
+                bail!(
+                    "aggregation_strategy and ignore_labels are only supported for token_classification models"
+                );
+            }
+
+            model_config.aggregation_strategy = self.aggregation_strategy;
+            model_config.ignore_labels = self.ignore_labels.clone();
+        }
+
+        if self.late_interaction.is_some() {
+            if self.model_type != ModelType::LateInteraction {
+                bail!("late_interaction is only supported for late_interaction models");
+            }
+
+            model_config.late_interaction = self.late_interaction.clone();
+        }
+
+        if self.image_embedding.is_some() {
+            if self.model_type != ModelType::ImageEmbedding {
+                bail!("image_embedding is only supported for image_embedding models");
+            }
+
+            model_config.image_embedding = self.image_embedding.clone();
+        }
+
+        if self.object_detection.is_some() {
+            if self.model_type != ModelType::ObjectDetection {
+                bail!("object_detection is only supported for object_detection models");
+            }
+
+            model_config.object_detection = self.object_detection.clone();
+        }
+
+        if self.multimodal_embedding.is_some() {
+            if self.model_type != ModelType::MultimodalEmbedding {
+                bail!("multimodal_embedding is only supported for multimodal_embedding models");
+            }
+
+            model_config.multimodal_embedding = self.multimodal_embedding.clone();
+        }
+
+        if let Some(dimensions) = &self.matryoshka_dimensions {
+            if !matches!(
+                self.model_type,
+                ModelType::Embedding | ModelType::SentenceEmbedding
+            ) {
+                bail!(
+                    "matryoshka_dimensions is only supported for embedding and sentence_embedding models"
+                );
+            }
+
+            if dimensions.is_empty() || dimensions.contains(&0) {
+                bail!("matryoshka_dimensions must be a non-empty list of positive sizes");
+            }
+
+            model_config.matryoshka_dimensions = Some(dimensions.clone());
+        }
+
+        if let Some(quantization) = &self.embedding_quantization {
+            if self.model_type != ModelType::SentenceEmbedding {
+                bail!("embedding_quantization is only supported for sentence_embedding models");
+            }
+
+            let ranges = quantization.ranges()?;
+            if let Err(e) = ranges.validate() {
+                bail!("Invalid embedding_quantization ranges: {e}");
+            }
+
+            model_config.embedding_quantization = Some(ranges);
+        }
+
+        if self.prompts.is_some() || self.default_prompt_name.is_some() {
+            if !matches!(
+                self.model_type,
+                ModelType::Embedding | ModelType::SentenceEmbedding | ModelType::SparseEmbedding
+            ) {
+                bail!(
+                    "prompts are only supported for embedding, sentence_embedding and sparse_embedding models"
+                );
+            }
+
+            if let Some(name) = &self.default_prompt_name
+                && !self.prompts.as_ref().is_some_and(|p| p.contains_key(name))
+            {
+                bail!("default_prompt_name {:?} is not one of the prompts", name);
+            }
+
+            model_config.prompts = self.prompts.clone();
+            model_config.default_prompt_name = self.default_prompt_name.clone();
+        }
+
+        match (&self.pooling, &self.path) {
+            (Some(pooling), _) => {
+                if self.model_type != ModelType::SentenceEmbedding {
+                    bail!("pooling is only supported for sentence_embedding models");
+                }
+
+                model_config.pooling = Some(pooling.clone());
+            }
+            (None, ModelPath::Directory(dir))
+                if self.model_type == ModelType::SentenceEmbedding =>
+            {
+                model_config.pooling = super::pooling::sentence_transformers_pooling(dir)?;
+            }
+            (None, _) => {}
+        }
+
+        if self.truncation.is_some() {
+            if self.model_type.input_type() != crate::runtime::Input::Text {
+                bail!("truncation is only supported for text models");
+            }
+
+            model_config.truncation = self.truncation;
+        }
+
+        if let Some(calibration) = &self.calibration {
+            if self.model_type != ModelType::SequenceClassification {
+                bail!("calibration is only supported for sequence_classification models");
+            }
+
+            let calibration = calibration.calibration()?;
+            let num_labels = model_config
+                .num_labels()
+                .ok_or_else(|| anyhow::anyhow!("Model config does not define its labels"))?;
+
+            if let Err(e) = calibration.validate(num_labels) {
+                bail!("Invalid calibration: {e}");
+            }
+
+            model_config.calibration = Some(calibration);
+        }
+
+        if let Some(labels) = &self.labels {
+            if !matches!(
+                self.model_type,
+                ModelType::SequenceClassification
+                    | ModelType::TokenClassification
+                    | ModelType::ImageClassification
+                    | ModelType::AudioClassification
+            ) {
+                bail!("labels are only supported for classification models");
+            }
+
+            model_config.label_overrides = Some(labels.clone());
+
+            if let Err(e) = crate::runtime::ClassifierState::from_model_config(&model_config) {
+                bail!("Invalid labels: {e}");
+            }
+        }
+
+        if self.outputs.is_some() || self.extra_outputs.is_some() {
+            let outputs = ModelOutputs {
+                names: self.outputs.clone().unwrap_or_default(),
+                extra: self.extra_outputs.clone().unwrap_or_default(),
+            };
+
+            if let Some(role) = outputs
+                .names
+                .keys()
+                .find(|role| !self.model_type.output_roles().contains(&role.as_str()))
+            {
+                bail!(
+                    "{:?} is not an output of {} models, expected one of {:?}",
+                    role,
+                    self.model_type,
+                    self.model_type.output_roles()
+                );
+            }
+
+            if !outputs.extra.is_empty()
+                && !matches!(
+                    self.model_type,
+                    ModelType::Embedding
+                        | ModelType::SentenceEmbedding
+                        | ModelType::SequenceClassification
+                        | ModelType::TokenClassification
+                )
+            {
+                bail!(
+                    "extra_outputs are only supported for embedding, sentence_embedding, sequence_classification and token_classification models"
+                );
+            }
+
+            model_config.outputs = Some(outputs);
+        }
+
+        if let Some(inputs) = &self.inputs {
+            if !matches!(
+                self.model_type.input_type(),
+                crate::runtime::Input::Text | crate::runtime::Input::Multimodal
+            ) {
+                bail!("inputs are only supported for text and multimodal models");
+            }
+
+            // input names are checked against the graph when the model is validated
+            model_config.inputs = Some(ModelInputs {
+                names: inputs.clone(),
+            });
+        }
+
+        Ok(model_config)
     }
 
     pub fn output_path(&self) -> PathBuf {
@@ -125,6 +382,106 @@ impl EncoderfileConfig {
     }
 }
 
+/// Ranges mapped onto the 256 buckets of int8 and uint8 embeddings. Set either
+/// `calibration_path` or `range`; defaults to a fixed `[-1.0, 1.0]` range.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EmbeddingQuantizationConfig {
+    /// JSON file of per-dimension `min` and `max` values computed from calibration embeddings.
+    pub calibration_path: Option<PathBuf>,
+    /// Fixed `[min, max]` range applied to every dimension.
+    pub range: Option<(f32, f32)>,
+}
+
+impl EmbeddingQuantizationConfig {
+    pub fn ranges(&self) -> Result<QuantizationRanges> {
+        match (&self.calibration_path, self.range) {
+            (Some(_), Some(_)) => {
+                bail!("Set either calibration_path or range in embedding_quantization, not both")
+            }
+            (Some(path), None) => {
+                let file = File::open(path).with_context(|| {
+                    format!("Failed to open calibration file at {}", path.display())
+                })?;
+
+                serde_json::from_reader(BufReader::new(file))
+                    .with_context(|| "Failed to deserialize calibration ranges")
+            }
+            (None, Some((min, max))) => Ok(QuantizationRanges {
+                min: vec![min],
+                max: vec![max],
+            }),
+            (None, None) => Ok(QuantizationRanges::default()),
+        }
+    }
+}
+
+/// Calibration parameters, either inline or in a JSON file written by `encoderfile calibrate`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum CalibrationConfig {
+    Path { path: PathBuf },
+    Inline(Calibration),
+}
+
+impl CalibrationConfig {
+    pub fn calibration(&self) -> Result<Calibration> {
+        match self {
+            Self::Path { path } => {
+                let file = File::open(path).with_context(|| {
+                    format!("Failed to open calibration file at {}", path.display())
+                })?;
+
+                serde_json::from_reader(BufReader::new(file))
+                    .with_context(|| "Failed to deserialize calibration")
+            }
+            Self::Inline(calibration) => Ok(calibration.clone()),
+        }
+    }
+}
+
+/// Searchable documents baked into the encoderfile.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CorpusConfig {
+    /// JSONL file with one `{"id": ..., "text": ..., "metadata": {...}}` document per line.
+    pub path: PathBuf,
+    /// Exact (`flat`) or approximate (`hnsw`) nearest neighbor search. Defaults to `flat`.
+    #[serde(default)]
+    pub index: CorpusIndexType,
+    /// HNSW graph parameters (`hnsw` index only).
+    pub hnsw: Option<HnswConfig>,
+    /// Prompt prepended to documents when embedding them. Defaults to `default_prompt_name`.
+    pub prompt_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CorpusIndexType {
+    #[default]
+    Flat,
+    Hnsw,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HnswConfig {
+    /// Links per document and layer (twice as many on the bottom layer).
+    pub m: usize,
+    /// Size of the candidate list while building the graph.
+    pub ef_construction: usize,
+    /// Size of the candidate list at query time.
+    pub ef_search: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TokenizerBuildConfig {
     pub pad_strategy: Option<TokenizerPadStrategy>,
@@ -269,6 +626,7 @@ pub enum ModelPath {
         tokenizer_path: PathBuf,
         tokenizer_config_path: Option<PathBuf>,
         preprocessor_config_path: Option<PathBuf>,
+        vision_model_weights_path: Option<PathBuf>,
     },
 }
 
@@ -329,9 +687,10 @@ macro_rules! asset_path {
 impl ModelPath {
     asset_path!(model_config_path, "config.json", "model config");
     asset_path!(tokenizer_path, "tokenizer.json", "tokenizer");
-    asset_path!(@Optional preprocessor_config_path, "preprocessor_config.json", "image preprocessing");
+    asset_path!(@Optional preprocessor_config_path, "preprocessor_config.json", "preprocessor config");
     asset_path!(model_weights_path, "model.onnx", "model weights");
     asset_path!(@Optional tokenizer_config_path, "tokenizer_config.json", "tokenizer config");
+    asset_path!(@Optional vision_model_weights_path, "vision_model.onnx", "vision model weights");
 }
 
 pub fn default_version() -> String {
@@ -345,6 +704,7 @@ pub fn default_validate_transform() -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{ImageEmbeddingOutput, PoolingMode, ProblemType};
     use std::{fs, path::PathBuf};
 
     // Create a stable, normal directory under the system temp dir
@@ -417,6 +777,7 @@ mod tests {
             model_weights_path: base.join("model.onnx"),
             tokenizer_config_path: Some(base.join("tokenizer_config.json")),
             preprocessor_config_path: None,
+            vision_model_weights_path: None,
         };
 
         assert!(mp.model_config_path().is_ok());
@@ -433,6 +794,7 @@ mod tests {
             model_weights_path: base.join("model.onnx"),
             tokenizer_config_path: None,
             preprocessor_config_path: Some(base.join("preprocessor_config.json")),
+            vision_model_weights_path: None,
         };
 
         assert!(mp.model_config_path().is_ok());
@@ -487,6 +849,25 @@ mod tests {
             tokenizer: None,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let generated = cfg.get_generated_dir();
@@ -495,6 +876,688 @@ mod tests {
         cleanup(&base);
     }
 
+    #[test]
+    fn test_aggregation_strategy_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/token_classification".into()),
+            model_type: ModelType::TokenClassification,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: Some(AggregationStrategy::First),
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let model_config = cfg.model_config().unwrap();
+        assert_eq!(
+            model_config.aggregation_strategy,
+            Some(AggregationStrategy::First)
+        );
+
+        cfg.path = ModelPath::Directory("../models/embedding".into());
+        cfg.model_type = ModelType::Embedding;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_image_embedding_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/image_classification".into()),
+            model_type: ModelType::ImageEmbedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: Some(ImageEmbeddingConfig {
+                output: Some(ImageEmbeddingOutput::ClsToken),
+                normalize: false,
+            }),
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let model_config = cfg.model_config().unwrap();
+        let image_embedding = model_config.image_embedding.unwrap();
+        assert_eq!(image_embedding.output, Some(ImageEmbeddingOutput::ClsToken));
+        assert!(!image_embedding.normalize);
+
+        cfg.model_type = ModelType::ImageClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_object_detection_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/image_classification".into()),
+            model_type: ModelType::ObjectDetection,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: Some(ObjectDetectionConfig {
+                threshold: 0.9,
+                nms_iou_threshold: Some(0.5),
+            }),
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let model_config = cfg.model_config().unwrap();
+        let object_detection = model_config.object_detection.unwrap();
+        assert_eq!(object_detection.threshold, 0.9);
+        assert_eq!(object_detection.nms_iou_threshold, Some(0.5));
+
+        cfg.model_type = ModelType::ImageClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_matryoshka_dimensions_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/sentence_embedding".into()),
+            model_type: ModelType::SentenceEmbedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: Some(vec![64, 128, 256]),
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let model_config = cfg.model_config().unwrap();
+        assert_eq!(model_config.matryoshka_dimensions, Some(vec![64, 128, 256]));
+
+        cfg.matryoshka_dimensions = Some(vec![0, 128]);
+        assert!(cfg.model_config().is_err());
+
+        cfg.matryoshka_dimensions = Some(vec![128]);
+        cfg.model_type = ModelType::SequenceClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_prompts_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/sentence_embedding".into()),
+            model_type: ModelType::SentenceEmbedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: Some(HashMap::from([
+                ("query".to_string(), "query: ".to_string()),
+                ("passage".to_string(), "passage: ".to_string()),
+            ])),
+            default_prompt_name: Some("query".into()),
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let model_config = cfg.model_config().unwrap();
+        assert_eq!(model_config.prompt(None), Ok(Some("query: ")));
+        assert_eq!(model_config.prompt(Some("passage")), Ok(Some("passage: ")));
+
+        cfg.default_prompt_name = Some("document".into());
+        assert!(cfg.model_config().is_err());
+
+        cfg.default_prompt_name = None;
+        cfg.model_type = ModelType::SequenceClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_pooling_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/sentence_embedding".into()),
+            model_type: ModelType::SentenceEmbedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        // no sentence-transformers pooling config
+        assert_eq!(cfg.model_config().unwrap().pooling, None);
+
+        let pooling = PoolingConfig {
+            mode: PoolingMode::Cls,
+            normalize: true,
+        };
+        cfg.pooling = Some(pooling.clone());
+        assert_eq!(cfg.model_config().unwrap().pooling, Some(pooling));
+
+        cfg.model_type = ModelType::Embedding;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_truncation_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/embedding".into()),
+            model_type: ModelType::SequenceClassification,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: Some(TruncationMode::Error),
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        assert_eq!(
+            cfg.model_config().unwrap().truncation,
+            Some(TruncationMode::Error)
+        );
+
+        cfg.model_type = ModelType::ImageClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_calibration_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/sequence_classification".into()),
+            model_type: ModelType::SequenceClassification,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: Some(CalibrationConfig::Inline(Calibration::Temperature {
+                temperature: 1.5,
+            })),
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        assert_eq!(
+            cfg.model_config().unwrap().calibration,
+            Some(Calibration::Temperature { temperature: 1.5 })
+        );
+
+        let calibration_path = create_test_dir("classifier_calibration").join("calibration.json");
+        fs::write(
+            &calibration_path,
+            r#"{"method": "platt", "slopes": [1.0, 0.5], "intercepts": [0.0, -0.1]}"#,
+        )
+        .unwrap();
+        cfg.calibration = Some(CalibrationConfig::Path {
+            path: calibration_path,
+        });
+        assert!(matches!(
+            cfg.model_config().unwrap().calibration,
+            Some(Calibration::Platt { .. })
+        ));
+
+        // the model has two labels
+        cfg.calibration = Some(CalibrationConfig::Inline(
+            Calibration::PerClassTemperature {
+                temperatures: vec![1.0, 2.0, 3.0],
+            },
+        ));
+        assert!(cfg.model_config().is_err());
+
+        cfg.calibration = Some(CalibrationConfig::Inline(Calibration::Temperature {
+            temperature: 0.0,
+        }));
+        assert!(cfg.model_config().is_err());
+
+        cfg.calibration = Some(CalibrationConfig::Inline(Calibration::Temperature {
+            temperature: 1.5,
+        }));
+        cfg.model_type = ModelType::TokenClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_labels_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/sequence_classification".into()),
+            model_type: ModelType::SequenceClassification,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: Some(HashMap::from([
+                (
+                    "good".to_string(),
+                    LabelConfig {
+                        name: Some("positive".to_string()),
+                        description: Some("A favorable review.".to_string()),
+                        hidden: false,
+                        threshold: None,
+                    },
+                ),
+                (
+                    "bad".to_string(),
+                    LabelConfig {
+                        hidden: true,
+                        ..Default::default()
+                    },
+                ),
+            ])),
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        };
+
+        let state =
+            crate::runtime::ClassifierState::from_model_config(&cfg.model_config().unwrap())
+                .unwrap();
+        assert_eq!(state.labels_by_id(), vec!["positive".to_string()]);
+        assert_eq!(state.label_map, Some(vec![Some(0), None]));
+        assert_eq!(
+            state.label_descriptions,
+            Some(HashMap::from([(
+                "positive".to_string(),
+                "A favorable review.".to_string()
+            )]))
+        );
+
+        // merging both labels leaves one label, which is still a classifier
+        cfg.labels = Some(HashMap::from([(
+            "bad".to_string(),
+            LabelConfig {
+                name: Some("good".to_string()),
+                ..Default::default()
+            },
+        )]));
+        let state =
+            crate::runtime::ClassifierState::from_model_config(&cfg.model_config().unwrap())
+                .unwrap();
+        assert_eq!(state.label_map, Some(vec![Some(0), Some(0)]));
+        assert_eq!(state.problem_type(), ProblemType::SingleLabelClassification);
+
+        cfg.labels = Some(HashMap::from([(
+            "neutral".to_string(),
+            LabelConfig::default(),
+        )]));
+        assert!(cfg.model_config().is_err());
+
+        cfg.labels = Some(HashMap::from([
+            (
+                "good".to_string(),
+                LabelConfig {
+                    hidden: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "bad".to_string(),
+                LabelConfig {
+                    hidden: true,
+                    ..Default::default()
+                },
+            ),
+        ]));
+        assert!(cfg.model_config().is_err());
+
+        // thresholds apply to multi-label models only
+        let threshold = |threshold| LabelConfig {
+            name: Some("any".to_string()),
+            threshold,
+            ..Default::default()
+        };
+        let labels = HashMap::from([
+            ("good".to_string(), threshold(Some(0.3))),
+            ("bad".to_string(), threshold(None)),
+        ]);
+        cfg.labels = Some(labels.clone());
+        assert!(cfg.model_config().is_err());
+
+        cfg.labels = None;
+        let mut model_config = cfg.model_config().unwrap();
+        model_config.problem_type = Some(ProblemType::MultiLabelClassification);
+        model_config.label_overrides = Some(labels);
+        let state = crate::runtime::ClassifierState::from_model_config(&model_config).unwrap();
+        assert_eq!(state.label_thresholds, Some(vec![Some(0.3)]));
+        assert_eq!(state.label_threshold(0, 0.5), 0.3);
+
+        model_config.label_overrides = Some(HashMap::from([
+            ("good".to_string(), threshold(Some(0.3))),
+            ("bad".to_string(), threshold(Some(0.6))),
+        ]));
+        assert!(crate::runtime::ClassifierState::from_model_config(&model_config).is_err());
+
+        cfg.labels = Some(HashMap::new());
+        cfg.model_type = ModelType::TokenClassification;
+        assert!(cfg.model_config().is_ok());
+
+        cfg.model_type = ModelType::Embedding;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_inputs_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/embedding".into()),
+            model_type: ModelType::Embedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: Some(HashMap::from([(InputRole::InputIds, "ids".to_string())])),
+        };
+
+        let inputs = cfg.model_config().unwrap().inputs.unwrap();
+        assert_eq!(inputs.name(InputRole::InputIds), "ids");
+        assert_eq!(inputs.name(InputRole::PositionIds), "position_ids");
+
+        cfg.model_type = ModelType::ImageClassification;
+        assert!(cfg.model_config().is_err());
+    }
+
+    #[test]
+    fn test_outputs_in_model_config() {
+        let mut cfg = EncoderfileConfig {
+            name: "my-model".into(),
+            version: "1.0".into(),
+            path: ModelPath::Directory("../models/embedding".into()),
+            model_type: ModelType::Embedding,
+            output_path: None,
+            cache_dir: None,
+            validate_transform: false,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: Some(HashMap::from([(
+                "last_hidden_state".to_string(),
+                "token_embeddings".to_string(),
+            )])),
+            extra_outputs: Some(vec!["pooler_output".to_string()]),
+            inputs: None,
+        };
+
+        let outputs = cfg.model_config().unwrap().outputs.unwrap();
+        assert_eq!(outputs.name("last_hidden_state"), "token_embeddings");
+        assert_eq!(outputs.extra, vec!["pooler_output".to_string()]);
+
+        cfg.outputs = Some(HashMap::from([(
+            "logits".to_string(),
+            "scores".to_string(),
+        )]));
+        assert!(cfg.model_config().is_err());
+
+        cfg.outputs = None;
+        cfg.model_type = ModelType::ImageClassification;
+        assert!(cfg.model_config().is_err());
+
+        cfg.model_type = ModelType::Embedding;
+        cfg.extra_outputs = None;
+        assert!(cfg.model_config().unwrap().outputs.is_none());
+    }
+
+    #[test]
+    fn test_embedding_quantization_ranges() {
+        let calibration_path = create_test_dir("calibration").join("calibration.json");
+        fs::write(
+            &calibration_path,
+            r#"{"min": [-0.5, 0.0], "max": [0.5, 1.0]}"#,
+        )
+        .unwrap();
+
+        let config = EmbeddingQuantizationConfig {
+            calibration_path: Some(calibration_path.clone()),
+            range: None,
+        };
+        assert_eq!(config.ranges().unwrap().max, vec![0.5, 1.0]);
+
+        let config = EmbeddingQuantizationConfig {
+            calibration_path: None,
+            range: Some((-0.25, 0.25)),
+        };
+        assert_eq!(config.ranges().unwrap().min, vec![-0.25]);
+
+        let config = EmbeddingQuantizationConfig {
+            calibration_path: Some(calibration_path),
+            range: Some((-0.25, 0.25)),
+        };
+        assert!(config.ranges().is_err());
+    }
+
     #[test]
     fn test_config_loading() {
         let dir = create_test_dir("config");
@@ -0,0 +1,285 @@
+use std::{collections::HashSet, path::Path};
+
+use crate::format::assets::{AssetKind, AssetSource, PlannedAsset};
+use anyhow::{Context, Result, bail};
+use ndarray::{Array2, Axis};
+use parking_lot::Mutex;
+
+use super::{
+    config::{CorpusIndexType, EncoderfileConfig},
+    tokenizer::tokenizer_service,
+};
+use crate::{
+    common::{
+        Corpus, CorpusDocument, CorpusIndex, InferenceOptions, ModelConfig, TruncationMode,
+        model_type::ModelType,
+    },
+    inference::{self, inputs::InputBinder},
+    runtime::ORTSessionBuilder,
+    transforms::{SentenceEmbeddingTransform, convert_libs},
+};
+
+const BATCH_SIZE: usize = 32;
+
+pub fn validate_corpus<'a>(
+    encoderfile_config: &'a EncoderfileConfig,
+) -> Result<Option<PlannedAsset<'a>>> {
+    let corpus_config = match &encoderfile_config.corpus {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    if encoderfile_config.model_type != ModelType::SentenceEmbedding {
+        bail!("corpus is only supported for sentence_embedding models");
+    }
+
+    if corpus_config.hnsw.is_some() && corpus_config.index != CorpusIndexType::Hnsw {
+        bail!("corpus.hnsw is only supported for the hnsw index");
+    }
+
+    let model_config = encoderfile_config.model_config()?;
+    let prompt = model_config
+        .prompt(corpus_config.prompt_name.as_deref())
+        .map_err(|e| anyhow::anyhow!("Invalid corpus.prompt_name: {e}"))?;
+
+    let documents = read_documents(&corpus_config.path)?;
+    let embeddings = embed_documents(encoderfile_config, &model_config, &documents, prompt)?;
+
+    let index = match corpus_config.index {
+        CorpusIndexType::Flat => CorpusIndex::Flat,
+        CorpusIndexType::Hnsw => {
+            let hnsw = corpus_config.hnsw.clone().unwrap_or_default();
+
+            if hnsw.m == 0 || hnsw.ef_construction == 0 || hnsw.ef_search == 0 {
+                bail!("corpus.hnsw parameters must be greater than 0");
+            }
+
+            CorpusIndex::Hnsw(inference::corpus::build_hnsw(
+                &embeddings,
+                hnsw.m,
+                hnsw.ef_construction,
+                hnsw.ef_search,
+            ))
+        }
+    };
+
+    let corpus = Corpus {
+        documents,
+        embeddings: embeddings.outer_iter().map(|row| row.to_vec()).collect(),
+        index,
+    };
+
+    let serialized = serde_json::to_vec(&corpus)?;
+
+    PlannedAsset::from_asset_source(
+        AssetSource::InMemory(std::borrow::Cow::Owned(serialized)),
+        AssetKind::Corpus,
+    )
+    .map(Some)
+}
+
+fn read_documents(path: &Path) -> Result<Vec<CorpusDocument>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read corpus at {:?}", path))?;
+
+    let mut ids = HashSet::new();
+    let mut documents = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let document: CorpusDocument = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse corpus document on line {}", i + 1))?;
+
+        if !ids.insert(document.id.clone()) {
+            bail!(
+                "Duplicate corpus document id {:?} on line {}",
+                document.id,
+                i + 1
+            );
+        }
+
+        documents.push(document);
+    }
+
+    if documents.is_empty() {
+        bail!("Corpus at {:?} has no documents", path);
+    }
+
+    Ok(documents)
+}
+
+/// L2-normalized embeddings of every document, computed with the model being packaged.
+fn embed_documents(
+    encoderfile_config: &EncoderfileConfig,
+    model_config: &ModelConfig,
+    documents: &[CorpusDocument],
+    prompt: Option<&str>,
+) -> Result<Array2<f32>> {
+    let session =
+        ORTSessionBuilder::default().from_file(encoderfile_config.path.model_weights_path()?)?;
+    let inputs = InputBinder::new(
+        &session.inputs,
+        &model_config.inputs.clone().unwrap_or_default(),
+    )?;
+    let session = Mutex::new(session);
+    let tokenizer = tokenizer_service(encoderfile_config)?;
+    let transform = SentenceEmbeddingTransform::new(
+        convert_libs(encoderfile_config.lua_libs()?.as_ref()),
+        encoderfile_config.transform()?,
+    )?;
+
+    let options = InferenceOptions {
+        normalize: Some(true),
+        ..Default::default()
+    };
+    let model_outputs = model_config.outputs.clone().unwrap_or_default();
+
+    let batches = documents
+        .chunks(BATCH_SIZE)
+        .map(|batch| {
+            let encodings = tokenizer.encode_text_with_prompt(
+                batch.iter().map(|d| d.text.clone()).collect(),
+                prompt,
+                None,
+                TruncationMode::Truncate,
+            )?;
+
+            inference::sentence_embedding::embeddings(
+                session.lock(),
+                &transform,
+                &model_outputs,
+                &inputs,
+                encodings,
+                &options,
+                model_config.pooling.as_ref(),
+            )
+            .with_context(|| "Failed to embed corpus")
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let views: Vec<_> = batches.iter().map(|batch| batch.view()).collect();
+
+    Ok(ndarray::concatenate(Axis(0), &views)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::config::{CorpusConfig, HnswConfig, ModelPath};
+
+    use super::*;
+
+    fn test_config(corpus: &Path, index: CorpusIndexType) -> EncoderfileConfig {
+        EncoderfileConfig {
+            name: "my-model".into(),
+            version: "0.0.1".into(),
+            path: ModelPath::Directory("../models/embedding".into()),
+            model_type: ModelType::SentenceEmbedding,
+            output_path: None,
+            cache_dir: None,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            validate_transform: false,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: Some(CorpusConfig {
+                path: corpus.to_path_buf(),
+                index,
+                hnsw: None,
+                prompt_name: None,
+            }),
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        }
+    }
+
+    fn write_corpus(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "encoderfile-corpus-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("corpus.jsonl");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const CORPUS: &str = r#"{"id": "cats", "text": "Cats are small domesticated felines.", "metadata": {"topic": "animals"}}
+{"id": "rust", "text": "Rust is a systems programming language."}
+
+{"id": "tea", "text": "Green tea is brewed from unoxidized leaves."}
+"#;
+
+    #[test]
+    fn test_read_documents() {
+        let path = write_corpus("read", CORPUS);
+
+        let documents = read_documents(&path).expect("Failed to read corpus");
+
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[1].id, "rust");
+        assert_eq!(
+            documents[0].metadata.as_ref().unwrap().get("topic"),
+            Some(&"animals".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_documents_duplicate_id() {
+        let path = write_corpus(
+            "duplicate",
+            "{\"id\": \"a\", \"text\": \"one\"}\n{\"id\": \"a\", \"text\": \"two\"}\n",
+        );
+
+        assert!(read_documents(&path).is_err());
+    }
+
+    #[test]
+    fn test_validate_corpus() {
+        let path = write_corpus("ok", CORPUS);
+
+        for index in [CorpusIndexType::Flat, CorpusIndexType::Hnsw] {
+            let config = test_config(&path, index);
+            let asset = validate_corpus(&config).expect("Failed to validate");
+
+            assert!(asset.is_some());
+        }
+    }
+
+    #[test]
+    fn test_validate_corpus_invalid_config() {
+        let path = write_corpus("config", CORPUS);
+
+        let mut config = test_config(&path, CorpusIndexType::Flat);
+        config.model_type = ModelType::Embedding;
+        assert!(validate_corpus(&config).is_err());
+
+        let mut config = test_config(&path, CorpusIndexType::Flat);
+        config.corpus.as_mut().unwrap().hnsw = Some(HnswConfig::default());
+        assert!(validate_corpus(&config).is_err());
+
+        let mut config = test_config(&path, CorpusIndexType::Flat);
+        config.corpus.as_mut().unwrap().prompt_name = Some("passage".into());
+        assert!(validate_corpus(&config).is_err());
+    }
+}
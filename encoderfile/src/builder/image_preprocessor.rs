@@ -33,7 +33,13 @@ pub fn validate_image_preprocessor<'a>(
     let serialized = serde_json::to_vec(&config)?;
 
     // num_channels must be same as len for mean and std
-    if let Some(num_channels) = model_config.num_channels {
+    // (dual-tower models nest it under vision_config)
+    let num_channels = model_config.num_channels.or(model_config
+        .vision_config
+        .as_ref()
+        .and_then(|vision_config| vision_config.num_channels));
+
+    if let Some(num_channels) = num_channels {
         if let Some(image_mean) = config.image_mean.as_ref()
             && image_mean.len() != num_channels as usize
         {
@@ -74,6 +80,25 @@ mod tests {
             validate_transform: false,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let preprocessor_config = validate_image_preprocessor(&config)
@@ -1,12 +1,17 @@
+pub mod audio_feature_extractor;
 pub mod base_binary;
 // I'm not playing this game with clippy today -RB
 #[allow(clippy::module_inception)]
 pub mod builder;
 pub mod cache;
+pub mod calibration;
 pub mod cli;
 pub mod config;
+pub mod corpus;
 pub mod image_preprocessor;
 pub mod model;
+pub mod pooling;
+pub mod projection;
 pub mod templates;
 /// Terminal logging utilities.
 pub mod terminal;
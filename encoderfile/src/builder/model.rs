@@ -1,6 +1,8 @@
 use crate::{
+    common::{ModelInputs, ModelOutputs},
     format::assets::{AssetKind, AssetSource, PlannedAsset},
-    runtime::ORTSessionBuilder,
+    inference::inputs::InputBinder,
+    runtime::{Input, ORTSessionBuilder},
 };
 use anyhow::{Result, bail};
 use ort::{
@@ -10,27 +12,112 @@ use ort::{
 use std::path::Path;
 
 pub trait ModelTypeExt {
-    fn validate_model<'a>(&self, path: &'a Path) -> Result<PlannedAsset<'a>>;
+    /// Roles of the graph outputs read at inference time, renamable with `outputs`.
+    fn output_roles(&self) -> &'static [&'static str];
+    fn validate_model<'a>(
+        &self,
+        path: &'a Path,
+        names: &ModelOutputs,
+        inputs: &ModelInputs,
+    ) -> Result<PlannedAsset<'a>>;
+    fn validate_vision_model<'a>(
+        &self,
+        path: &'a Path,
+        names: &ModelOutputs,
+    ) -> Result<PlannedAsset<'a>>;
 }
 
 impl ModelTypeExt for crate::common::model_type::ModelType {
-    fn validate_model<'a>(&self, path: &'a Path) -> Result<PlannedAsset<'a>> {
+    fn output_roles(&self) -> &'static [&'static str] {
+        match self {
+            Self::Embedding | Self::SentenceEmbedding | Self::LateInteraction => {
+                &["last_hidden_state"]
+            }
+            Self::SequenceClassification
+            | Self::TokenClassification
+            | Self::SparseEmbedding
+            | Self::ImageClassification
+            | Self::ImageSegmentation
+            | Self::AudioClassification => &["logits"],
+            Self::ImageEmbedding => &["image_embeds", "pooler_output", "last_hidden_state"],
+            Self::ObjectDetection => &["logits", "pred_boxes"],
+            Self::AudioEmbedding => &["pooler_output", "last_hidden_state"],
+            Self::MultimodalEmbedding => &["text_embeds", "image_embeds"],
+        }
+    }
+
+    fn validate_model<'a>(
+        &self,
+        path: &'a Path,
+        names: &ModelOutputs,
+        inputs: &ModelInputs,
+    ) -> Result<PlannedAsset<'a>> {
         let model = ORTSessionBuilder::default().from_file(path)?;
 
+        // text inputs are bound from the graph signature, so unsupported inputs fail here
+        if matches!(self.input_type(), Input::Text | Input::Multimodal) {
+            InputBinder::new(&model.inputs, inputs)?;
+        }
+
+        validate_extra_outputs(&model, names)?;
+
         match self {
-            Self::Embedding => validate_embedding_model(model),
-            Self::SequenceClassification => validate_sequence_classification_model(model),
-            Self::TokenClassification => validate_token_classification_model(model),
-            Self::SentenceEmbedding => validate_sentence_embedding_model(model),
-            Self::ImageClassification => validate_image_classification_model(model),
+            Self::Embedding => validate_embedding_model(model, names),
+            Self::SequenceClassification => validate_sequence_classification_model(model, names),
+            Self::TokenClassification => validate_token_classification_model(model, names),
+            Self::SentenceEmbedding => validate_sentence_embedding_model(model, names),
+            Self::SparseEmbedding => validate_sparse_embedding_model(model, names),
+            Self::LateInteraction => validate_late_interaction_model(model, names),
+            Self::ImageClassification => validate_image_classification_model(model, names),
+            Self::ImageEmbedding => validate_image_embedding_model(model, names),
+            Self::ObjectDetection => validate_object_detection_model(model, names),
+            Self::ImageSegmentation => validate_image_segmentation_model(model, names),
+            Self::AudioClassification => validate_audio_classification_model(model, names),
+            Self::AudioEmbedding => validate_audio_embedding_model(model, names),
+            Self::MultimodalEmbedding => validate_multimodal_text_model(model, names),
         }?;
 
         PlannedAsset::from_asset_source(AssetSource::File(path), AssetKind::ModelWeights)
     }
+
+    fn validate_vision_model<'a>(
+        &self,
+        path: &'a Path,
+        names: &ModelOutputs,
+    ) -> Result<PlannedAsset<'a>> {
+        let model = ORTSessionBuilder::default().from_file(path)?;
+
+        match self {
+            Self::MultimodalEmbedding => validate_multimodal_vision_model(model, names),
+            _ => bail!("{self} models do not have a vision tower"),
+        }?;
+
+        PlannedAsset::from_asset_source(AssetSource::File(path), AssetKind::VisionModelWeights)
+    }
+}
+
+fn validate_sentence_embedding_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "last_hidden_state")?;
+
+    if shape.len() != 3 {
+        bail!("Model must return tensor of shape [batch_size, seq_len, hidden_dim]")
+    }
+
+    Ok(())
+}
+
+fn validate_sparse_embedding_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
+
+    if shape.len() != 3 {
+        bail!("Model must return tensor of shape [batch_size, seq_len, vocab_size]")
+    }
+
+    Ok(())
 }
 
-fn validate_sentence_embedding_model(model: Session) -> Result<()> {
-    let shape = get_outp_dim(model.outputs.as_slice(), "last_hidden_state")?;
+fn validate_late_interaction_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "last_hidden_state")?;
 
     if shape.len() != 3 {
         bail!("Model must return tensor of shape [batch_size, seq_len, hidden_dim]")
@@ -39,8 +126,8 @@ fn validate_sentence_embedding_model(model: Session) -> Result<()> {
     Ok(())
 }
 
-fn validate_embedding_model(model: Session) -> Result<()> {
-    let shape = get_outp_dim(model.outputs.as_slice(), "last_hidden_state")?;
+fn validate_embedding_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "last_hidden_state")?;
 
     if shape.len() != 3 {
         bail!("Model must return tensor of shape [batch_size, seq_len, hidden_dim]")
@@ -49,8 +136,8 @@ fn validate_embedding_model(model: Session) -> Result<()> {
     Ok(())
 }
 
-fn validate_sequence_classification_model(model: Session) -> Result<()> {
-    let shape = get_outp_dim(model.outputs.as_slice(), "logits")?;
+fn validate_sequence_classification_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
 
     if shape.len() != 2 {
         bail!("Model must return tensor of shape [batch_size, n_labels]")
@@ -59,8 +146,8 @@ fn validate_sequence_classification_model(model: Session) -> Result<()> {
     Ok(())
 }
 
-fn validate_token_classification_model(model: Session) -> Result<()> {
-    let shape = get_outp_dim(model.outputs.as_slice(), "logits")?;
+fn validate_token_classification_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
 
     if shape.len() != 3 {
         bail!("Model must return tensor of shape [batch_size, seq_len, n_labels]")
@@ -69,8 +156,8 @@ fn validate_token_classification_model(model: Session) -> Result<()> {
     Ok(())
 }
 
-fn validate_image_classification_model(model: Session) -> Result<()> {
-    let shape = get_outp_dim(model.outputs.as_slice(), "logits")?;
+fn validate_image_classification_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
 
     if shape.len() != 2 {
         bail!("Model must return tensor of shape [batch_size, n_labels]")
@@ -79,12 +166,146 @@ fn validate_image_classification_model(model: Session) -> Result<()> {
     Ok(())
 }
 
-fn get_outp_dim<'a>(outputs: &'a [Output], outp_name: &str) -> Result<&'a Shape> {
+fn validate_image_embedding_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let outputs = model.outputs.as_slice();
+
+    if let Ok(shape) = get_outp_dim(outputs, names, "image_embeds") {
+        if shape.len() != 2 {
+            bail!("image_embeds must be a tensor of shape [batch_size, dim]")
+        }
+    } else if let Ok(shape) = get_outp_dim(outputs, names, "pooler_output") {
+        if shape.len() != 2 {
+            bail!("pooler_output must be a tensor of shape [batch_size, hidden_dim]")
+        }
+    } else {
+        let shape = get_outp_dim(outputs, names, "last_hidden_state").map_err(|_| {
+            anyhow::anyhow!("Model must return image_embeds, pooler_output or last_hidden_state")
+        })?;
+
+        if shape.len() != 3 {
+            bail!("Model must return tensor of shape [batch_size, seq_len, hidden_dim]")
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_object_detection_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let outputs = model.outputs.as_slice();
+
+    let shape = get_outp_dim(outputs, names, "logits")?;
+
+    if shape.len() != 3 {
+        bail!("Model must return logits of shape [batch_size, num_queries, num_classes]")
+    }
+
+    let shape = get_outp_dim(outputs, names, "pred_boxes")?;
+
+    // box dimension may be dynamic (-1) in some exports
+    if shape.len() != 3 || !matches!(shape[2], 4 | -1) {
+        bail!("Model must return pred_boxes of shape [batch_size, num_queries, 4]")
+    }
+
+    Ok(())
+}
+
+fn validate_image_segmentation_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
+
+    if shape.len() != 4 {
+        bail!("Model must return tensor of shape [batch_size, n_labels, height, width]")
+    }
+
+    Ok(())
+}
+
+fn validate_audio_classification_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "logits")?;
+
+    if shape.len() != 2 {
+        bail!("Model must return tensor of shape [batch_size, n_labels]")
+    }
+
+    Ok(())
+}
+
+fn validate_audio_embedding_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let outputs = model.outputs.as_slice();
+
+    if let Ok(shape) = get_outp_dim(outputs, names, "pooler_output") {
+        if shape.len() != 2 {
+            bail!("pooler_output must be a tensor of shape [batch_size, hidden_dim]")
+        }
+    } else {
+        let shape = get_outp_dim(outputs, names, "last_hidden_state")
+            .map_err(|_| anyhow::anyhow!("Model must return pooler_output or last_hidden_state"))?;
+
+        if shape.len() != 3 {
+            bail!("Model must return tensor of shape [batch_size, n_frames, hidden_dim]")
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_multimodal_text_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "text_embeds")?;
+
+    if shape.len() != 2 {
+        bail!("Text tower must return text_embeds of shape [batch_size, dim]")
+    }
+
+    Ok(())
+}
+
+fn validate_multimodal_vision_model(model: Session, names: &ModelOutputs) -> Result<()> {
+    let shape = get_outp_dim(model.outputs.as_slice(), names, "image_embeds")?;
+
+    if shape.len() != 2 {
+        bail!("Vision tower must return image_embeds of shape [batch_size, dim]")
+    }
+
+    Ok(())
+}
+
+fn get_outp_dim<'a>(outputs: &'a [Output], names: &ModelOutputs, role: &str) -> Result<&'a Shape> {
+    let outp_name = names.name(role);
+
     outputs
         .iter()
         .find(|i| i.name == outp_name)
-        .ok_or(anyhow::anyhow!(format!("Model must return {}", outp_name)))?
+        .ok_or_else(|| match outp_name == role {
+            true => anyhow::anyhow!("Model must return {}", role),
+            false => anyhow::anyhow!("Model must return {} (configured as {})", outp_name, role),
+        })?
         .output_type
         .tensor_shape()
         .ok_or(anyhow::anyhow!("Model must return tensor"))
 }
+
+/// Checks that the model returns every configured extra output as a tensor.
+fn validate_extra_outputs(model: &Session, names: &ModelOutputs) -> Result<()> {
+    for name in names.extra.iter() {
+        let output = model
+            .outputs
+            .iter()
+            .find(|i| &i.name == name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Model does not return extra output {:?}, expected one of {:?}",
+                    name,
+                    model
+                        .outputs
+                        .iter()
+                        .map(|o| o.name.as_str())
+                        .collect::<Vec<_>>()
+                )
+            })?;
+
+        if output.output_type.tensor_shape().is_none() {
+            bail!("Extra output {:?} must be a tensor", name);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::common::{PoolingConfig, PoolingMode};
+
+const DEFAULT_POOLING_DIR: &str = "1_Pooling";
+
+/// An entry of sentence-transformers' `modules.json`.
+#[derive(Debug, Deserialize)]
+struct Module {
+    path: String,
+    #[serde(rename = "type")]
+    module_type: String,
+}
+
+/// sentence-transformers' `1_Pooling/config.json`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SentenceTransformersPooling {
+    pooling_mode_cls_token: bool,
+    pooling_mode_mean_tokens: bool,
+    pooling_mode_max_tokens: bool,
+    pooling_mode_mean_sqrt_len_tokens: bool,
+    pooling_mode_weightedmean_tokens: bool,
+    pooling_mode_lasttoken: bool,
+}
+
+/// Pooling of a sentence-transformers model directory, read from its pooling module config
+/// and `modules.json`. `None` if the directory has no pooling module.
+pub fn sentence_transformers_pooling(dir: &Path) -> Result<Option<PoolingConfig>> {
+    let modules_path = dir.join("modules.json");
+    let modules: Vec<Module> = match modules_path.try_exists()? {
+        true => serde_json::from_str(&std::fs::read_to_string(&modules_path)?)
+            .with_context(|| format!("Failed to parse {:?}", modules_path))?,
+        false => Vec::new(),
+    };
+
+    let pooling_dir = modules
+        .iter()
+        .find(|m| m.module_type.ends_with(".Pooling"))
+        .map_or(DEFAULT_POOLING_DIR, |m| m.path.as_str());
+
+    let config_path = dir.join(pooling_dir).join("config.json");
+    if !config_path.try_exists()? {
+        return Ok(None);
+    }
+
+    let config: SentenceTransformersPooling =
+        serde_json::from_str(&std::fs::read_to_string(&config_path)?)
+            .with_context(|| format!("Failed to parse {:?}", config_path))?;
+
+    let modes: Vec<PoolingMode> = [
+        (config.pooling_mode_mean_tokens, PoolingMode::Mean),
+        (config.pooling_mode_cls_token, PoolingMode::Cls),
+        (config.pooling_mode_max_tokens, PoolingMode::Max),
+        (config.pooling_mode_lasttoken, PoolingMode::LastToken),
+        (
+            config.pooling_mode_weightedmean_tokens,
+            PoolingMode::WeightedMean,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, mode)| enabled.then_some(mode))
+    .collect();
+
+    if config.pooling_mode_mean_sqrt_len_tokens {
+        bail!("mean_sqrt_len_tokens pooling is not supported, set pooling explicitly");
+    }
+
+    let mode = match modes.as_slice() {
+        [mode] => *mode,
+        [] => bail!("{:?} enables no pooling mode", config_path),
+        _ => bail!(
+            "{:?} enables several pooling modes, which is not supported",
+            config_path
+        ),
+    };
+
+    Ok(Some(PoolingConfig {
+        mode,
+        normalize: modules
+            .iter()
+            .any(|m| m.module_type.ends_with(".Normalize")),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_model_dir(name: &str, pooling: &str, modules: Option<&str>) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "encoderfile-pooling-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(dir.join("1_Pooling")).unwrap();
+        std::fs::write(dir.join("1_Pooling").join("config.json"), pooling).unwrap();
+        if let Some(modules) = modules {
+            std::fs::write(dir.join("modules.json"), modules).unwrap();
+        }
+        dir
+    }
+
+    const MODULES: &str = r#"[
+        {"idx": 0, "name": "0", "path": "", "type": "sentence_transformers.models.Transformer"},
+        {"idx": 1, "name": "1", "path": "1_Pooling", "type": "sentence_transformers.models.Pooling"},
+        {"idx": 2, "name": "2", "path": "2_Normalize", "type": "sentence_transformers.models.Normalize"}
+    ]"#;
+
+    #[test]
+    fn test_sentence_transformers_pooling() {
+        let dir = write_model_dir(
+            "cls",
+            r#"{"word_embedding_dimension": 384, "pooling_mode_cls_token": true, "pooling_mode_mean_tokens": false}"#,
+            Some(MODULES),
+        );
+
+        let pooling = sentence_transformers_pooling(&dir).unwrap();
+
+        assert_eq!(
+            pooling,
+            Some(PoolingConfig {
+                mode: PoolingMode::Cls,
+                normalize: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_sentence_transformers_pooling_without_modules() {
+        let dir = write_model_dir("mean", r#"{"pooling_mode_mean_tokens": true}"#, None);
+
+        let pooling = sentence_transformers_pooling(&dir).unwrap();
+
+        assert_eq!(pooling, Some(PoolingConfig::default()));
+    }
+
+    #[test]
+    fn test_sentence_transformers_pooling_unsupported() {
+        let dir = write_model_dir(
+            "combined",
+            r#"{"pooling_mode_mean_tokens": true, "pooling_mode_max_tokens": true}"#,
+            None,
+        );
+        assert!(sentence_transformers_pooling(&dir).is_err());
+
+        let dir = write_model_dir(
+            "sqrt",
+            r#"{"pooling_mode_mean_sqrt_len_tokens": true}"#,
+            None,
+        );
+        assert!(sentence_transformers_pooling(&dir).is_err());
+    }
+
+    #[test]
+    fn test_no_pooling_module() {
+        let dir = std::path::PathBuf::from("../models/embedding");
+
+        assert_eq!(sentence_transformers_pooling(&dir).unwrap(), None);
+    }
+}
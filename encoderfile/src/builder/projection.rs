@@ -0,0 +1,119 @@
+use crate::format::assets::{AssetKind, AssetSource, PlannedAsset};
+use anyhow::{Context, Result, bail};
+
+use super::config::EncoderfileConfig;
+use crate::{
+    common::{Projection, model_type::ModelType},
+    runtime::ProjectionState,
+};
+
+pub fn validate_projection<'a>(
+    encoderfile_config: &'a EncoderfileConfig,
+) -> Result<Option<PlannedAsset<'a>>> {
+    let projection_path = match &encoderfile_config.projection {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    if encoderfile_config.model_type != ModelType::LateInteraction {
+        bail!("projection is only supported for late_interaction models");
+    }
+
+    let contents = std::fs::read_to_string(projection_path)
+        .with_context(|| format!("Failed to read projection at {:?}", projection_path))?;
+    let projection: Projection =
+        serde_json::from_str(contents.as_str()).with_context(|| "Failed to parse projection")?;
+
+    // check shapes
+    ProjectionState::try_from(projection.clone())?;
+
+    let serialized = serde_json::to_vec(&projection)?;
+
+    PlannedAsset::from_asset_source(
+        AssetSource::InMemory(std::borrow::Cow::Owned(serialized)),
+        AssetKind::Projection,
+    )
+    .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::config::ModelPath;
+
+    use super::*;
+
+    fn test_config(projection: &std::path::Path) -> EncoderfileConfig {
+        EncoderfileConfig {
+            name: "my-model".into(),
+            version: "0.0.1".into(),
+            path: ModelPath::Directory("../models/embedding".into()),
+            model_type: ModelType::LateInteraction,
+            output_path: None,
+            cache_dir: None,
+            transform: None,
+            lua_libs: None,
+            tokenizer: None,
+            validate_transform: false,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: Some(projection.to_path_buf()),
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        }
+    }
+
+    fn write_projection(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "encoderfile-projection-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("projection.json");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_validate_projection() {
+        let path = write_projection("ok", r#"{"weight": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]}"#);
+
+        let config = test_config(&path);
+        let asset = validate_projection(&config).expect("Failed to validate");
+
+        assert!(asset.is_some());
+    }
+
+    #[test]
+    fn test_validate_projection_ragged() {
+        let path = write_projection("ragged", r#"{"weight": [[1.0, 0.0, 0.0], [0.0, 1.0]]}"#);
+
+        assert!(validate_projection(&test_config(&path)).is_err());
+    }
+
+    #[test]
+    fn test_validate_projection_wrong_model_type() {
+        let path = write_projection("model-type", r#"{"weight": [[1.0]]}"#);
+
+        let mut config = test_config(&path);
+        config.model_type = ModelType::Embedding;
+
+        assert!(validate_projection(&config).is_err());
+    }
+}
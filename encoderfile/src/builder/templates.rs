@@ -10,4 +10,12 @@ pub mod transforms {
     embed_transform_template!(SEQUENCE_CLASSIFICATION, "sequence_classification");
     embed_transform_template!(TOKEN_CLASSIFICATION, "token_classification");
     embed_transform_template!(SENTENCE_EMBEDDING, "sentence_embedding");
+    embed_transform_template!(SPARSE_EMBEDDING, "sparse_embedding");
+    embed_transform_template!(LATE_INTERACTION, "late_interaction");
+    embed_transform_template!(IMAGE_EMBEDDING, "image_embedding");
+    embed_transform_template!(OBJECT_DETECTION, "object_detection");
+    embed_transform_template!(IMAGE_SEGMENTATION, "image_segmentation");
+    embed_transform_template!(AUDIO_CLASSIFICATION, "audio_classification");
+    embed_transform_template!(AUDIO_EMBEDDING, "audio_embedding");
+    embed_transform_template!(MULTIMODAL_EMBEDDING, "multimodal_embedding");
 }
@@ -38,6 +38,18 @@ use super::config::{
 };
 
 pub fn validate_tokenizer<'a>(config: &'a EncoderfileConfig) -> Result<PlannedAsset<'a>> {
+    let service = tokenizer_service(config)?;
+
+    let serialized = serde_json::to_vec(&service)?;
+
+    PlannedAsset::from_asset_source(
+        AssetSource::InMemory(std::borrow::Cow::Owned(serialized)),
+        AssetKind::Tokenizer,
+    )
+}
+
+/// Tokenizer as it runs in the encoderfile.
+pub fn tokenizer_service(config: &EncoderfileConfig) -> Result<TokenizerService> {
     let tokenizer =
         match Tokenizer::from_str(std::fs::read_to_string(config.path.tokenizer_path()?)?.as_str())
         {
@@ -47,14 +59,7 @@ pub fn validate_tokenizer<'a>(config: &'a EncoderfileConfig) -> Result<PlannedAs
 
     let config = config.validate_tokenizer_config(&tokenizer)?;
 
-    let service = TokenizerService::new(tokenizer, config)?;
-
-    let serialized = serde_json::to_vec(&service)?;
-
-    PlannedAsset::from_asset_source(
-        AssetSource::InMemory(std::borrow::Cow::Owned(serialized)),
-        AssetKind::Tokenizer,
-    )
+    TokenizerService::new(tokenizer, config)
 }
 
 impl EncoderfileConfig {
@@ -372,6 +377,25 @@ mod tests {
             validate_transform: false,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let tokenizer = load_tokenizer_from_path(
@@ -418,6 +442,25 @@ mod tests {
             validate_transform: false,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let tokenizer = load_tokenizer_from_path(
@@ -453,6 +496,7 @@ mod tests {
             tokenizer_path: path.tokenizer_path().unwrap(),
             tokenizer_config_path: None,
             preprocessor_config_path: None,
+            vision_model_weights_path: None,
         };
 
         let config = EncoderfileConfig {
@@ -468,6 +512,25 @@ mod tests {
             validate_transform: false,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let tokenizer = load_tokenizer_from_path(
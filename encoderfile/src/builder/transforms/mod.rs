@@ -11,6 +11,14 @@ pub fn new_transform(model_type: String) -> Result<()> {
         "sequence_classification" => transforms::SEQUENCE_CLASSIFICATION,
         "token_classification" => transforms::TOKEN_CLASSIFICATION,
         "sentence_embedding" => transforms::SENTENCE_EMBEDDING,
+        "sparse_embedding" => transforms::SPARSE_EMBEDDING,
+        "late_interaction" => transforms::LATE_INTERACTION,
+        "image_embedding" => transforms::IMAGE_EMBEDDING,
+        "object_detection" => transforms::OBJECT_DETECTION,
+        "image_segmentation" => transforms::IMAGE_SEGMENTATION,
+        "audio_classification" => transforms::AUDIO_CLASSIFICATION,
+        "audio_embedding" => transforms::AUDIO_EMBEDDING,
+        "multimodal_embedding" => transforms::MULTIMODAL_EMBEDDING,
         _ => bail!("Unknown model type: {}", model_type),
     };
 
@@ -0,0 +1,162 @@
+use super::{
+    TransformValidatorExt,
+    utils::{BATCH_SIZE, HIDDEN_DIM, random_tensor, validation_err, validation_err_ctx},
+};
+use crate::{
+    common::ModelConfig,
+    transforms::{ImageEmbeddingTransform, Postprocessor},
+};
+use anyhow::{Context, Result};
+
+impl TransformValidatorExt for ImageEmbeddingTransform {
+    fn dry_run(&self, _model_config: &ModelConfig) -> Result<()> {
+        // create dummy image embeddings with shape [batch_size, hidden_dim]
+        let dummy_embeddings = random_tensor(&[BATCH_SIZE, HIDDEN_DIM], (-1.0, 1.0))?;
+        let shape = dummy_embeddings.shape().to_owned();
+
+        let res = self.postprocess(dummy_embeddings)
+            .with_context(|| {
+                validation_err_ctx(
+                    format!(
+                        "Failed to run postprocessing on dummy image embeddings (randomly generated in range -1.0..1.0) of shape {:?}",
+                        shape.as_slice(),
+                    )
+                )
+            })?;
+
+        // result must return tensor of rank 2
+        if res.ndim() != 2 {
+            validation_err(format!(
+                "Transform must return tensor of rank 2. Got tensor of shape {:?}.",
+                res.shape()
+            ))?
+        }
+
+        // result must have same batch_size
+        if res.shape()[0] != BATCH_SIZE {
+            validation_err(format!(
+                "Transform must preserve batch size [{}, *]. Got shape {:?}",
+                BATCH_SIZE,
+                res.shape()
+            ))?
+        }
+
+        if res.shape()[1] < 1 {
+            validation_err(format!(
+                "Transform returned a tensor with last dimension 0. Shape: {:?}",
+                res.shape()
+            ))?
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::config::{EncoderfileConfig, ModelPath};
+    use crate::common::model_type::ModelType;
+    use crate::transforms::DEFAULT_LIBS;
+
+    use super::*;
+
+    fn test_encoderfile_config() -> EncoderfileConfig {
+        EncoderfileConfig {
+            name: "my-model".to_string(),
+            version: "0.0.1".to_string(),
+            path: ModelPath::Directory(std::path::PathBuf::from("models/image_classification")),
+            model_type: ModelType::ImageEmbedding,
+            cache_dir: None,
+            output_path: None,
+            transform: None,
+            lua_libs: None,
+            validate_transform: true,
+            tokenizer: None,
+            base_binary_path: None,
+            target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
+        }
+    }
+
+    fn test_model_config() -> ModelConfig {
+        let config_json = include_str!("../../../../../models/image_classification/config.json");
+
+        serde_json::from_str(config_json).unwrap()
+    }
+
+    #[test]
+    fn test_identity_validation() {
+        let encoderfile_config = test_encoderfile_config();
+        let model_config = test_model_config();
+
+        ImageEmbeddingTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("function Postprocess(arr) return arr end".to_string()),
+        )
+        .expect("Failed to create transform")
+        .validate(&encoderfile_config, &model_config)
+        .expect("Failed to validate");
+    }
+
+    #[test]
+    fn test_truncate_validation() {
+        let encoderfile_config = test_encoderfile_config();
+        let model_config = test_model_config();
+
+        ImageEmbeddingTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("function Postprocess(arr) return arr:truncate_axis(2, 64) end".to_string()),
+        )
+        .expect("Failed to create transform")
+        .validate(&encoderfile_config, &model_config)
+        .expect("Failed to validate");
+    }
+
+    #[test]
+    fn test_bad_return_type() {
+        let encoderfile_config = test_encoderfile_config();
+        let model_config = test_model_config();
+
+        let result = ImageEmbeddingTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("function Postprocess(arr) return 1 end".to_string()),
+        )
+        .expect("Failed to create transform")
+        .validate(&encoderfile_config, &model_config);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bad_dimensionality() {
+        let encoderfile_config = test_encoderfile_config();
+        let model_config = test_model_config();
+
+        let result = ImageEmbeddingTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("function Postprocess(arr) return arr:sum_axis(1) end".to_string()),
+        )
+        .expect("Failed to create transform")
+        .validate(&encoderfile_config, &model_config);
+
+        assert!(result.is_err());
+    }
+}
@@ -9,10 +9,18 @@ use anyhow::{Context, Result};
 use crate::builder::config::EncoderfileConfig;
 use prost::Message;
 
+mod audio_classification;
+mod audio_embedding;
 mod embedding;
 mod image_classification;
+mod image_embedding;
+mod image_segmentation;
+mod late_interaction;
+mod multimodal_embedding;
+mod object_detection;
 mod sentence_embedding;
 mod sequence_classification;
+mod sparse_embedding;
 mod token_classification;
 mod utils;
 
@@ -90,12 +98,60 @@ pub fn validate_transform<'a>(
             encoderfile_config,
             model_config
         ),
+        ModelType::SparseEmbedding => validate_transform!(
+            SparseEmbeddingTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::LateInteraction => validate_transform!(
+            LateInteractionTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
         ModelType::ImageClassification => validate_transform!(
             ImageClassificationTransform,
             transform_str,
             encoderfile_config,
             model_config
         ),
+        ModelType::ImageEmbedding => validate_transform!(
+            ImageEmbeddingTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::ObjectDetection => validate_transform!(
+            ObjectDetectionTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::ImageSegmentation => validate_transform!(
+            ImageSegmentationTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::AudioClassification => validate_transform!(
+            AudioClassificationTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::AudioEmbedding => validate_transform!(
+            AudioEmbeddingTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
+        ModelType::MultimodalEmbedding => validate_transform!(
+            MultimodalEmbeddingTransform,
+            transform_str,
+            encoderfile_config,
+            model_config
+        ),
     }?;
 
     let lua_libs: Option<ManifestLuaLibs> = encoderfile_config
@@ -137,6 +193,25 @@ mod tests {
             tokenizer: None,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         }
     }
 
@@ -183,6 +258,25 @@ mod tests {
             tokenizer: None,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let model_config_str = include_str!(concat!(
@@ -212,6 +306,25 @@ mod tests {
             tokenizer: None,
             base_binary_path: None,
             target: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            projection: None,
+            image_embedding: None,
+            object_detection: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            corpus: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            labels: None,
+            outputs: None,
+            extra_outputs: None,
+            inputs: None,
         };
 
         let model_config_str = include_str!(concat!(
@@ -0,0 +1,73 @@
+use crate::common::FromReadInput;
+use crate::common::SequenceClassificationResult;
+use crate::common::audio_types::AudioInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioClassificationRequest {
+    pub audios: Vec<AudioInfo>,
+    /// Score above which a label is predicted (multi-label models only), for labels without
+    /// a build-time threshold. Defaults to 0.5.
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for AudioClassificationRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            audios: inputs.into_iter().map(AudioInfo::from_path).collect(),
+            threshold: None,
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+impl FromReadInput for AudioClassificationRequest {
+    fn from_read_input(input: Vec<&mut impl Read>) -> Result<Self> {
+        let audios = input
+            .into_iter()
+            .map(AudioInfo::from_reader)
+            .collect::<Result<Vec<AudioInfo>>>()?;
+
+        Ok(Self {
+            audios,
+            threshold: None,
+            metadata: Some(HashMap::default()),
+        })
+    }
+}
+
+/// Results share the shape of sequence classification results: logits, scores and the
+/// predicted label(s) according to the model's `problem_type`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct AudioClassificationResponse {
+    pub results: Vec<SequenceClassificationResult>,
+    pub model_id: String,
+    /// The threshold applied to the scores of labels without a build-time threshold
+    /// (multi-label models only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_classification_request_from_read_input() {
+        let mut first = std::io::Cursor::new(b"RIFF0000WAVE".to_vec());
+        let mut second = std::io::Cursor::new(b"fLaC".to_vec());
+        let request = AudioClassificationRequest::from_read_input(vec![&mut first, &mut second])
+            .expect("Failed to create request from read input");
+
+        assert_eq!(request.audios.len(), 2);
+        assert_eq!(request.audios[0].audio_bytes.as_ref(), b"RIFF0000WAVE");
+        assert_eq!(request.threshold, None);
+    }
+}
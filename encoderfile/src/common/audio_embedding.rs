@@ -0,0 +1,48 @@
+use crate::common::FromReadInput;
+use crate::common::audio_types::AudioInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioEmbeddingRequest {
+    pub audios: Vec<AudioInfo>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for AudioEmbeddingRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            audios: inputs.into_iter().map(AudioInfo::from_path).collect(),
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+impl FromReadInput for AudioEmbeddingRequest {
+    fn from_read_input(input: Vec<&mut impl Read>) -> Result<Self> {
+        let audios = input
+            .into_iter()
+            .map(AudioInfo::from_reader)
+            .collect::<Result<Vec<AudioInfo>>>()?;
+
+        Ok(Self {
+            audios,
+            metadata: Some(HashMap::default()),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct AudioEmbeddingResponse {
+    pub results: Vec<AudioEmbedding>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct AudioEmbedding {
+    pub embedding: Vec<f32>,
+}
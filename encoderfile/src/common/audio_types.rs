@@ -0,0 +1,27 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// Encoded audio clip (WAV, FLAC or OGG/Vorbis). Decoded and resampled at inference time.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioInfo {
+    pub audio_bytes: Bytes,
+}
+
+impl AudioInfo {
+    pub(crate) fn from_path(path: String) -> Self {
+        let audio_data = std::fs::read(path).expect("Failed to read audio file");
+        AudioInfo {
+            audio_bytes: Bytes::from(audio_data),
+        }
+    }
+
+    pub(crate) fn from_reader(reader: &mut impl std::io::Read) -> anyhow::Result<Self> {
+        let mut audio_data = Vec::new();
+        reader
+            .read_to_end(&mut audio_data)
+            .map_err(|e| anyhow::anyhow!("Failed to read audio data: {}", e))?;
+        Ok(AudioInfo {
+            audio_bytes: Bytes::from(audio_data),
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// A document of the corpus, as read from one line of the corpus JSONL file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusDocument {
+    pub id: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Documents embedded at build time, with their L2-normalized embeddings and search index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corpus {
+    pub documents: Vec<CorpusDocument>,
+    /// `[num_documents, dim]`, in document order.
+    pub embeddings: Vec<Vec<f32>>,
+    pub index: CorpusIndex,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CorpusIndex {
+    /// Exact search over every document.
+    Flat,
+    /// Approximate search over a hierarchical navigable small world graph.
+    Hnsw(HnswIndex),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HnswIndex {
+    /// Size of the candidate list at query time.
+    pub ef_search: usize,
+    /// Document the search starts from, on the top layer.
+    pub entry_point: usize,
+    /// Neighbors of each document on each of its layers, bottom layer first.
+    pub neighbors: Vec<Vec<Vec<usize>>>,
+}
+
+/// Top-`top_k` documents of the corpus most similar to `query`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SearchRequest {
+    pub query: String,
+    /// Number of documents returned. Defaults to `5`.
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct SearchResponse {
+    /// Matching documents, most similar first.
+    pub results: Vec<SearchResult>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SearchResult {
+    pub id: String,
+    pub text: String,
+    /// Cosine similarity of the document to the query.
+    pub score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+pub const DEFAULT_SEARCH_TOP_K: usize = 5;
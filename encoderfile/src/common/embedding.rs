@@ -6,6 +6,9 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct EmbeddingRequest {
     pub inputs: Vec<String>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
     #[serde(default)]
     pub metadata: Option<HashMap<String, String>>,
 }
@@ -14,6 +17,7 @@ impl super::FromCliInput for EmbeddingRequest {
     fn from_cli_input(inputs: Vec<String>) -> Self {
         Self {
             inputs,
+            options: Default::default(),
             metadata: Some(HashMap::default()),
         }
     }
@@ -30,6 +34,13 @@ pub struct EmbeddingResponse {
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct TokenEmbeddingSequence {
     pub embeddings: Vec<TokenEmbedding>,
+    /// Number of tokens the model saw, special tokens included.
+    pub num_tokens: usize,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+    /// Extra model outputs configured at build time, by output name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, super::OutputTensor>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
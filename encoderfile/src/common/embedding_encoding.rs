@@ -0,0 +1,141 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::ToSchema;
+
+/// Encoding of returned embeddings.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EncodingFormat {
+    /// 32-bit floats.
+    #[default]
+    Float,
+    /// Signed 8-bit integers, one per dimension.
+    Int8,
+    /// Unsigned 8-bit integers, one per dimension.
+    Uint8,
+    /// Signs of each dimension packed into signed bytes, 8 dimensions per byte.
+    Binary,
+    /// Signs of each dimension packed into unsigned bytes, 8 dimensions per byte.
+    Ubinary,
+}
+
+/// Values of an encoded embedding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(untagged)]
+pub enum EmbeddingValues {
+    Float(Vec<f32>),
+    Int8(Vec<i8>),
+    Uint8(Vec<u8>),
+    /// Raw bytes of any encoding format (little-endian for floats), serialized as base64.
+    #[schema(value_type = String)]
+    Base64(
+        #[serde(
+            serialize_with = "serialize_base64",
+            deserialize_with = "deserialize_base64"
+        )]
+        #[schemars(with = "String")]
+        Vec<u8>,
+    ),
+}
+
+impl EmbeddingValues {
+    /// The float values, if the embedding is float-encoded.
+    pub fn as_float(&self) -> Option<&[f32]> {
+        match self {
+            Self::Float(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// Value ranges mapped onto the 256 buckets of int8 and uint8 embeddings, set at build time.
+/// Ranges are either per dimension, computed from calibration embeddings, or a single range
+/// applied to every dimension.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuantizationRanges {
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+}
+
+impl Default for QuantizationRanges {
+    /// Fixed `[-1.0, 1.0]` scaling, which covers L2-normalized embeddings.
+    fn default() -> Self {
+        Self {
+            min: vec![-1.0],
+            max: vec![1.0],
+        }
+    }
+}
+
+impl QuantizationRanges {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.min.is_empty() || self.min.len() != self.max.len() {
+            return Err("min and max must be non-empty and of the same length");
+        }
+
+        if self.min.iter().zip(&self.max).any(|(min, max)| min >= max) {
+            return Err("each min must be less than its max");
+        }
+
+        Ok(())
+    }
+
+    /// The range of dimension `i`.
+    pub fn range(&self, i: usize) -> Option<(f32, f32)> {
+        match self.min.len() {
+            1 => Some((self.min[0], self.max[0])),
+            _ => Some((*self.min.get(i)?, *self.max.get(i)?)),
+        }
+    }
+}
+
+fn serialize_base64<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
+
+fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_values_serialization() {
+        let values = EmbeddingValues::Base64(b"foo".to_vec());
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, r#""Zm9v""#);
+        assert_eq!(
+            serde_json::from_str::<EmbeddingValues>(&json).unwrap(),
+            values
+        );
+
+        let values: EmbeddingValues = serde_json::from_str("[0.5, -1.0]").unwrap();
+        assert_eq!(values.as_float(), Some(&[0.5, -1.0][..]));
+    }
+
+    #[test]
+    fn test_quantization_ranges() {
+        let fixed = QuantizationRanges::default();
+        assert!(fixed.validate().is_ok());
+        assert_eq!(fixed.range(100), Some((-1.0, 1.0)));
+
+        let calibrated = QuantizationRanges {
+            min: vec![-0.5, 0.0],
+            max: vec![0.5, 0.0],
+        };
+        assert!(calibrated.validate().is_err());
+
+        let calibrated = QuantizationRanges {
+            min: vec![-0.5, 0.0],
+            max: vec![0.5, 2.0],
+        };
+        assert_eq!(calibrated.range(1), Some((0.0, 2.0)));
+        assert_eq!(calibrated.range(2), None);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::common::FromReadInput;
+use crate::common::image_types::ImageInfo;
+use anyhow::Result;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageEmbeddingRequest {
+    pub images: Vec<ImageInfo>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for ImageEmbeddingRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        let images = inputs
+            .into_iter()
+            .map(|path| {
+                let image_data = std::fs::read(path).expect("Failed to read image file");
+                let format =
+                    image::guess_format(&image_data).expect("Failed to guess image format");
+                ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                }
+            })
+            .collect();
+
+        Self {
+            images,
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+impl FromReadInput for ImageEmbeddingRequest {
+    fn from_read_input(input: Vec<&mut impl Read>) -> Result<Self> {
+        let images = input
+            .into_iter()
+            .map(|reader| {
+                let mut image_data = Vec::new();
+                reader
+                    .read_to_end(&mut image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to read image data: {}", e))?;
+                let format = image::guess_format(&image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to guess image format: {}", e))?;
+                Ok(ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                })
+            })
+            .collect::<Result<Vec<ImageInfo>>>()?;
+
+        Ok(Self {
+            images,
+            metadata: Some(HashMap::default()),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct ImageEmbeddingResponse {
+    pub results: Vec<ImageEmbedding>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ImageEmbedding {
+    pub embedding: Vec<f32>,
+}
+
+/// Model output an image embedding is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageEmbeddingOutput {
+    /// `image_embeds` of shape `[batch_size, dim]`, e.g. CLIP vision models with projection.
+    ImageEmbeds,
+    /// `pooler_output` of shape `[batch_size, hidden_dim]`.
+    PoolerOutput,
+    /// First (CLS) token of `last_hidden_state`, e.g. DINOv2.
+    ClsToken,
+}
+
+impl ImageEmbeddingOutput {
+    /// Order in which outputs are tried when none is configured.
+    pub const FALLBACK_ORDER: [ImageEmbeddingOutput; 3] = [
+        ImageEmbeddingOutput::ImageEmbeds,
+        ImageEmbeddingOutput::PoolerOutput,
+        ImageEmbeddingOutput::ClsToken,
+    ];
+
+    pub fn output_name(&self) -> &'static str {
+        match self {
+            Self::ImageEmbeds => "image_embeds",
+            Self::PoolerOutput => "pooler_output",
+            Self::ClsToken => "last_hidden_state",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageEmbeddingConfig {
+    /// Model output to embed from. Defaults to the first of `image_embeds`,
+    /// `pooler_output` or `last_hidden_state` returned by the model.
+    #[serde(default)]
+    pub output: Option<ImageEmbeddingOutput>,
+    /// L2-normalize embeddings. Defaults to `true`.
+    #[serde(default = "default_normalize")]
+    pub normalize: bool,
+}
+
+impl Default for ImageEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            output: None,
+            normalize: default_normalize(),
+        }
+    }
+}
+
+fn default_normalize() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+    use std::fs::File;
+
+    #[test]
+    fn test_image_embedding_request_from_read_input() {
+        let mut file =
+            File::open("../test-pictures/yoga01.jpg").expect("Failed to open test image");
+        let file_vec = vec![&mut file];
+        let request = ImageEmbeddingRequest::from_read_input(file_vec)
+            .expect("Failed to create request from read input");
+
+        assert_eq!(request.images.len(), 1);
+        assert_eq!(request.images[0].image_format, ImageFormat::Jpeg);
+        assert!(!request.images[0].image_bytes.is_empty());
+    }
+
+    #[test]
+    fn test_image_embedding_config_defaults() {
+        let config: ImageEmbeddingConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(config.output, None);
+        assert!(config.normalize);
+
+        let config: ImageEmbeddingConfig =
+            serde_json::from_str(r#"{"output": "cls_token", "normalize": false}"#).unwrap();
+
+        assert_eq!(config.output, Some(ImageEmbeddingOutput::ClsToken));
+        assert!(!config.normalize);
+    }
+}
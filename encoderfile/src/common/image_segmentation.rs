@@ -0,0 +1,155 @@
+use crate::common::FromReadInput;
+use crate::common::image_types::ImageInfo;
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageSegmentationRequest {
+    pub images: Vec<ImageInfo>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for ImageSegmentationRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        let images = inputs
+            .into_iter()
+            .map(|path| {
+                let image_data = std::fs::read(path).expect("Failed to read image file");
+                let format =
+                    image::guess_format(&image_data).expect("Failed to guess image format");
+                ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                }
+            })
+            .collect();
+
+        Self {
+            images,
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+impl FromReadInput for ImageSegmentationRequest {
+    fn from_read_input(input: Vec<&mut impl Read>) -> Result<Self> {
+        let images = input
+            .into_iter()
+            .map(|reader| {
+                let mut image_data = Vec::new();
+                reader
+                    .read_to_end(&mut image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to read image data: {}", e))?;
+                let format = image::guess_format(&image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to guess image format: {}", e))?;
+                Ok(ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                })
+            })
+            .collect::<Result<Vec<ImageInfo>>>()?;
+
+        Ok(Self {
+            images,
+            metadata: Some(HashMap::default()),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct ImageSegmentationResponse {
+    pub results: Vec<ImageSegmentationResult>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Segments of a single image, one per label present in the image.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ImageSegmentationResult {
+    pub segments: Vec<ImageSegment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq)]
+pub struct ImageSegment {
+    pub label: String,
+    /// Mean probability of `label` over the pixels of the mask.
+    pub score: f32,
+    pub mask: SegmentMask,
+}
+
+/// Binary mask at the resolution of the original image, run-length encoded in row-major
+/// order. `counts` alternates between runs of unset and set pixels, starting with unset.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq, Eq)]
+pub struct SegmentMask {
+    pub width: u32,
+    pub height: u32,
+    pub counts: Vec<u32>,
+}
+
+impl SegmentMask {
+    /// Encodes `width * height` pixels given in row-major order.
+    pub fn from_pixels(width: u32, height: u32, pixels: impl IntoIterator<Item = bool>) -> Self {
+        let mut counts = Vec::new();
+        let mut current = false;
+        let mut run = 0;
+
+        for pixel in pixels {
+            if pixel != current {
+                counts.push(run);
+                current = pixel;
+                run = 0;
+            }
+            run += 1;
+        }
+        counts.push(run);
+
+        Self {
+            width,
+            height,
+            counts,
+        }
+    }
+
+    /// Decodes the mask into a grayscale image, set pixels being white.
+    pub fn to_image(&self) -> image::GrayImage {
+        let mut pixels = Vec::with_capacity((self.width * self.height) as usize);
+
+        for (i, &run) in self.counts.iter().enumerate() {
+            let value = if i % 2 == 0 { 0 } else { u8::MAX };
+            pixels.extend(std::iter::repeat_n(value, run as usize));
+        }
+
+        image::GrayImage::from_raw(self.width, self.height, pixels)
+            .expect("Mask run lengths do not match its size")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_mask_round_trip() {
+        let pixels = [true, true, false, false, false, true];
+
+        let mask = SegmentMask::from_pixels(3, 2, pixels);
+
+        assert_eq!(mask.counts, vec![0, 2, 3, 1]);
+
+        let image = mask.to_image();
+        let decoded: Vec<bool> = image.pixels().map(|p| p.0[0] > 0).collect();
+        assert_eq!(decoded, pixels);
+    }
+
+    #[test]
+    fn test_segment_mask_empty() {
+        let mask = SegmentMask::from_pixels(2, 2, [false; 4]);
+
+        assert_eq!(mask.counts, vec![4]);
+        assert_eq!(mask.to_image().len(), 4);
+    }
+}
@@ -0,0 +1,498 @@
+use super::{EncodingFormat, model_type::ModelType};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Per-request inference options. Unset options fall back to the defaults reported by
+/// `GET /model`; options the model type does not support are rejected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct InferenceOptions {
+    /// `true` truncates inputs to the tokenizer's `max_length`, and a number truncates inputs
+    /// to that many tokens, at most `max_length`. `false` rejects inputs longer than
+    /// `max_length` instead of truncating them, like `truncation: error`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncate: Option<Truncate>,
+    /// L2-normalize embeddings. Defaults to `true` when `dimensions` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize: Option<bool>,
+    /// Return the `top_k` highest-scoring labels of each input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<usize>,
+    /// Return raw logits alongside scores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_logits: Option<bool>,
+    /// Keep special tokens such as `[CLS]` and `[SEP]` in token outputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_special_tokens: Option<bool>,
+    /// Return the token, token id and offsets of each token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_offsets: Option<bool>,
+    /// Keep only the first `dimensions` values of each embedding (Matryoshka truncation).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+    /// Return embeddings as floats, int8, uint8 or bit-packed binary/ubinary values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<EncodingFormat>,
+    /// Return the raw bytes of each embedding as a base64 string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base64: Option<bool>,
+    /// Name of the build-time prompt prepended to each input. Defaults to the model's
+    /// default prompt, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_name: Option<String>,
+    /// What happens to inputs longer than the truncation length. Defaults to the model's
+    /// build-time `truncation`, or `truncate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<TruncationMode>,
+    /// Unit of the returned token and entity offsets: UTF-8 bytes, Unicode characters or
+    /// UTF-16 code units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_mapping: Option<OffsetMapping>,
+}
+
+/// Truncation of over-length inputs: enabled, or a maximum number of tokens. Inference requests
+/// disabling truncation reject over-length inputs, as the model cannot take inputs past its
+/// `max_length`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(untagged)]
+pub enum Truncate {
+    Enabled(bool),
+    MaxLength(usize),
+}
+
+/// Handling of inputs longer than the truncation length.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationMode {
+    /// Drop the tokens past the truncation length.
+    #[default]
+    Truncate,
+    /// Reject the request.
+    Error,
+}
+
+/// Unit of token and entity offsets into the input text.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetMapping {
+    /// UTF-8 bytes, e.g. for slicing Rust or Go strings.
+    #[default]
+    Byte,
+    /// Unicode scalar values, e.g. for slicing Python strings.
+    Char,
+    /// UTF-16 code units, e.g. for slicing JavaScript or Java strings.
+    Utf16,
+}
+
+impl OffsetMapping {
+    /// Converter of byte offsets into `text` to this unit.
+    pub fn converter(&self, text: &str) -> OffsetConverter {
+        let len = match self {
+            Self::Byte => return OffsetConverter { offsets: None },
+            Self::Char => |_: char| 1,
+            Self::Utf16 => char::len_utf16,
+        };
+
+        let mut offsets = vec![0; text.len() + 1];
+        let mut offset = 0;
+
+        for (i, c) in text.char_indices() {
+            offsets[i] = offset;
+            offset += len(c);
+            offsets[i + 1..=i + c.len_utf8()].fill(offset);
+        }
+
+        OffsetConverter {
+            offsets: Some(offsets),
+        }
+    }
+}
+
+/// Converts byte offsets into a text to an [`OffsetMapping`], from the converted offset of
+/// each byte of the text.
+#[derive(Debug, Clone)]
+pub struct OffsetConverter {
+    offsets: Option<Vec<usize>>,
+}
+
+impl OffsetConverter {
+    /// Converts a byte `offset`. Offsets inside a multi-byte character round up to the end of
+    /// that character.
+    pub fn convert(&self, offset: usize) -> usize {
+        match &self.offsets {
+            None => offset,
+            Some(offsets) => offsets
+                .get(offset)
+                .or(offsets.last())
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferenceOption {
+    Truncate,
+    Normalize,
+    TopK,
+    ReturnLogits,
+    IncludeSpecialTokens,
+    ReturnOffsets,
+    Dimensions,
+    EncodingFormat,
+    Base64,
+    PromptName,
+    Truncation,
+    OffsetMapping,
+}
+
+impl InferenceOption {
+    fn unsupported_error(&self) -> &'static str {
+        match self {
+            Self::Truncate => "truncate is not supported by this model type",
+            Self::Normalize => "normalize is not supported by this model type",
+            Self::TopK => "top_k is not supported by this model type",
+            Self::ReturnLogits => "return_logits is not supported by this model type",
+            Self::IncludeSpecialTokens => {
+                "include_special_tokens is not supported by this model type"
+            }
+            Self::ReturnOffsets => "return_offsets is not supported by this model type",
+            Self::Dimensions => "dimensions is not supported by this model type",
+            Self::EncodingFormat => "encoding_format is not supported by this model type",
+            Self::Base64 => "base64 is not supported by this model type",
+            Self::PromptName => "prompt_name is not supported by this model type",
+            Self::Truncation => "truncation is not supported by this model type",
+            Self::OffsetMapping => "offset_mapping is not supported by this model type",
+        }
+    }
+}
+
+impl ModelType {
+    /// Per-request options accepted by this model type.
+    pub fn supported_options(&self) -> &'static [InferenceOption] {
+        match self {
+            ModelType::Embedding => &[
+                InferenceOption::Truncate,
+                InferenceOption::Normalize,
+                InferenceOption::IncludeSpecialTokens,
+                InferenceOption::ReturnOffsets,
+                InferenceOption::Dimensions,
+                InferenceOption::PromptName,
+                InferenceOption::Truncation,
+                InferenceOption::OffsetMapping,
+            ],
+            ModelType::SentenceEmbedding => &[
+                InferenceOption::Truncate,
+                InferenceOption::Normalize,
+                InferenceOption::Dimensions,
+                InferenceOption::EncodingFormat,
+                InferenceOption::Base64,
+                InferenceOption::PromptName,
+                InferenceOption::Truncation,
+            ],
+            ModelType::SequenceClassification => &[
+                InferenceOption::Truncate,
+                InferenceOption::TopK,
+                InferenceOption::ReturnLogits,
+                InferenceOption::Truncation,
+            ],
+            ModelType::TokenClassification => &[
+                InferenceOption::Truncate,
+                InferenceOption::ReturnLogits,
+                InferenceOption::IncludeSpecialTokens,
+                InferenceOption::Truncation,
+                InferenceOption::OffsetMapping,
+            ],
+            ModelType::SparseEmbedding => &[
+                InferenceOption::Truncate,
+                InferenceOption::PromptName,
+                InferenceOption::Truncation,
+            ],
+            ModelType::LateInteraction => &[InferenceOption::Truncate, InferenceOption::Truncation],
+            ModelType::ImageClassification
+            | ModelType::ImageEmbedding
+            | ModelType::ObjectDetection
+            | ModelType::ImageSegmentation
+            | ModelType::AudioClassification
+            | ModelType::AudioEmbedding
+            | ModelType::MultimodalEmbedding => &[],
+        }
+    }
+}
+
+impl InferenceOptions {
+    /// The options applied when a request leaves them unset, or `None` if the model type
+    /// takes no options. `max_length` is the tokenizer's truncation length.
+    pub fn defaults(model_type: &ModelType, max_length: usize) -> Option<Self> {
+        let supported = model_type.supported_options();
+
+        if supported.is_empty() {
+            return None;
+        }
+
+        let defaults = Self::default();
+        let mut options = Self::default();
+
+        for option in supported {
+            match option {
+                InferenceOption::Truncate => {
+                    options.truncate = Some(Truncate::MaxLength(max_length))
+                }
+                InferenceOption::Normalize => options.normalize = Some(defaults.normalize()),
+                InferenceOption::ReturnLogits => {
+                    options.return_logits = Some(defaults.return_logits())
+                }
+                InferenceOption::IncludeSpecialTokens => {
+                    options.include_special_tokens = Some(defaults.include_special_tokens())
+                }
+                InferenceOption::ReturnOffsets => {
+                    options.return_offsets = Some(defaults.return_offsets())
+                }
+                InferenceOption::EncodingFormat => {
+                    options.encoding_format = Some(defaults.encoding_format())
+                }
+                InferenceOption::Base64 => options.base64 = Some(defaults.base64()),
+                InferenceOption::Truncation => options.truncation = Some(TruncationMode::default()),
+                InferenceOption::OffsetMapping => {
+                    options.offset_mapping = Some(defaults.offset_mapping())
+                }
+                // no default: all labels are scored, embeddings are not truncated and the
+                // default prompt depends on the model
+                InferenceOption::TopK
+                | InferenceOption::Dimensions
+                | InferenceOption::PromptName => {}
+            }
+        }
+
+        Some(options)
+    }
+
+    /// Checks that every option set is supported by `model_type` and in range.
+    pub fn validate(&self, model_type: &ModelType) -> Result<(), &'static str> {
+        let supported = model_type.supported_options();
+
+        if let Some(option) = self
+            .set_options()
+            .into_iter()
+            .find(|option| !supported.contains(option))
+        {
+            return Err(option.unsupported_error());
+        }
+
+        if self.truncate == Some(Truncate::MaxLength(0)) {
+            return Err("truncate must be a bool or a positive number of tokens");
+        }
+
+        if self.top_k == Some(0) {
+            return Err("top_k must be greater than 0");
+        }
+
+        if self.dimensions == Some(0) {
+            return Err("dimensions must be greater than 0");
+        }
+
+        Ok(())
+    }
+
+    /// Checks `dimensions` against the Matryoshka dimensions allowed at build time, if any.
+    pub fn validate_dimensions(&self, allowed: Option<&[usize]>) -> Result<(), &'static str> {
+        match (self.dimensions, allowed) {
+            (Some(dimensions), Some(allowed)) if !allowed.contains(&dimensions) => {
+                Err("dimensions must be one of the model's matryoshka dimensions")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn set_options(&self) -> Vec<InferenceOption> {
+        [
+            (self.truncate.is_some(), InferenceOption::Truncate),
+            (self.normalize.is_some(), InferenceOption::Normalize),
+            (self.top_k.is_some(), InferenceOption::TopK),
+            (self.return_logits.is_some(), InferenceOption::ReturnLogits),
+            (
+                self.include_special_tokens.is_some(),
+                InferenceOption::IncludeSpecialTokens,
+            ),
+            (
+                self.return_offsets.is_some(),
+                InferenceOption::ReturnOffsets,
+            ),
+            (self.dimensions.is_some(), InferenceOption::Dimensions),
+            (
+                self.encoding_format.is_some(),
+                InferenceOption::EncodingFormat,
+            ),
+            (self.base64.is_some(), InferenceOption::Base64),
+            (self.prompt_name.is_some(), InferenceOption::PromptName),
+            (self.truncation.is_some(), InferenceOption::Truncation),
+            (
+                self.offset_mapping.is_some(),
+                InferenceOption::OffsetMapping,
+            ),
+        ]
+        .into_iter()
+        .filter(|(is_set, _)| *is_set)
+        .map(|(_, option)| option)
+        .collect()
+    }
+
+    pub fn normalize(&self) -> bool {
+        // truncated Matryoshka embeddings are re-normalized unless asked otherwise
+        self.normalize.unwrap_or(self.dimensions.is_some())
+    }
+
+    pub fn return_logits(&self) -> bool {
+        self.return_logits.unwrap_or(true)
+    }
+
+    pub fn include_special_tokens(&self) -> bool {
+        self.include_special_tokens.unwrap_or(false)
+    }
+
+    pub fn return_offsets(&self) -> bool {
+        self.return_offsets.unwrap_or(true)
+    }
+
+    pub fn encoding_format(&self) -> EncodingFormat {
+        self.encoding_format.unwrap_or_default()
+    }
+
+    pub fn base64(&self) -> bool {
+        self.base64.unwrap_or(false)
+    }
+
+    pub fn offset_mapping(&self) -> OffsetMapping {
+        self.offset_mapping.unwrap_or_default()
+    }
+
+    /// The requested truncation mode. Disabling truncation rejects over-length inputs.
+    pub fn truncation_mode(&self) -> Option<TruncationMode> {
+        match self.truncate {
+            Some(Truncate::Enabled(false)) => Some(TruncationMode::Error),
+            _ => self.truncation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_deserialization() {
+        let options: InferenceOptions = serde_json::from_str(r#"{"truncate": false}"#).unwrap();
+        assert_eq!(options.truncate, Some(Truncate::Enabled(false)));
+
+        let options: InferenceOptions = serde_json::from_str(r#"{"truncate": 128}"#).unwrap();
+        assert_eq!(options.truncate, Some(Truncate::MaxLength(128)));
+
+        let options: InferenceOptions = serde_json::from_str(r#"{"truncation": "error"}"#).unwrap();
+        assert_eq!(options.truncation, Some(TruncationMode::Error));
+    }
+
+    #[test]
+    fn test_disabled_truncation_rejects_long_inputs() {
+        let options = InferenceOptions {
+            truncate: Some(Truncate::Enabled(false)),
+            truncation: Some(TruncationMode::Truncate),
+            ..Default::default()
+        };
+
+        assert!(options.validate(&ModelType::Embedding).is_ok());
+        assert_eq!(options.truncation_mode(), Some(TruncationMode::Error));
+        assert_eq!(InferenceOptions::default().truncation_mode(), None);
+    }
+
+    #[test]
+    fn test_validate_supported_options() {
+        let options = InferenceOptions {
+            top_k: Some(3),
+            return_logits: Some(false),
+            ..Default::default()
+        };
+
+        assert!(options.validate(&ModelType::SequenceClassification).is_ok());
+        assert_eq!(
+            options.validate(&ModelType::SentenceEmbedding),
+            Err("top_k is not supported by this model type")
+        );
+    }
+
+    #[test]
+    fn test_validate_ranges() {
+        let options = InferenceOptions {
+            dimensions: Some(0),
+            ..Default::default()
+        };
+
+        assert!(options.validate(&ModelType::SentenceEmbedding).is_err());
+
+        let options = InferenceOptions {
+            truncate: Some(Truncate::MaxLength(0)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.validate(&ModelType::Embedding),
+            Err("truncate must be a bool or a positive number of tokens")
+        );
+        assert!(
+            InferenceOptions::default()
+                .validate(&ModelType::ImageEmbedding)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_dimensions() {
+        let options = InferenceOptions {
+            dimensions: Some(256),
+            ..Default::default()
+        };
+
+        assert!(options.validate_dimensions(None).is_ok());
+        assert!(options.validate_dimensions(Some(&[128, 256, 768])).is_ok());
+        assert!(options.validate_dimensions(Some(&[128, 768])).is_err());
+        assert!(options.normalize());
+        assert!(
+            InferenceOptions::default()
+                .validate_dimensions(Some(&[128]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_offset_mapping() {
+        // "é" is 2 bytes and "𝄞" 4 bytes, or 2 UTF-16 code units
+        let text = "né 𝄞 x";
+
+        let chars = OffsetMapping::Char.converter(text);
+
+        assert_eq!(OffsetMapping::Byte.converter(text).convert(9), 9);
+        assert_eq!(chars.convert(9), 5);
+        assert_eq!(OffsetMapping::Utf16.converter(text).convert(9), 6);
+
+        // inside "é"
+        assert_eq!(chars.convert(2), 2);
+        assert_eq!(chars.convert(text.len()), 6);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let defaults = InferenceOptions::defaults(&ModelType::Embedding, 512).unwrap();
+
+        assert_eq!(defaults.truncate, Some(Truncate::MaxLength(512)));
+        assert_eq!(defaults.normalize, Some(false));
+        assert_eq!(defaults.return_offsets, Some(true));
+        assert_eq!(defaults.top_k, None);
+        assert_eq!(defaults.truncation, Some(TruncationMode::Truncate));
+        assert_eq!(defaults.offset_mapping, Some(OffsetMapping::Byte));
+
+        assert!(InferenceOptions::defaults(&ModelType::ImageEmbedding, 512).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LateInteractionRequest {
+    pub inputs: Vec<String>,
+    /// Encode inputs as queries (marker + `[MASK]` augmentation) rather than documents.
+    #[serde(default)]
+    pub is_query: bool,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for LateInteractionRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            is_query: false,
+            options: Default::default(),
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct LateInteractionResponse {
+    pub results: Vec<LateInteractionEmbedding>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Matrix of L2-normalized token vectors of shape `[n_tokens, dim]`, flattened in row-major order.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LateInteractionEmbedding {
+    pub n_tokens: usize,
+    pub dim: usize,
+    pub values: Vec<f32>,
+    /// Number of tokens the model saw, special tokens included. Unlike `n_tokens`, counts
+    /// the tokens dropped from the matrix, and not the padding of fixed-length queries.
+    pub num_tokens: usize,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LateInteractionScoreRequest {
+    pub query: String,
+    pub documents: Vec<String>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct LateInteractionScoreResponse {
+    /// MaxSim score of each document against the query, in input order.
+    pub scores: Vec<f32>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// ColBERT-style query and document encoding options, set at build time.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LateInteractionConfig {
+    /// Text prepended to every query, e.g. `"[Q] "`.
+    #[serde(default)]
+    pub query_prefix: Option<String>,
+    /// Text prepended to every document, e.g. `"[D] "`.
+    #[serde(default)]
+    pub document_prefix: Option<String>,
+    /// Pad (or truncate) queries to exactly this many tokens using `mask_token`.
+    #[serde(default)]
+    pub query_length: Option<usize>,
+    /// Token used for query augmentation. Defaults to `[MASK]`.
+    #[serde(default = "default_mask_token")]
+    pub mask_token: String,
+    /// Drop punctuation tokens from document matrices.
+    #[serde(default = "default_mask_punctuation")]
+    pub mask_punctuation: bool,
+}
+
+impl Default for LateInteractionConfig {
+    fn default() -> Self {
+        Self {
+            query_prefix: None,
+            document_prefix: None,
+            query_length: None,
+            mask_token: default_mask_token(),
+            mask_punctuation: default_mask_punctuation(),
+        }
+    }
+}
+
+fn default_mask_token() -> String {
+    "[MASK]".to_string()
+}
+
+fn default_mask_punctuation() -> bool {
+    true
+}
+
+/// Linear projection applied to token vectors, in `torch.nn.Linear` layout:
+/// `weight` has shape `[dim, hidden_dim]` and `bias` (if any) has length `dim`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Projection {
+    pub weight: Vec<Vec<f32>>,
+    #[serde(default)]
+    pub bias: Option<Vec<f32>>,
+}
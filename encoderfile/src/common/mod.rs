@@ -1,30 +1,68 @@
 mod config;
+mod corpus;
 mod embedding;
+mod embedding_encoding;
+mod inference_options;
+mod late_interaction;
 mod model_config;
+mod model_inputs;
 mod model_metadata;
+mod model_outputs;
 pub mod model_type;
 mod sentence_embedding;
 mod sequence_classification;
+mod sparse_embedding;
 mod token;
 mod token_classification;
+mod tokenize;
 
 // CV
 mod image_classification;
+mod image_embedding;
+mod image_segmentation;
 mod image_types;
+mod object_detection;
+
+// Audio
+mod audio_classification;
+mod audio_embedding;
+mod audio_types;
+
+// Multimodal
+mod multimodal_embedding;
 
 pub use config::*;
+pub use corpus::*;
 pub use embedding::*;
+pub use embedding_encoding::*;
+pub use inference_options::*;
+pub use late_interaction::*;
 pub use model_config::*;
+pub use model_inputs::*;
 pub use model_metadata::*;
+pub use model_outputs::*;
 pub use sentence_embedding::*;
 pub use sequence_classification::*;
+pub use sparse_embedding::*;
 pub use token::*;
 pub use token_classification::*;
+pub use tokenize::*;
 
 // CV
 use anyhow::Result;
 pub use image_classification::*;
+pub use image_embedding::*;
+pub use image_segmentation::*;
 pub use image_types::*;
+pub use object_detection::*;
+
+// Audio
+pub use audio_classification::*;
+pub use audio_embedding::*;
+pub use audio_types::*;
+
+// Multimodal
+pub use multimodal_embedding::*;
 use std::io::Read;
 
 pub trait FromCliInput {
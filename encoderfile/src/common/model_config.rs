@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::{
+    AggregationStrategy, Calibration, ImageEmbeddingConfig, LabelConfig, LateInteractionConfig,
+    ModelInputs, ModelOutputs, MultimodalEmbeddingConfig, ObjectDetectionConfig, PoolingConfig,
+    ProblemType, QuantizationRanges, TruncationMode,
+};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelConfig {
     pub model_type: String,
@@ -12,6 +18,46 @@ pub struct ModelConfig {
     pub width: Option<u32>,
     pub image_size: Option<u32>,
     pub num_channels: Option<u32>,
+    pub problem_type: Option<ProblemType>,
+    // token classification defaults set at build time
+    pub aggregation_strategy: Option<AggregationStrategy>,
+    pub ignore_labels: Option<Vec<String>>,
+    // late interaction encoding options set at build time
+    pub late_interaction: Option<LateInteractionConfig>,
+    // image embedding output selection set at build time
+    pub image_embedding: Option<ImageEmbeddingConfig>,
+    // object detection thresholds set at build time
+    pub object_detection: Option<ObjectDetectionConfig>,
+    // vision tower settings of dual-tower models (e.g. CLIP)
+    pub vision_config: Option<VisionConfig>,
+    // image-text scoring options set at build time
+    pub multimodal_embedding: Option<MultimodalEmbeddingConfig>,
+    // embedding sizes accepted by the `dimensions` option, set at build time
+    pub matryoshka_dimensions: Option<Vec<usize>>,
+    // int8/uint8 quantization ranges, set at build time
+    pub embedding_quantization: Option<QuantizationRanges>,
+    // named input prefixes selected by the `prompt_name` option, set at build time
+    pub prompts: Option<HashMap<String, String>>,
+    pub default_prompt_name: Option<String>,
+    // sentence embedding pooling, set at build time
+    pub pooling: Option<PoolingConfig>,
+    // handling of over-length text inputs when a request sets no `truncation`, set at build time
+    pub truncation: Option<TruncationMode>,
+    // sequence classification score calibration, set at build time
+    pub calibration: Option<Calibration>,
+    // classifier label renames, merges, hiding and descriptions by model label, set at build time
+    pub label_overrides: Option<HashMap<String, LabelConfig>>,
+    // graph output names by role and extra outputs to return, set at build time
+    pub outputs: Option<ModelOutputs>,
+    // graph input names of text models by role, set at build time
+    pub inputs: Option<ModelInputs>,
+}
+
+/// Vision tower settings nested under `vision_config` in dual-tower model configs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VisionConfig {
+    pub image_size: Option<u32>,
+    pub num_channels: Option<u32>,
 }
 
 // TODO add image handling metadata
@@ -50,6 +96,24 @@ impl ModelConfig {
     pub fn num_channels(&self) -> Option<u32> {
         self.num_channels
     }
+
+    /// The prefix of prompt `name`, or of the default prompt if `name` is unset.
+    pub fn prompt(&self, name: Option<&str>) -> Result<Option<&str>, &'static str> {
+        let Some(name) = name.or(self.default_prompt_name.as_deref()) else {
+            return Ok(None);
+        };
+
+        self.prompts
+            .as_ref()
+            .and_then(|prompts| prompts.get(name))
+            .map(|prompt| Some(prompt.as_str()))
+            .ok_or("prompt_name must be one of the model's prompts")
+    }
+
+    /// The truncation mode of a request, or the build-time one if the request sets none.
+    pub fn truncation(&self, truncation: Option<TruncationMode>) -> TruncationMode {
+        truncation.or(self.truncation).unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +143,24 @@ mod tests {
             width: None,
             image_size: None,
             num_channels: None,
+            problem_type: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            image_embedding: None,
+            object_detection: None,
+            vision_config: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            label_overrides: None,
+            outputs: None,
+            inputs: None,
         };
 
         assert_eq!(config.num_labels(), Some(3));
@@ -92,6 +174,24 @@ mod tests {
             width: None,
             image_size: None,
             num_channels: None,
+            problem_type: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            image_embedding: None,
+            object_detection: None,
+            vision_config: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            label_overrides: None,
+            outputs: None,
+            inputs: None,
         };
 
         assert_eq!(config.num_labels(), Some(3));
@@ -105,8 +205,45 @@ mod tests {
             width: None,
             image_size: None,
             num_channels: None,
+            problem_type: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            late_interaction: None,
+            image_embedding: None,
+            object_detection: None,
+            vision_config: None,
+            multimodal_embedding: None,
+            matryoshka_dimensions: None,
+            embedding_quantization: None,
+            prompts: None,
+            default_prompt_name: None,
+            pooling: None,
+            truncation: None,
+            calibration: None,
+            label_overrides: None,
+            outputs: None,
+            inputs: None,
         };
 
         assert_eq!(config.num_labels(), Some(3));
     }
+
+    #[test]
+    fn test_prompt() {
+        let mut config: ModelConfig =
+            serde_json::from_str(r#"{"model_type": "bert"}"#).expect("Failed to parse config");
+
+        assert_eq!(config.prompt(None), Ok(None));
+        assert!(config.prompt(Some("query")).is_err());
+
+        config.prompts = Some(HashMap::from([
+            ("query".to_string(), "query: ".to_string()),
+            ("passage".to_string(), "passage: ".to_string()),
+        ]));
+        config.default_prompt_name = Some("query".to_string());
+
+        assert_eq!(config.prompt(None), Ok(Some("query: ")));
+        assert_eq!(config.prompt(Some("passage")), Ok(Some("passage: ")));
+        assert!(config.prompt(Some("document")).is_err());
+    }
 }
@@ -0,0 +1,92 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Tensor bound to a graph input of a text model.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InputRole {
+    /// Token ids.
+    InputIds,
+    /// 1 for tokens, 0 for padding.
+    AttentionMask,
+    /// Segment ids of sentence pairs.
+    TokenTypeIds,
+    /// Position of each token, counted over the attention mask.
+    PositionIds,
+}
+
+impl InputRole {
+    pub const ALL: [InputRole; 4] = [
+        InputRole::InputIds,
+        InputRole::AttentionMask,
+        InputRole::TokenTypeIds,
+        InputRole::PositionIds,
+    ];
+
+    /// Graph input name the role binds to by default.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputRole::InputIds => "input_ids",
+            InputRole::AttentionMask => "attention_mask",
+            InputRole::TokenTypeIds => "token_type_ids",
+            InputRole::PositionIds => "position_ids",
+        }
+    }
+}
+
+impl std::fmt::Display for InputRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Graph input names of a text model, set at build time.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ModelInputs {
+    /// Graph input names by the tensor bound to them. Roles without an entry bind to the graph
+    /// input named after the role.
+    #[serde(default)]
+    pub names: HashMap<InputRole, String>,
+}
+
+impl ModelInputs {
+    /// Graph input name `role` binds to.
+    pub fn name(&self, role: InputRole) -> &str {
+        self.names
+            .get(&role)
+            .map(String::as_str)
+            .unwrap_or(role.as_str())
+    }
+
+    /// Role bound to the graph input `name`, if any.
+    pub fn role(&self, name: &str) -> Option<InputRole> {
+        InputRole::ALL
+            .into_iter()
+            .find(|role| self.name(*role) == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_role() {
+        let inputs = ModelInputs {
+            names: HashMap::from([(InputRole::InputIds, "ids".to_string())]),
+        };
+
+        assert_eq!(inputs.name(InputRole::InputIds), "ids");
+        assert_eq!(inputs.role("ids"), Some(InputRole::InputIds));
+        // a renamed role no longer binds to its default name
+        assert_eq!(inputs.role("input_ids"), None);
+        assert_eq!(
+            inputs.role("attention_mask"),
+            Some(InputRole::AttentionMask)
+        );
+        assert_eq!(inputs.role("pixel_values"), None);
+    }
+}
@@ -6,4 +6,26 @@ pub struct GetModelMetadataResponse {
     pub model_id: String,
     pub model_type: ModelType,
     pub id2label: Option<HashMap<u32, String>>,
+    /// Options applied when a request leaves them unset. Absent for model types that take
+    /// no per-request options.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_options: Option<super::InferenceOptions>,
+    /// Embedding sizes accepted by the `dimensions` option, if restricted at build time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<Vec<usize>>,
+    /// Prompts selectable with the `prompt_name` option, by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<HashMap<String, String>>,
+    /// Prompt applied when a request sets no `prompt_name`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_prompt_name: Option<String>,
+    /// Calibration of scores set at build time (sequence classification only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<super::Calibration>,
+    /// What each label means, by label, if described at build time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_descriptions: Option<HashMap<String, String>>,
+    /// Thresholds of labels set at build time, by label (multi-label classification only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_thresholds: Option<HashMap<String, f32>>,
 }
@@ -0,0 +1,52 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Graph outputs read at inference time, set at build time.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ModelOutputs {
+    /// Graph output names by the role inference reads them for (e.g. `logits`). Roles
+    /// without an entry read the graph output named after the role.
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+    /// Additional graph outputs returned with each result and passed to `Postprocess`.
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+impl ModelOutputs {
+    /// Graph output name filling `role`.
+    pub fn name<'a>(&'a self, role: &'a str) -> &'a str {
+        self.names.get(role).map(String::as_str).unwrap_or(role)
+    }
+}
+
+/// An extra model output for a single input.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema, JsonSchema)]
+pub struct OutputTensor {
+    /// Shape of the output without its batch dimension.
+    pub shape: Vec<usize>,
+    /// Values in row-major order.
+    pub values: Vec<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_name() {
+        let outputs = ModelOutputs {
+            names: HashMap::from([(
+                "last_hidden_state".to_string(),
+                "token_embeddings".to_string(),
+            )]),
+            extra: vec![],
+        };
+
+        assert_eq!(outputs.name("last_hidden_state"), "token_embeddings");
+        assert_eq!(outputs.name("logits"), "logits");
+        assert_eq!(ModelOutputs::default().name("logits"), "logits");
+    }
+}
@@ -51,5 +51,13 @@ model_type![
     SequenceClassification,
     TokenClassification,
     SentenceEmbedding,
-    ImageClassification
+    SparseEmbedding,
+    LateInteraction,
+    ImageClassification,
+    ImageEmbedding,
+    ObjectDetection,
+    ImageSegmentation,
+    AudioClassification,
+    AudioEmbedding,
+    MultimodalEmbedding
 ];
@@ -0,0 +1,129 @@
+use crate::common::image_types::{ImageInfo, ImageLabels};
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct MultimodalTextEmbeddingRequest {
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultimodalImageEmbeddingRequest {
+    pub images: Vec<ImageInfo>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct MultimodalEmbeddingResponse {
+    pub results: Vec<MultimodalEmbedding>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// L2-normalized embedding in the shared text/image space.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, Clone)]
+pub struct MultimodalEmbedding {
+    pub embedding: Vec<f32>,
+}
+
+/// Scores each image against a set of candidate text labels (zero-shot image classification).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultimodalSimilarityRequest {
+    pub images: Vec<ImageInfo>,
+    pub labels: Vec<String>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for MultimodalSimilarityRequest {
+    /// Arguments naming an existing file are read as images; all others are candidate labels.
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        let (paths, labels): (Vec<String>, Vec<String>) = inputs
+            .into_iter()
+            .partition(|input| std::path::Path::new(input).is_file());
+
+        let images = paths
+            .into_iter()
+            .map(|path| {
+                let image_data = std::fs::read(path).expect("Failed to read image file");
+                let format =
+                    image::guess_format(&image_data).expect("Failed to guess image format");
+                ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                }
+            })
+            .collect();
+
+        Self {
+            images,
+            labels,
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct MultimodalSimilarityResponse {
+    /// Labels of each image, sorted by descending score.
+    pub results: Vec<ImageLabels>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Image-text scoring options, set at build time.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MultimodalEmbeddingConfig {
+    /// Multiplier applied to cosine similarities before the softmax over labels.
+    /// Defaults to `100.0`, the trained value of CLIP's `exp(logit_scale)`.
+    #[serde(default = "default_logit_scale")]
+    pub logit_scale: f32,
+}
+
+impl Default for MultimodalEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            logit_scale: default_logit_scale(),
+        }
+    }
+}
+
+fn default_logit_scale() -> f32 {
+    100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FromCliInput;
+    use image::ImageFormat;
+
+    #[test]
+    fn test_similarity_request_from_cli_input() {
+        let request = MultimodalSimilarityRequest::from_cli_input(vec![
+            "../test-pictures/yoga01.jpg".to_string(),
+            "a photo of a cat".to_string(),
+            "a photo of a person".to_string(),
+        ]);
+
+        assert_eq!(request.images.len(), 1);
+        assert_eq!(request.images[0].image_format, ImageFormat::Jpeg);
+        assert_eq!(
+            request.labels,
+            vec!["a photo of a cat", "a photo of a person"]
+        );
+    }
+
+    #[test]
+    fn test_multimodal_embedding_config_defaults() {
+        let config: MultimodalEmbeddingConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(config.logit_scale, 100.0);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::common::FromReadInput;
+use crate::common::image_types::ImageInfo;
+use anyhow::Result;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectDetectionRequest {
+    pub images: Vec<ImageInfo>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for ObjectDetectionRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        let images = inputs
+            .into_iter()
+            .map(|path| {
+                let image_data = std::fs::read(path).expect("Failed to read image file");
+                let format =
+                    image::guess_format(&image_data).expect("Failed to guess image format");
+                ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                }
+            })
+            .collect();
+
+        Self {
+            images,
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+impl FromReadInput for ObjectDetectionRequest {
+    fn from_read_input(input: Vec<&mut impl Read>) -> Result<Self> {
+        let images = input
+            .into_iter()
+            .map(|reader| {
+                let mut image_data = Vec::new();
+                reader
+                    .read_to_end(&mut image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to read image data: {}", e))?;
+                let format = image::guess_format(&image_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to guess image format: {}", e))?;
+                Ok(ImageInfo {
+                    image_bytes: Bytes::from(image_data),
+                    image_format: format,
+                })
+            })
+            .collect::<Result<Vec<ImageInfo>>>()?;
+
+        Ok(Self {
+            images,
+            metadata: Some(HashMap::default()),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::ToResponse)]
+pub struct ObjectDetectionResponse {
+    pub results: Vec<ObjectDetectionResult>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Objects detected in a single image, sorted by descending score.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ObjectDetectionResult {
+    pub objects: Vec<DetectedObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq)]
+pub struct DetectedObject {
+    pub label: String,
+    pub score: f32,
+    #[serde(rename = "box")]
+    pub bounding_box: BoundingBox,
+}
+
+/// Bounding box in pixel coordinates of the original image.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub xmin: i32,
+    pub ymin: i32,
+    pub xmax: i32,
+    pub ymax: i32,
+}
+
+impl BoundingBox {
+    pub fn area(&self) -> f32 {
+        ((self.xmax - self.xmin).max(0) * (self.ymax - self.ymin).max(0)) as f32
+    }
+
+    /// Intersection over union of two boxes.
+    pub fn iou(&self, other: &BoundingBox) -> f32 {
+        let intersection = BoundingBox {
+            xmin: self.xmin.max(other.xmin),
+            ymin: self.ymin.max(other.ymin),
+            xmax: self.xmax.min(other.xmax),
+            ymax: self.ymax.min(other.ymax),
+        }
+        .area();
+
+        let union = self.area() + other.area() - intersection;
+
+        if union > 0.0 {
+            intersection / union
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectDetectionConfig {
+    /// Minimum score of a detected object. Defaults to `0.5`.
+    #[serde(default = "default_threshold")]
+    pub threshold: f32,
+    /// Suppress same-label boxes overlapping a higher-scoring box by more than
+    /// this IoU. Non-maximum suppression is skipped if not set.
+    #[serde(default)]
+    pub nms_iou_threshold: Option<f32>,
+}
+
+impl Default for ObjectDetectionConfig {
+    fn default() -> Self {
+        Self {
+            threshold: default_threshold(),
+            nms_iou_threshold: None,
+        }
+    }
+}
+
+fn default_threshold() -> f32 {
+    0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounding_box_iou() {
+        let a = BoundingBox {
+            xmin: 0,
+            ymin: 0,
+            xmax: 10,
+            ymax: 10,
+        };
+        let b = BoundingBox {
+            xmin: 5,
+            ymin: 0,
+            xmax: 15,
+            ymax: 10,
+        };
+        let c = BoundingBox {
+            xmin: 20,
+            ymin: 20,
+            xmax: 30,
+            ymax: 30,
+        };
+
+        assert_eq!(a.iou(&a), 1.0);
+        assert!((a.iou(&b) - 50.0 / 150.0).abs() < 1e-6);
+        assert_eq!(a.iou(&c), 0.0);
+    }
+
+    #[test]
+    fn test_object_detection_config_defaults() {
+        let config: ObjectDetectionConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(config.threshold, 0.5);
+        assert_eq!(config.nms_iou_threshold, None);
+    }
+}
@@ -6,6 +6,9 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct SentenceEmbeddingRequest {
     pub inputs: Vec<String>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
     #[serde(default)]
     pub metadata: Option<HashMap<String, String>>,
 }
@@ -14,6 +17,7 @@ impl super::FromCliInput for SentenceEmbeddingRequest {
     fn from_cli_input(inputs: Vec<String>) -> Self {
         Self {
             inputs,
+            options: Default::default(),
             metadata: Some(HashMap::default()),
         }
     }
@@ -29,5 +33,96 @@ pub struct SentenceEmbeddingResponse {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct SentenceEmbedding {
-    pub embedding: Vec<f32>,
+    /// Floats, int8 or uint8 values, or a base64 string, depending on `encoding_format`
+    /// and `base64`.
+    pub embedding: super::EmbeddingValues,
+    /// Number of tokens the model saw, special tokens included.
+    pub num_tokens: usize,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+    /// Extra model outputs configured at build time, by output name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, super::OutputTensor>>,
+}
+
+/// Cosine similarity of every source against every target, or of every source against
+/// every other source if `targets` is omitted.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SimilarityRequest {
+    pub sources: Vec<String>,
+    #[serde(default)]
+    pub targets: Option<Vec<String>>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct SimilarityResponse {
+    /// `[len(sources), len(targets)]` matrix of cosine similarities.
+    pub similarities: Vec<Vec<f32>>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Groups near-duplicate inputs: inputs whose cosine similarity is at least `threshold` share
+/// a cluster, as do inputs linked through a chain of near-duplicates.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct DedupeRequest {
+    pub inputs: Vec<String>,
+    /// Minimum cosine similarity of near-duplicates. Defaults to `0.9`.
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct DedupeResponse {
+    /// Cluster of each input, in input order. Clusters are numbered in order of first
+    /// appearance.
+    pub assignments: Vec<usize>,
+    /// Indices of the inputs of each cluster, in input order. The first input of a cluster is
+    /// its representative.
+    pub clusters: Vec<Vec<usize>>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+pub const DEFAULT_DEDUPE_THRESHOLD: f32 = 0.9;
+
+/// How token embeddings are pooled into one embedding per input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolingMode {
+    /// Average of the token embeddings.
+    #[default]
+    Mean,
+    /// First (CLS) token embedding, e.g. BGE.
+    Cls,
+    /// Element-wise maximum of the token embeddings.
+    Max,
+    /// Last non-padding token embedding, e.g. decoder-style embedders.
+    LastToken,
+    /// Average of the token embeddings weighted by position (1 for the first token).
+    WeightedMean,
+}
+
+/// Pooling of sentence embeddings, set at build time. A Lua `Postprocess` function overrides
+/// the pooling mode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PoolingConfig {
+    /// Defaults to `mean`.
+    #[serde(default)]
+    pub mode: PoolingMode,
+    /// L2-normalize pooled embeddings. Defaults to `false`.
+    #[serde(default)]
+    pub normalize: bool,
 }
@@ -6,6 +6,13 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct SequenceClassificationRequest {
     pub inputs: Vec<String>,
+    /// Score above which a label is predicted (multi-label models only), for labels without
+    /// a build-time threshold. Defaults to 0.5.
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
     #[serde(default)]
     pub metadata: Option<HashMap<String, String>>,
 }
@@ -14,6 +21,8 @@ impl super::FromCliInput for SequenceClassificationRequest {
     fn from_cli_input(inputs: Vec<String>) -> Self {
         Self {
             inputs,
+            threshold: None,
+            options: Default::default(),
             metadata: Some(HashMap::default()),
         }
     }
@@ -23,14 +32,160 @@ impl super::FromCliInput for SequenceClassificationRequest {
 pub struct SequenceClassificationResponse {
     pub results: Vec<SequenceClassificationResult>,
     pub model_id: String,
+    /// The threshold applied to the scores of labels without a build-time threshold
+    /// (multi-label models only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct SequenceClassificationResult {
+    /// Empty when `return_logits` is `false`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub logits: Vec<f32>,
     pub scores: Vec<f32>,
-    pub predicted_index: u32,
+    pub predicted_index: Option<u32>,
     pub predicted_label: Option<String>,
+    pub predicted_labels: Vec<String>,
+    /// The `top_k` highest-scoring labels, sorted by descending score. Only set when `top_k`
+    /// is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_labels: Option<Vec<LabelScore>>,
+    /// Number of tokens the model saw, special tokens included. Not set for audio inputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_tokens: Option<usize>,
+    /// Whether the input was cut to fit the truncation length. Not set for audio inputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    /// Extra model outputs configured at build time, by output name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, super::OutputTensor>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LabelScore {
+    pub label: String,
+    pub score: f32,
+}
+
+pub const DEFAULT_MULTI_LABEL_THRESHOLD: f32 = 0.5;
+
+/// The `problem_type` of a HuggingFace sequence classification model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemType {
+    /// Softmax over labels; the top label is predicted.
+    SingleLabelClassification,
+    /// Sigmoid per label; every label scoring above a threshold is predicted.
+    MultiLabelClassification,
+    /// Raw scores; no label is predicted.
+    Regression,
+}
+
+/// Calibration of classifier scores, set at build time. Fit it on a labelled dev set with
+/// `encoderfile calibrate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Calibration {
+    /// Logits divided by a single temperature.
+    Temperature { temperature: f32 },
+    /// Logits divided by one temperature per class.
+    PerClassTemperature { temperatures: Vec<f32> },
+    /// A sigmoid of `slope * logit + intercept` per class (Platt scaling).
+    Platt {
+        slopes: Vec<f32>,
+        intercepts: Vec<f32>,
+    },
+    /// A non-decreasing, piecewise-linear map of the uncalibrated score per class, fit by
+    /// isotonic regression.
+    Isotonic { curves: Vec<IsotonicCurve> },
+}
+
+/// Points of a piecewise-linear curve, sorted by `x`. Scores outside `x` are clamped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct IsotonicCurve {
+    pub x: Vec<f32>,
+    pub y: Vec<f32>,
+}
+
+impl Calibration {
+    /// Checks the parameters against the model's number of labels.
+    pub fn validate(&self, num_labels: usize) -> Result<(), &'static str> {
+        let (lengths, positive) = match self {
+            Self::Temperature { temperature } => (vec![num_labels], *temperature > 0.0),
+            Self::PerClassTemperature { temperatures } => (
+                vec![temperatures.len()],
+                temperatures.iter().all(|t| *t > 0.0),
+            ),
+            Self::Platt { slopes, intercepts } => (vec![slopes.len(), intercepts.len()], true),
+            Self::Isotonic { curves } => {
+                if curves
+                    .iter()
+                    .any(|c| c.x.is_empty() || c.x.len() != c.y.len())
+                {
+                    return Err(
+                        "isotonic curves must have as many x as y values, and at least one",
+                    );
+                }
+
+                if curves.iter().any(|c| {
+                    c.x.windows(2).any(|w| w[0] > w[1]) || c.y.windows(2).any(|w| w[0] > w[1])
+                }) {
+                    return Err("isotonic curves must be non-decreasing");
+                }
+
+                (vec![curves.len()], true)
+            }
+        };
+
+        if !positive {
+            return Err("temperatures must be greater than 0");
+        }
+
+        if lengths.iter().any(|len| *len != num_labels) {
+            return Err("calibration must have one parameter per label");
+        }
+
+        Ok(())
+    }
+}
+
+impl IsotonicCurve {
+    /// The curve's value at `x`, interpolated linearly between points.
+    pub fn interpolate(&self, x: f32) -> f32 {
+        let i = self.x.partition_point(|p| *p < x);
+
+        match i {
+            0 => self.y[0],
+            i if i == self.x.len() => self.y[i - 1],
+            i => {
+                let (x0, x1) = (self.x[i - 1], self.x[i]);
+                let (y0, y1) = (self.y[i - 1], self.y[i]);
+
+                if x1 > x0 {
+                    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+                } else {
+                    y1
+                }
+            }
+        }
+    }
+}
+
+/// Build-time override of one of a classifier's labels.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LabelConfig {
+    /// Name the label is returned under. Labels renamed to the same name are merged into
+    /// one label.
+    pub name: Option<String>,
+    /// What the label means, reported in the model metadata and MCP server instructions.
+    pub description: Option<String>,
+    /// Drops the label from results.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Score above which the label is predicted (multi-label models only), in place of the
+    /// request's `threshold`.
+    pub threshold: Option<f32>,
 }
@@ -0,0 +1,58 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SparseEmbeddingRequest {
+    pub inputs: Vec<String>,
+    /// Keep only the `top_k` highest-weighted terms of each vector.
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    /// Drop terms whose weight is not strictly greater than `threshold`. Defaults to `0.0`.
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Decode indices into vocabulary tokens using the model's tokenizer.
+    #[serde(default)]
+    pub return_tokens: bool,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for SparseEmbeddingRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            top_k: None,
+            threshold: None,
+            return_tokens: false,
+            options: Default::default(),
+            metadata: Some(HashMap::default()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct SparseEmbeddingResponse {
+    pub results: Vec<SparseEmbedding>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Sparse vector over the model vocabulary. `indices` are sorted in ascending order
+/// and `values[i]` is the weight of `indices[i]`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SparseEmbedding {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<String>>,
+    /// Number of tokens the model saw, special tokens included.
+    pub num_tokens: usize,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+}
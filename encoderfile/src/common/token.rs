@@ -4,6 +4,9 @@ use schemars::JsonSchema;
 pub struct TokenInfo {
     pub token: String,
     pub token_id: u32,
+    /// Start of the token in the input, in the unit of the `offset_mapping` option (UTF-8
+    /// bytes by default).
     pub start: usize,
+    /// Exclusive end of the token in the input, in the same unit as `start`.
     pub end: usize,
 }
@@ -5,7 +5,22 @@ use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct TokenClassificationRequest {
+    /// Texts to classify. Leave empty when `words` is set.
+    #[serde(default)]
     pub inputs: Vec<String>,
+    /// Pre-tokenized inputs, e.g. from an upstream word segmenter, as an alternative to
+    /// `inputs`. Each input gets one label per word in `words`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<Vec<String>>>,
+    /// Overrides the aggregation strategy set at build time.
+    #[serde(default)]
+    pub aggregation_strategy: Option<AggregationStrategy>,
+    /// Entity groups to drop from `entities`. Defaults to `["O"]`.
+    #[serde(default)]
+    pub ignore_labels: Option<Vec<String>>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
     #[serde(default)]
     pub metadata: Option<HashMap<String, String>>,
 }
@@ -14,6 +29,10 @@ impl super::FromCliInput for TokenClassificationRequest {
     fn from_cli_input(inputs: Vec<String>) -> Self {
         Self {
             inputs,
+            words: None,
+            aggregation_strategy: None,
+            ignore_labels: None,
+            options: Default::default(),
             metadata: Some(HashMap::default()),
         }
     }
@@ -30,12 +49,144 @@ pub struct TokenClassificationResponse {
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct TokenClassificationResult {
     pub tokens: Vec<TokenClassification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entities: Option<Vec<TokenClassificationEntity>>,
+    /// One prediction per word, for pre-tokenized `words` inputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<WordClassification>>,
+    /// Number of tokens the model saw, special tokens included.
+    pub num_tokens: usize,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+    /// Extra model outputs configured at build time, by output name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, super::OutputTensor>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct TokenClassification {
     pub token_info: super::token::TokenInfo,
+    /// Logits of every label. Empty when `return_logits` is `false`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub scores: Vec<f32>,
     pub label: String,
     pub score: f32,
 }
+
+/// The label of a pre-tokenized word, aggregated from its sub-word tokens.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct WordClassification {
+    /// Index of the word in the input's `words`.
+    pub word_index: usize,
+    pub word: String,
+    pub label: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct TokenClassificationEntity {
+    pub entity_group: String,
+    pub score: f32,
+    pub word: String,
+    /// Start of the entity in the input, in the unit of the `offset_mapping` option (UTF-8
+    /// bytes by default).
+    pub start: usize,
+    /// Exclusive end of the entity in the input, in the same unit as `start`.
+    pub end: usize,
+}
+
+/// How sub-word predictions are merged into entities. Follows the semantics of
+/// the `aggregation_strategy` argument of the HuggingFace token classification pipeline.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// No aggregation: only per-token predictions are returned.
+    #[default]
+    None,
+    /// Merge adjacent tokens sharing an entity tag, without regard for word boundaries.
+    Simple,
+    /// Label each word with the prediction of its first sub-word.
+    First,
+    /// Label each word with the argmax of its sub-words' averaged scores.
+    Average,
+    /// Label each word with the prediction of its highest-scoring sub-word.
+    Max,
+}
+
+pub const DEFAULT_IGNORE_LABELS: &[&str] = &["O"];
+
+/// Replaces the entities detected in each input, e.g. to redact personal data.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RedactRequest {
+    pub inputs: Vec<String>,
+    /// How entities are replaced. Defaults to `label`.
+    #[serde(default)]
+    pub mode: Option<RedactionMode>,
+    /// Character entities are masked with in `mask` mode. Defaults to `*`.
+    #[serde(default)]
+    pub mask_char: Option<String>,
+    /// Entity groups to redact. Defaults to every entity group not in `ignore_labels`.
+    #[serde(default)]
+    pub labels: Option<Vec<String>>,
+    /// Entity groups never redacted. Defaults to the labels set at build time, or `["O"]`.
+    #[serde(default)]
+    pub ignore_labels: Option<Vec<String>>,
+    /// Minimum score of redacted entities. Defaults to `0.0`.
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Overrides the aggregation strategy set at build time. `none` is treated as `simple`.
+    #[serde(default)]
+    pub aggregation_strategy: Option<AggregationStrategy>,
+    /// Per-request inference options.
+    #[serde(default)]
+    pub options: super::InferenceOptions,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// How redacted entities are replaced.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionMode {
+    /// The entity group in brackets, e.g. `[PER]`.
+    #[default]
+    Label,
+    /// `mask_char` repeated once per character of the entity.
+    Mask,
+    /// The entity group and a number, e.g. `[PER_1]`, the same for every occurrence of an
+    /// entity within a request.
+    Pseudonym,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct RedactResponse {
+    pub results: Vec<Redaction>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct Redaction {
+    /// The input with every redacted entity replaced.
+    pub text: String,
+    /// The redacted entities, in input order, for auditing.
+    pub spans: Vec<RedactedSpan>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RedactedSpan {
+    pub entity_group: String,
+    pub score: f32,
+    /// Start of the entity in the input, in the unit of the `offset_mapping` option (UTF-8
+    /// bytes by default).
+    pub start: usize,
+    /// Exclusive end of the entity in the input, in the same unit as `start`.
+    pub end: usize,
+    /// The text the entity was replaced with.
+    pub replacement: String,
+}
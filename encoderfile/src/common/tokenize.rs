@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::{OffsetMapping, Truncate};
+
+/// Tokenizes inputs with the model's tokenizer, as inference would.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct TokenizeRequest {
+    pub inputs: Vec<String>,
+    /// `true` truncates inputs to the tokenizer's `max_length`, `false` disables truncation,
+    /// and a number truncates inputs to that many tokens. Defaults to `true`.
+    #[serde(default)]
+    pub truncate: Option<Truncate>,
+    /// Unit of token offsets, like the `offset_mapping` inference option. Defaults to `byte`.
+    #[serde(default)]
+    pub offset_mapping: Option<OffsetMapping>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for TokenizeRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            truncate: None,
+            offset_mapping: None,
+            metadata: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct TokenizeResponse {
+    pub results: Vec<Tokenization>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Tokens of one input, without padding.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct Tokenization {
+    pub ids: Vec<u32>,
+    pub tokens: Vec<String>,
+    /// `[start, end)` offsets of each token in the input, in the requested `offset_mapping`
+    /// unit. Special tokens have `[0, 0]`.
+    pub offsets: Vec<[usize; 2]>,
+    /// `1` for special tokens such as `[CLS]` and `[SEP]`, `0` otherwise.
+    pub special_tokens_mask: Vec<u32>,
+    /// Whether the input was cut to fit the truncation length.
+    pub truncated: bool,
+}
+
+/// Decodes token ids back into text.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct DetokenizeRequest {
+    /// Token ids of each text.
+    pub inputs: Vec<Vec<u32>>,
+    /// Leave special tokens out of the decoded text. Defaults to `true`.
+    #[serde(default)]
+    pub skip_special_tokens: Option<bool>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct DetokenizeResponse {
+    /// Decoded text of each input.
+    pub results: Vec<String>,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Counts the tokens of inputs, including special tokens, without truncating them.
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct CountTokensRequest {
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl super::FromCliInput for CountTokensRequest {
+    fn from_cli_input(inputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            metadata: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, JsonSchema, utoipa::ToResponse)]
+pub struct CountTokensResponse {
+    pub results: Vec<TokenCount>,
+    /// Number of tokens inputs are truncated to by default.
+    pub max_length: usize,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct TokenCount {
+    pub num_tokens: usize,
+    /// Whether the input is longer than `max_length`, and so truncated by default.
+    pub truncated: bool,
+}
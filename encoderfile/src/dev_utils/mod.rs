@@ -1,14 +1,19 @@
 use crate::{
     common::{
-        Config, TokenizerConfig,
+        Config, Corpus, CorpusDocument, CorpusIndex, ImageEmbeddingOutput, InferenceOptions,
+        ModelOutputs, SentenceEmbeddingRequest, TokenizerConfig,
         model_type::{self, ModelTypeSpec},
     },
     runtime::{
-        AppState, ClassifierState, EncoderfileState, FeatureExtractorState, ImageConfig,
-        ImageInputState, ImagePreprocessing, ImageSize, InputType, ORTSessionBuilder, TaskType,
-        TextInputState,
+        AppState, AudioFeatureExtractor, AudioInputState, ClassifierState, CorpusState,
+        EncoderfileState, FeatureExtractorState, ImageConfig, ImageEmbeddingState, ImageInputState,
+        ImagePreprocessing, ImageSize, InputType, LateInteractionState, MultimodalEmbeddingState,
+        MultimodalInputState, ORTSessionBuilder, ObjectDetectionState, SentenceEmbeddingState,
+        TaskType, TextInputState,
     },
+    services::Inference,
 };
+use bytes::Bytes;
 use ort::session::Session;
 use parking_lot::Mutex;
 use std::str::FromStr;
@@ -18,6 +23,7 @@ const EMBEDDING_DIR: &str = "../models/embedding";
 const SEQUENCE_CLASSIFICATION_DIR: &str = "../models/sequence_classification";
 const TOKEN_CLASSIFICATION_DIR: &str = "../models/token_classification";
 const IMAGE_CLASSIFICATION_DIR: &str = "../models/image_classification";
+const AUDIO_CLASSIFICATION_DIR: &str = "../models/audio_classification";
 
 pub fn get_state<'a, T: ModelTypeSpec + InputType + TaskType>(dir: &'a str) -> AppState<T>
 where
@@ -41,7 +47,10 @@ where
     let model_task_state =
         <T as TaskType>::State::try_from(dir).expect("could not load model task state from file");
 
-    EncoderfileState::new(config, session, model_input_state, model_task_state).into()
+    EncoderfileState::new(config, session, model_input_state, model_task_state)
+        .with_inputs(&Default::default())
+        .expect("could not bind model inputs")
+        .into()
 }
 
 pub trait TaskTypeFromFile: TaskType {
@@ -93,11 +102,25 @@ fn get_image_input_state(dir: &str) -> Result<ImageInputState, anyhow::Error> {
                 width: config_state.image_size,
                 height: config_state.image_size,
                 shortest_edge: None,
+                longest_edge: None,
             })),
         },
     })
 }
 
+fn get_audio_input_state(dir: &str) -> Result<AudioInputState, anyhow::Error> {
+    let preproc_reader = get_preproc_reader(dir);
+    let feature_extractor: AudioFeatureExtractor = serde_json::from_reader(preproc_reader)?;
+    Ok(AudioInputState { feature_extractor })
+}
+
+fn get_multimodal_input_state(dir: &str) -> Result<MultimodalInputState, anyhow::Error> {
+    Ok(MultimodalInputState {
+        text: get_text_input_state(dir)?,
+        image: get_image_input_state(dir)?,
+    })
+}
+
 macro_rules! state_impl {
     ($input_type:ty, $state_fun:ident) => {
         impl TryFrom<&str> for $input_type {
@@ -112,8 +135,18 @@ macro_rules! state_impl {
 
 state_impl!(TextInputState, get_text_input_state);
 state_impl!(ImageInputState, get_image_input_state);
+state_impl!(AudioInputState, get_audio_input_state);
+state_impl!(MultimodalInputState, get_multimodal_input_state);
 state_impl!(ClassifierState, get_class_task_state);
 state_impl!(FeatureExtractorState, get_feature_task_state);
+state_impl!(SentenceEmbeddingState, get_sentence_embedding_task_state);
+state_impl!(LateInteractionState, get_late_interaction_task_state);
+state_impl!(ImageEmbeddingState, get_image_embedding_task_state);
+state_impl!(ObjectDetectionState, get_object_detection_task_state);
+state_impl!(
+    MultimodalEmbeddingState,
+    get_multimodal_embedding_task_state
+);
 
 // Task types
 fn get_class_task_state(dir: &str) -> Result<ClassifierState, anyhow::Error> {
@@ -126,6 +159,47 @@ fn get_feature_task_state(_dir: &str) -> Result<FeatureExtractorState, anyhow::E
     Ok(FeatureExtractorState {})
 }
 
+fn get_sentence_embedding_task_state(_dir: &str) -> Result<SentenceEmbeddingState, anyhow::Error> {
+    Ok(SentenceEmbeddingState { corpus: None })
+}
+
+fn get_late_interaction_task_state(dir: &str) -> Result<LateInteractionState, anyhow::Error> {
+    let reader = get_config_reader(dir);
+    let model_config: crate::common::ModelConfig = serde_json::from_reader(reader)?;
+    Ok(LateInteractionState {
+        config: model_config.late_interaction.unwrap_or_default(),
+        projection: None,
+    })
+}
+
+fn get_image_embedding_task_state(dir: &str) -> Result<ImageEmbeddingState, anyhow::Error> {
+    let reader = get_config_reader(dir);
+    let model_config: crate::common::ModelConfig = serde_json::from_reader(reader)?;
+    Ok(ImageEmbeddingState {
+        config: model_config.image_embedding.unwrap_or_default(),
+    })
+}
+
+fn get_object_detection_task_state(dir: &str) -> Result<ObjectDetectionState, anyhow::Error> {
+    let labels = get_class_task_state(dir)?;
+    let reader = get_config_reader(dir);
+    let model_config: crate::common::ModelConfig = serde_json::from_reader(reader)?;
+    Ok(ObjectDetectionState {
+        labels,
+        config: model_config.object_detection.unwrap_or_default(),
+    })
+}
+
+fn get_multimodal_embedding_task_state(
+    dir: &str,
+) -> Result<MultimodalEmbeddingState, anyhow::Error> {
+    let reader = get_config_reader(dir);
+    let model_config: crate::common::ModelConfig = serde_json::from_reader(reader)?;
+    Ok(MultimodalEmbeddingState {
+        config: model_config.multimodal_embedding.unwrap_or_default(),
+    })
+}
+
 pub fn embedding_state() -> AppState<model_type::Embedding> {
     get_state(EMBEDDING_DIR)
 }
@@ -134,6 +208,84 @@ pub fn sentence_embedding_state() -> AppState<model_type::SentenceEmbedding> {
     get_state(EMBEDDING_DIR)
 }
 
+/// Sentence embedding state with a small corpus embedded by the model itself.
+pub fn sentence_embedding_corpus_state() -> AppState<model_type::SentenceEmbedding> {
+    let state = sentence_embedding_state();
+
+    let documents: Vec<CorpusDocument> = [
+        ("cats", "Cats are small domesticated felines that purr."),
+        (
+            "rust",
+            "Rust is a systems programming language focused on safety.",
+        ),
+        ("tea", "Green tea is brewed from unoxidized tea leaves."),
+    ]
+    .into_iter()
+    .map(|(id, text)| CorpusDocument {
+        id: id.to_string(),
+        text: text.to_string(),
+        metadata: Some([("source".to_string(), "test".to_string())].into()),
+    })
+    .collect();
+
+    let request = SentenceEmbeddingRequest {
+        inputs: documents.iter().map(|d| d.text.clone()).collect(),
+        options: InferenceOptions {
+            normalize: Some(true),
+            ..Default::default()
+        },
+        metadata: None,
+    };
+
+    let embeddings = state
+        .inference(request)
+        .expect("Failed to embed corpus")
+        .results
+        .into_iter()
+        .map(|r| {
+            r.embedding
+                .as_float()
+                .expect("Expected float embeddings")
+                .to_vec()
+        })
+        .collect();
+
+    let corpus = CorpusState::try_from(Corpus {
+        documents,
+        embeddings,
+        index: CorpusIndex::Flat,
+    })
+    .expect("Failed to load corpus");
+
+    let mut state = std::sync::Arc::into_inner(state).expect("State is shared");
+    state.task_state.corpus = Some(corpus);
+    state.into()
+}
+
+/// Embedding state with E5-style `query` and `passage` prompts, `query` by default.
+pub fn embedding_prompt_state() -> AppState<model_type::Embedding> {
+    let mut state = std::sync::Arc::into_inner(embedding_state()).expect("State is shared");
+    state.model_input_state.model_config.prompts = Some(
+        [
+            ("query".to_string(), "query: ".to_string()),
+            ("passage".to_string(), "passage: ".to_string()),
+        ]
+        .into(),
+    );
+    state.model_input_state.model_config.default_prompt_name = Some("query".to_string());
+    state.into()
+}
+
+/// Sparse embedding state over the token classifier, whose per-token logits stand in for the
+/// vocabulary logits of a SPLADE model.
+pub fn sparse_embedding_state() -> AppState<model_type::SparseEmbedding> {
+    get_state(TOKEN_CLASSIFICATION_DIR)
+}
+
+pub fn late_interaction_state() -> AppState<model_type::LateInteraction> {
+    get_state(EMBEDDING_DIR)
+}
+
 pub fn sequence_classification_state() -> AppState<model_type::SequenceClassification> {
     get_state(SEQUENCE_CLASSIFICATION_DIR)
 }
@@ -146,6 +298,102 @@ pub fn image_classification_state() -> AppState<model_type::ImageClassification>
     get_state(IMAGE_CLASSIFICATION_DIR)
 }
 
+/// Object detection state over the image classifier, which returns no boxes, so only
+/// requests rejected before or after running the model can be served.
+pub fn object_detection_state() -> AppState<model_type::ObjectDetection> {
+    get_state(IMAGE_CLASSIFICATION_DIR)
+}
+
+/// Image segmentation state over the image classifier, which returns no per-pixel logits.
+pub fn image_segmentation_state() -> AppState<model_type::ImageSegmentation> {
+    get_state(IMAGE_CLASSIFICATION_DIR)
+}
+
+/// Audio classification state over a waveform classifier whose logits are
+/// `[e - 0.05, 0.05 - e]` for the labels `loud` and `quiet`, where `e` is the mean squared
+/// sample of the clip.
+pub fn audio_classification_state() -> AppState<model_type::AudioClassification> {
+    get_state(AUDIO_CLASSIFICATION_DIR)
+}
+
+/// Encodes interleaved samples in `[-1, 1]` as a 16-bit PCM WAV file.
+pub fn wav_bytes(samples: &[f32], sampling_rate: u32, channels: u16) -> Bytes {
+    let data_len = (samples.len() * 2) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sampling_rate.to_le_bytes());
+    out.extend_from_slice(&(sampling_rate * channels as u32 * 2).to_le_bytes());
+    out.extend_from_slice(&(channels * 2).to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        out.extend_from_slice(&((s * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    Bytes::from(out)
+}
+
+/// Dual-tower state embedding texts with the text classifier and images with the image
+/// classifier. The towers embed into spaces of different dimensions, so they can be used
+/// separately but not compared.
+pub fn multimodal_embedding_state() -> AppState<model_type::MultimodalEmbedding> {
+    let config = Config {
+        name: "my-model".to_string(),
+        version: "0.0.1".to_string(),
+        model_type: model_type::MultimodalEmbedding::enum_val(),
+        transform: None,
+        lua_libs: None,
+    };
+
+    let model_input_state = MultimodalInputState {
+        text: get_text_input_state(SEQUENCE_CLASSIFICATION_DIR)
+            .expect("could not load model input state from file"),
+        image: get_image_input_state(IMAGE_CLASSIFICATION_DIR)
+            .expect("could not load model input state from file"),
+    };
+
+    let mut state = EncoderfileState::new(
+        config,
+        get_model(SEQUENCE_CLASSIFICATION_DIR),
+        model_input_state,
+        MultimodalEmbeddingState {
+            config: Default::default(),
+        },
+    )
+    .with_vision_session(Some(get_model(IMAGE_CLASSIFICATION_DIR).into_inner()))
+    .with_inputs(&Default::default())
+    .expect("could not bind model inputs");
+
+    state.outputs = ModelOutputs {
+        names: [
+            ("text_embeds".to_string(), "logits".to_string()),
+            ("image_embeds".to_string(), "logits".to_string()),
+        ]
+        .into(),
+        extra: vec![],
+    };
+    state.into()
+}
+
+/// Image embedding state over the image classifier, embedding images with its logits.
+pub fn image_embedding_state() -> AppState<model_type::ImageEmbedding> {
+    let mut state = std::sync::Arc::into_inner(get_state::<model_type::ImageEmbedding>(
+        IMAGE_CLASSIFICATION_DIR,
+    ))
+    .expect("State is shared");
+    state.outputs = ModelOutputs {
+        names: [("pooler_output".to_string(), "logits".to_string())].into(),
+        extra: vec![],
+    };
+    state.task_state.config.output = Some(ImageEmbeddingOutput::PoolerOutput);
+    state.into()
+}
+
 fn get_tokenizer(dir: &str) -> crate::runtime::TokenizerService {
     let tokenizer_str = std::fs::read_to_string(format!("{}/{}", dir, "tokenizer.json"))
         .expect("Tokenizer json not found");
@@ -14,4 +14,13 @@ pub enum ApiError {
 
     #[error("Lua Error: {0}")]
     LuaError(String),
+
+    #[error(
+        "Invalid Input: input {index} has {num_tokens} tokens, more than the maximum of {max_length}"
+    )]
+    InputTooLong {
+        index: usize,
+        num_tokens: usize,
+        max_length: usize,
+    },
 }
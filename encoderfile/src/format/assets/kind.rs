@@ -45,6 +45,18 @@ pub enum AssetKind {
 
     /// Optional image preprocessing configuration.
     ImagePreprocessor,
+
+    /// Optional linear projection applied to token vectors (late interaction models).
+    Projection,
+
+    /// Audio feature extractor configuration required for audio models.
+    AudioFeatureExtractor,
+
+    /// Vision tower weights of dual-tower (text and image) models.
+    VisionModelWeights,
+
+    /// Optional searchable documents and their embeddings (sentence embedding models).
+    Corpus,
 }
 
 impl AssetKind {
@@ -54,6 +66,10 @@ impl AssetKind {
         AssetKind::ModelConfig,
         AssetKind::Tokenizer,
         AssetKind::ImagePreprocessor,
+        AssetKind::Projection,
+        AssetKind::AudioFeatureExtractor,
+        AssetKind::VisionModelWeights,
+        AssetKind::Corpus,
     ];
 }
 
@@ -80,6 +96,23 @@ pub trait AssetPolicySpec: ModelTypeSpec + InputType + TaskType {
                 AssetKind::ModelConfig,
                 AssetKind::ImagePreprocessor,
             ],
+            (Input::Audio, Task::Classification) => &[
+                AssetKind::ModelWeights,
+                AssetKind::ModelConfig,
+                AssetKind::AudioFeatureExtractor,
+            ],
+            (Input::Audio, Task::FeatureExtraction) => &[
+                AssetKind::ModelWeights,
+                AssetKind::ModelConfig,
+                AssetKind::AudioFeatureExtractor,
+            ],
+            (Input::Multimodal, _) => &[
+                AssetKind::ModelWeights,
+                AssetKind::ModelConfig,
+                AssetKind::Tokenizer,
+                AssetKind::ImagePreprocessor,
+                AssetKind::VisionModelWeights,
+            ],
         }
     }
     fn optional_assets() -> &'static [AssetKind] {
@@ -88,6 +121,9 @@ pub trait AssetPolicySpec: ModelTypeSpec + InputType + TaskType {
             (Input::Text, Task::FeatureExtraction) => &[AssetKind::Transform],
             (Input::Image, Task::Classification) => &[AssetKind::Transform],
             (Input::Image, Task::FeatureExtraction) => &[AssetKind::Transform],
+            (Input::Audio, Task::Classification) => &[AssetKind::Transform],
+            (Input::Audio, Task::FeatureExtraction) => &[AssetKind::Transform],
+            (Input::Multimodal, _) => &[AssetKind::Transform],
         }
     }
 }
@@ -97,10 +133,34 @@ macro_rules! asset_policy_spec {
     (Encoder, $model_type:ident) => {
         impl AssetPolicySpec for crate::common::model_type::$model_type {}
     };
+    // Encoders with additional optional assets
+    (Encoder, $model_type:ident, [$($optional:expr),* $(,)?]) => {
+        impl AssetPolicySpec for crate::common::model_type::$model_type {
+            fn optional_assets() -> &'static [AssetKind] {
+                &[$($optional),*]
+            }
+        }
+    };
 }
 
 asset_policy_spec!(Encoder, Embedding);
 asset_policy_spec!(Encoder, SequenceClassification);
 asset_policy_spec!(Encoder, TokenClassification);
-asset_policy_spec!(Encoder, SentenceEmbedding);
+asset_policy_spec!(
+    Encoder,
+    SentenceEmbedding,
+    [AssetKind::Transform, AssetKind::Corpus]
+);
+asset_policy_spec!(Encoder, SparseEmbedding);
+asset_policy_spec!(
+    Encoder,
+    LateInteraction,
+    [AssetKind::Transform, AssetKind::Projection]
+);
 asset_policy_spec!(Encoder, ImageClassification);
+asset_policy_spec!(Encoder, ImageEmbedding);
+asset_policy_spec!(Encoder, ObjectDetection);
+asset_policy_spec!(Encoder, ImageSegmentation);
+asset_policy_spec!(Encoder, AudioClassification);
+asset_policy_spec!(Encoder, AudioEmbedding);
+asset_policy_spec!(Encoder, MultimodalEmbedding);
@@ -3,8 +3,9 @@ use anyhow::{Result, bail};
 
 use crate::{
     common::model_type::{
-        Embedding, ImageClassification, ModelType, SentenceEmbedding, SequenceClassification,
-        TokenClassification,
+        AudioClassification, AudioEmbedding, Embedding, ImageClassification, ImageEmbedding,
+        ImageSegmentation, LateInteraction, ModelType, MultimodalEmbedding, ObjectDetection,
+        SentenceEmbedding, SequenceClassification, SparseEmbedding, TokenClassification,
     },
     format::{
         assets::{AssetPlan, AssetPolicySpec},
@@ -86,7 +87,15 @@ impl EncoderfileCodec {
             }
             ModelType::TokenClassification => Self::validate_assets::<TokenClassification>(plan)?,
             ModelType::SentenceEmbedding => Self::validate_assets::<SentenceEmbedding>(plan)?,
+            ModelType::SparseEmbedding => Self::validate_assets::<SparseEmbedding>(plan)?,
+            ModelType::LateInteraction => Self::validate_assets::<LateInteraction>(plan)?,
             ModelType::ImageClassification => Self::validate_assets::<ImageClassification>(plan)?,
+            ModelType::ImageEmbedding => Self::validate_assets::<ImageEmbedding>(plan)?,
+            ModelType::ObjectDetection => Self::validate_assets::<ObjectDetection>(plan)?,
+            ModelType::ImageSegmentation => Self::validate_assets::<ImageSegmentation>(plan)?,
+            ModelType::AudioClassification => Self::validate_assets::<AudioClassification>(plan)?,
+            ModelType::AudioEmbedding => Self::validate_assets::<AudioEmbedding>(plan)?,
+            ModelType::MultimodalEmbedding => Self::validate_assets::<MultimodalEmbedding>(plan)?,
         };
 
         let model_type: crate::generated::metadata::ModelType = model_type.into();
@@ -102,6 +111,10 @@ impl EncoderfileCodec {
             transform: None,
             tokenizer: None,
             image_preprocessor: None,
+            projection: None,
+            audio_feature_extractor: None,
+            vision_weights: None,
+            corpus: None,
         };
 
         // Populate artifacts with length + hash
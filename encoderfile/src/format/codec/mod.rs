@@ -42,6 +42,10 @@ impl EncoderfileManifest {
             AssetKind::Transform => &mut self.transform,
             AssetKind::Tokenizer => &mut self.tokenizer,
             AssetKind::ImagePreprocessor => &mut self.image_preprocessor,
+            AssetKind::Projection => &mut self.projection,
+            AssetKind::AudioFeatureExtractor => &mut self.audio_feature_extractor,
+            AssetKind::VisionModelWeights => &mut self.vision_weights,
+            AssetKind::Corpus => &mut self.corpus,
         }
     }
 
@@ -52,6 +56,10 @@ impl EncoderfileManifest {
             AssetKind::Transform => &self.transform,
             AssetKind::Tokenizer => &self.tokenizer,
             AssetKind::ImagePreprocessor => &self.image_preprocessor,
+            AssetKind::Projection => &self.projection,
+            AssetKind::AudioFeatureExtractor => &self.audio_feature_extractor,
+            AssetKind::VisionModelWeights => &self.vision_weights,
+            AssetKind::Corpus => &self.corpus,
         }
     }
 
@@ -77,6 +85,10 @@ mod tests {
             transform: None,
             tokenizer: None,
             image_preprocessor: None,
+            projection: None,
+            audio_feature_extractor: None,
+            vision_weights: None,
+            corpus: None,
         }
     }
 
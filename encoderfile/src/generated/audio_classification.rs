@@ -0,0 +1,28 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.audio_classification");
+
+impl From<AudioClassificationRequest> for common::AudioClassificationRequest {
+    fn from(val: AudioClassificationRequest) -> Self {
+        Self {
+            audios: val.inputs.into_iter().map(|input| input.into()).collect(),
+            threshold: val.threshold,
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::AudioClassificationResponse> for AudioClassificationResponse {
+    fn from(val: common::AudioClassificationResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            threshold: val.threshold,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
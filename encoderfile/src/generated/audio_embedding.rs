@@ -0,0 +1,34 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.audio_embedding");
+
+impl From<AudioEmbeddingRequest> for common::AudioEmbeddingRequest {
+    fn from(val: AudioEmbeddingRequest) -> Self {
+        Self {
+            audios: val.inputs.into_iter().map(|input| input.into()).collect(),
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::AudioEmbeddingResponse> for AudioEmbeddingResponse {
+    fn from(val: common::AudioEmbeddingResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::AudioEmbedding> for AudioEmbedding {
+    fn from(val: common::AudioEmbedding) -> Self {
+        Self {
+            embedding: val.embedding,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.audio_types");
+
+impl From<AudioInput> for common::AudioInfo {
+    fn from(val: AudioInput) -> Self {
+        common::AudioInfo {
+            audio_bytes: bytes::Bytes::from(val.audio),
+        }
+    }
+}
+
+impl From<common::AudioInfo> for AudioInput {
+    fn from(val: common::AudioInfo) -> Self {
+        AudioInput {
+            audio: val.audio_bytes.to_vec(),
+        }
+    }
+}
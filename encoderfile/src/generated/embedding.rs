@@ -2,10 +2,16 @@ use crate::common;
 
 tonic::include_proto!("encoderfile.embedding");
 
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
 impl From<EmbeddingRequest> for common::EmbeddingRequest {
     fn from(val: EmbeddingRequest) -> Self {
         Self {
             inputs: val.inputs,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
             metadata: Some(val.metadata),
         }
     }
@@ -25,6 +31,14 @@ impl From<common::TokenEmbeddingSequence> for TokenEmbeddingSequence {
     fn from(val: common::TokenEmbeddingSequence) -> Self {
         Self {
             embeddings: val.embeddings.into_iter().map(|i| i.into()).collect(),
+            num_tokens: val.num_tokens as u32,
+            truncated: val.truncated,
+            outputs: val
+                .outputs
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, output)| (name, output.into()))
+                .collect(),
         }
     }
 }
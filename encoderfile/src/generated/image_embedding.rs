@@ -0,0 +1,42 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.image_embedding");
+
+impl From<ImageEmbeddingRequest> for common::ImageEmbeddingRequest {
+    fn from(val: ImageEmbeddingRequest) -> Self {
+        let images = val
+            .inputs
+            .into_iter()
+            .map(|input| common::ImageInfo {
+                image_format: image::guess_format(&input.image).unwrap_or(image::ImageFormat::Png),
+                image_bytes: bytes::Bytes::from(input.image),
+            })
+            .collect();
+        Self {
+            images,
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::ImageEmbeddingResponse> for ImageEmbeddingResponse {
+    fn from(val: common::ImageEmbeddingResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::ImageEmbedding> for ImageEmbedding {
+    fn from(val: common::ImageEmbedding) -> Self {
+        Self {
+            embedding: val.embedding,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{common, generated::image_types::ImageLabelScore};
+
+tonic::include_proto!("encoderfile.image_segmentation");
+
+impl From<ImageSegmentationRequest> for common::ImageSegmentationRequest {
+    fn from(val: ImageSegmentationRequest) -> Self {
+        let images = val
+            .images
+            .into_iter()
+            .map(|input| common::ImageInfo {
+                image_format: image::guess_format(&input.image).unwrap_or(image::ImageFormat::Png),
+                image_bytes: bytes::Bytes::from(input.image),
+            })
+            .collect();
+        Self {
+            images,
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::ImageSegmentationResponse> for ImageSegmentationResponse {
+    fn from(val: common::ImageSegmentationResponse) -> Self {
+        Self {
+            segments_batch: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::ImageSegmentationResult> for ImageSegments {
+    fn from(val: common::ImageSegmentationResult) -> Self {
+        Self {
+            segments: val.segments.into_iter().map(|i| i.into()).collect(),
+        }
+    }
+}
+
+impl From<common::ImageSegment> for ImageSegment {
+    fn from(val: common::ImageSegment) -> Self {
+        Self {
+            label: Some(ImageLabelScore {
+                label: val.label,
+                score: Some(val.score),
+            }),
+            mask: Some(val.mask.into()),
+        }
+    }
+}
+
+impl From<common::SegmentMask> for SegmentMask {
+    fn from(val: common::SegmentMask) -> Self {
+        Self {
+            width: val.width,
+            height: val.height,
+            counts: val.counts,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.late_interaction");
+
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
+impl From<LateInteractionRequest> for common::LateInteractionRequest {
+    fn from(val: LateInteractionRequest) -> Self {
+        Self {
+            inputs: val.inputs,
+            is_query: val.is_query,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::LateInteractionResponse> for LateInteractionResponse {
+    fn from(val: common::LateInteractionResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::LateInteractionEmbedding> for LateInteractionEmbedding {
+    fn from(val: common::LateInteractionEmbedding) -> Self {
+        Self {
+            n_tokens: val.n_tokens as u32,
+            dim: val.dim as u32,
+            values: val.values,
+            num_tokens: val.num_tokens as u32,
+            truncated: val.truncated,
+        }
+    }
+}
+
+impl From<LateInteractionScoreRequest> for common::LateInteractionScoreRequest {
+    fn from(val: LateInteractionScoreRequest) -> Self {
+        Self {
+            query: val.query,
+            documents: val.documents,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::LateInteractionScoreResponse> for LateInteractionScoreResponse {
+    fn from(val: common::LateInteractionScoreResponse) -> Self {
+        Self {
+            scores: val.scores,
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
@@ -8,6 +8,48 @@ impl From<common::GetModelMetadataResponse> for GetModelMetadataResponse {
             model_id: val.model_id,
             model_type: ModelType::from(val.model_type).into(),
             id2label: val.id2label.unwrap_or_default(),
+            default_options: val.default_options.map(|o| o.into()),
+            dimensions: val
+                .dimensions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|d| d as u32)
+                .collect(),
+            prompts: val.prompts.unwrap_or_default(),
+            default_prompt_name: val.default_prompt_name,
+            calibration: val.calibration.map(|c| c.into()),
+            label_descriptions: val.label_descriptions.unwrap_or_default(),
+            label_thresholds: val.label_thresholds.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::Calibration> for Calibration {
+    fn from(val: common::Calibration) -> Self {
+        let method = match val {
+            common::Calibration::Temperature { temperature } => {
+                calibration::Method::Temperature(TemperatureCalibration { temperature })
+            }
+            common::Calibration::PerClassTemperature { temperatures } => {
+                calibration::Method::PerClassTemperature(PerClassTemperatureCalibration {
+                    temperatures,
+                })
+            }
+            common::Calibration::Platt { slopes, intercepts } => {
+                calibration::Method::Platt(PlattCalibration { slopes, intercepts })
+            }
+            common::Calibration::Isotonic { curves } => {
+                calibration::Method::Isotonic(IsotonicCalibration {
+                    curves: curves
+                        .into_iter()
+                        .map(|c| IsotonicCurve { x: c.x, y: c.y })
+                        .collect(),
+                })
+            }
+        };
+
+        Self {
+            method: Some(method),
         }
     }
 }
@@ -19,7 +61,15 @@ impl From<common::model_type::ModelType> for ModelType {
             common::model_type::ModelType::SequenceClassification => Self::SequenceClassification,
             common::model_type::ModelType::TokenClassification => Self::TokenClassification,
             common::model_type::ModelType::SentenceEmbedding => Self::SentenceEmbedding,
+            common::model_type::ModelType::SparseEmbedding => Self::SparseEmbedding,
+            common::model_type::ModelType::LateInteraction => Self::LateInteraction,
             common::model_type::ModelType::ImageClassification => Self::ImageClassification,
+            common::model_type::ModelType::ImageEmbedding => Self::ImageEmbedding,
+            common::model_type::ModelType::ObjectDetection => Self::ObjectDetection,
+            common::model_type::ModelType::ImageSegmentation => Self::ImageSegmentation,
+            common::model_type::ModelType::AudioClassification => Self::AudioClassification,
+            common::model_type::ModelType::AudioEmbedding => Self::AudioEmbedding,
+            common::model_type::ModelType::MultimodalEmbedding => Self::MultimodalEmbedding,
         }
     }
 }
@@ -33,7 +83,15 @@ impl From<ModelType> for common::model_type::ModelType {
             }
             ModelType::TokenClassification => common::model_type::ModelType::TokenClassification,
             ModelType::SentenceEmbedding => common::model_type::ModelType::SentenceEmbedding,
+            ModelType::SparseEmbedding => common::model_type::ModelType::SparseEmbedding,
+            ModelType::LateInteraction => common::model_type::ModelType::LateInteraction,
             ModelType::ImageClassification => common::model_type::ModelType::ImageClassification,
+            ModelType::ImageEmbedding => common::model_type::ModelType::ImageEmbedding,
+            ModelType::ObjectDetection => common::model_type::ModelType::ObjectDetection,
+            ModelType::ImageSegmentation => common::model_type::ModelType::ImageSegmentation,
+            ModelType::AudioClassification => common::model_type::ModelType::AudioClassification,
+            ModelType::AudioEmbedding => common::model_type::ModelType::AudioEmbedding,
+            ModelType::MultimodalEmbedding => common::model_type::ModelType::MultimodalEmbedding,
             ModelType::Unspecified => {
                 unreachable!("Unspecified model type. This should not happen.")
             }
@@ -1,9 +1,21 @@
+pub mod audio_classification;
+pub mod audio_embedding;
+pub mod audio_types;
 pub mod embedding;
 pub mod image_classification;
+pub mod image_embedding;
+pub mod image_segmentation;
 pub mod image_types;
+pub mod late_interaction;
 pub mod manifest;
 pub mod metadata;
+pub mod multimodal_embedding;
+pub mod object_detection;
+pub mod options;
+pub mod outputs;
 pub mod sentence_embedding;
 pub mod sequence_classification;
+pub mod sparse_embedding;
 pub mod token;
 pub mod token_classification;
+pub mod tokenize;
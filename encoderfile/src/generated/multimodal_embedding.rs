@@ -0,0 +1,77 @@
+use crate::{common, generated::image_types::ImageInput};
+
+tonic::include_proto!("encoderfile.multimodal_embedding");
+
+fn images_from_inputs(inputs: Vec<ImageInput>) -> Vec<common::ImageInfo> {
+    inputs
+        .into_iter()
+        .map(|input| common::ImageInfo {
+            image_format: image::guess_format(&input.image).unwrap_or(image::ImageFormat::Png),
+            image_bytes: bytes::Bytes::from(input.image),
+        })
+        .collect()
+}
+
+impl From<MultimodalSimilarityRequest> for common::MultimodalSimilarityRequest {
+    fn from(val: MultimodalSimilarityRequest) -> Self {
+        Self {
+            images: images_from_inputs(val.inputs),
+            labels: val.labels,
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::MultimodalSimilarityResponse> for MultimodalSimilarityResponse {
+    fn from(val: common::MultimodalSimilarityResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<MultimodalTextEmbeddingRequest> for common::MultimodalTextEmbeddingRequest {
+    fn from(val: MultimodalTextEmbeddingRequest) -> Self {
+        Self {
+            inputs: val.inputs,
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<MultimodalImageEmbeddingRequest> for common::MultimodalImageEmbeddingRequest {
+    fn from(val: MultimodalImageEmbeddingRequest) -> Self {
+        Self {
+            images: images_from_inputs(val.inputs),
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::MultimodalEmbeddingResponse> for MultimodalEmbeddingResponse {
+    fn from(val: common::MultimodalEmbeddingResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::MultimodalEmbedding> for MultimodalEmbedding {
+    fn from(val: common::MultimodalEmbedding) -> Self {
+        Self {
+            embedding: val.embedding,
+        }
+    }
+}
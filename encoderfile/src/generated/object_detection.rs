@@ -0,0 +1,57 @@
+use crate::{common, generated::image_types::ImageLabelScore};
+
+tonic::include_proto!("encoderfile.object_detection");
+
+impl From<ObjectDetectionRequest> for common::ObjectDetectionRequest {
+    fn from(val: ObjectDetectionRequest) -> Self {
+        let images = val
+            .inputs
+            .into_iter()
+            .map(|input| common::ImageInfo {
+                image_format: image::guess_format(&input.image).unwrap_or(image::ImageFormat::Png),
+                image_bytes: bytes::Bytes::from(input.image),
+            })
+            .collect();
+        Self {
+            images,
+            metadata: if val.metadata.is_empty() {
+                None
+            } else {
+                Some(val.metadata)
+            },
+        }
+    }
+}
+
+impl From<common::ObjectDetectionResponse> for ObjectDetectionResponse {
+    fn from(val: common::ObjectDetectionResponse) -> Self {
+        Self {
+            boxes: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::ObjectDetectionResult> for ImageBoundingBoxes {
+    fn from(val: common::ObjectDetectionResult) -> Self {
+        Self {
+            r#box: val.objects.into_iter().map(|i| i.into()).collect(),
+        }
+    }
+}
+
+impl From<common::DetectedObject> for ImageBoundingBox {
+    fn from(val: common::DetectedObject) -> Self {
+        Self {
+            label: Some(ImageLabelScore {
+                label: val.label,
+                score: Some(val.score),
+            }),
+            xmin: val.bounding_box.xmin,
+            xmax: val.bounding_box.xmax,
+            ymin: val.bounding_box.ymin,
+            ymax: val.bounding_box.ymax,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.options");
+
+impl From<InferenceOptions> for common::InferenceOptions {
+    fn from(val: InferenceOptions) -> Self {
+        let truncate = val.truncate.map(|truncate| match truncate {
+            inference_options::Truncate::TruncationEnabled(enabled) => {
+                common::Truncate::Enabled(enabled)
+            }
+            inference_options::Truncate::MaxLength(max_length) => {
+                common::Truncate::MaxLength(max_length as usize)
+            }
+        });
+
+        Self {
+            truncate,
+            normalize: val.normalize,
+            top_k: val.top_k.map(|k| k as usize),
+            return_logits: val.return_logits,
+            include_special_tokens: val.include_special_tokens,
+            return_offsets: val.return_offsets,
+            dimensions: val.dimensions.map(|d| d as usize),
+            encoding_format: val
+                .encoding_format
+                .and_then(|f| EncodingFormat::try_from(f).ok())
+                .and_then(|f| f.into()),
+            base64: val.base64,
+            prompt_name: val.prompt_name,
+            truncation: val
+                .truncation
+                .and_then(|t| TruncationMode::try_from(t).ok())
+                .and_then(|t| t.into()),
+            offset_mapping: val
+                .offset_mapping
+                .and_then(|m| OffsetMapping::try_from(m).ok())
+                .and_then(|m| m.into()),
+        }
+    }
+}
+
+impl From<common::InferenceOptions> for InferenceOptions {
+    fn from(val: common::InferenceOptions) -> Self {
+        let truncate = val.truncate.map(|truncate| match truncate {
+            common::Truncate::Enabled(enabled) => {
+                inference_options::Truncate::TruncationEnabled(enabled)
+            }
+            common::Truncate::MaxLength(max_length) => {
+                inference_options::Truncate::MaxLength(max_length as u32)
+            }
+        });
+
+        Self {
+            truncate,
+            normalize: val.normalize,
+            top_k: val.top_k.map(|k| k as u32),
+            return_logits: val.return_logits,
+            include_special_tokens: val.include_special_tokens,
+            return_offsets: val.return_offsets,
+            dimensions: val.dimensions.map(|d| d as u32),
+            encoding_format: val.encoding_format.map(|f| EncodingFormat::from(f) as i32),
+            base64: val.base64,
+            prompt_name: val.prompt_name,
+            truncation: val.truncation.map(|t| TruncationMode::from(t) as i32),
+            offset_mapping: val.offset_mapping.map(|m| OffsetMapping::from(m) as i32),
+        }
+    }
+}
+
+impl From<EncodingFormat> for Option<common::EncodingFormat> {
+    fn from(val: EncodingFormat) -> Self {
+        match val {
+            EncodingFormat::Unspecified => None,
+            EncodingFormat::Float => Some(common::EncodingFormat::Float),
+            EncodingFormat::Int8 => Some(common::EncodingFormat::Int8),
+            EncodingFormat::Uint8 => Some(common::EncodingFormat::Uint8),
+            EncodingFormat::Binary => Some(common::EncodingFormat::Binary),
+            EncodingFormat::Ubinary => Some(common::EncodingFormat::Ubinary),
+        }
+    }
+}
+
+impl From<common::EncodingFormat> for EncodingFormat {
+    fn from(val: common::EncodingFormat) -> Self {
+        match val {
+            common::EncodingFormat::Float => Self::Float,
+            common::EncodingFormat::Int8 => Self::Int8,
+            common::EncodingFormat::Uint8 => Self::Uint8,
+            common::EncodingFormat::Binary => Self::Binary,
+            common::EncodingFormat::Ubinary => Self::Ubinary,
+        }
+    }
+}
+
+impl From<TruncationMode> for Option<common::TruncationMode> {
+    fn from(val: TruncationMode) -> Self {
+        match val {
+            TruncationMode::Unspecified => None,
+            TruncationMode::Truncate => Some(common::TruncationMode::Truncate),
+            TruncationMode::Error => Some(common::TruncationMode::Error),
+        }
+    }
+}
+
+impl From<common::TruncationMode> for TruncationMode {
+    fn from(val: common::TruncationMode) -> Self {
+        match val {
+            common::TruncationMode::Truncate => Self::Truncate,
+            common::TruncationMode::Error => Self::Error,
+        }
+    }
+}
+
+impl From<OffsetMapping> for Option<common::OffsetMapping> {
+    fn from(val: OffsetMapping) -> Self {
+        match val {
+            OffsetMapping::Unspecified => None,
+            OffsetMapping::Byte => Some(common::OffsetMapping::Byte),
+            OffsetMapping::Char => Some(common::OffsetMapping::Char),
+            OffsetMapping::Utf16 => Some(common::OffsetMapping::Utf16),
+        }
+    }
+}
+
+impl From<common::OffsetMapping> for OffsetMapping {
+    fn from(val: common::OffsetMapping) -> Self {
+        match val {
+            common::OffsetMapping::Byte => Self::Byte,
+            common::OffsetMapping::Char => Self::Char,
+            common::OffsetMapping::Utf16 => Self::Utf16,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.outputs");
+
+impl From<common::OutputTensor> for OutputTensor {
+    fn from(val: common::OutputTensor) -> Self {
+        Self {
+            shape: val.shape.into_iter().map(|d| d as u32).collect(),
+            values: val.values,
+        }
+    }
+}
@@ -2,10 +2,16 @@ use crate::common;
 
 tonic::include_proto!("encoderfile.sentence_embedding");
 
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
 impl From<SentenceEmbeddingRequest> for common::SentenceEmbeddingRequest {
     fn from(val: SentenceEmbeddingRequest) -> Self {
         Self {
             inputs: val.inputs,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
             metadata: Some(val.metadata),
         }
     }
@@ -23,8 +29,110 @@ impl From<common::SentenceEmbeddingResponse> for SentenceEmbeddingResponse {
 
 impl From<common::SentenceEmbedding> for SentenceEmbedding {
     fn from(val: common::SentenceEmbedding) -> Self {
+        let (embedding, embedding_bytes) = match val.embedding {
+            common::EmbeddingValues::Float(values) => (values, Vec::new()),
+            common::EmbeddingValues::Int8(values) => {
+                (Vec::new(), values.into_iter().map(|v| v as u8).collect())
+            }
+            common::EmbeddingValues::Uint8(bytes) | common::EmbeddingValues::Base64(bytes) => {
+                (Vec::new(), bytes)
+            }
+        };
+
+        Self {
+            embedding,
+            embedding_bytes,
+            num_tokens: val.num_tokens as u32,
+            truncated: val.truncated,
+            outputs: val
+                .outputs
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, output)| (name, output.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<SimilarityRequest> for common::SimilarityRequest {
+    fn from(val: SimilarityRequest) -> Self {
+        Self {
+            sources: val.sources,
+            targets: (!val.targets.is_empty()).then_some(val.targets),
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::SimilarityResponse> for SimilarityResponse {
+    fn from(val: common::SimilarityResponse) -> Self {
+        Self {
+            rows: val
+                .similarities
+                .into_iter()
+                .map(|similarities| SimilarityRow { similarities })
+                .collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<DedupeRequest> for common::DedupeRequest {
+    fn from(val: DedupeRequest) -> Self {
+        Self {
+            inputs: val.inputs,
+            threshold: val.threshold,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::DedupeResponse> for DedupeResponse {
+    fn from(val: common::DedupeResponse) -> Self {
         Self {
-            embedding: val.embedding,
+            assignments: val.assignments.into_iter().map(|i| i as u32).collect(),
+            clusters: val
+                .clusters
+                .into_iter()
+                .map(|members| DedupeCluster {
+                    members: members.into_iter().map(|i| i as u32).collect(),
+                })
+                .collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<SearchRequest> for common::SearchRequest {
+    fn from(val: SearchRequest) -> Self {
+        Self {
+            query: val.query,
+            top_k: val.top_k.map(|k| k as usize),
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::SearchResponse> for SearchResponse {
+    fn from(val: common::SearchResponse) -> Self {
+        Self {
+            results: val
+                .results
+                .into_iter()
+                .map(|result| SearchResult {
+                    id: result.id,
+                    text: result.text,
+                    score: result.score,
+                    metadata: result.metadata.unwrap_or_default(),
+                })
+                .collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
         }
     }
 }
@@ -2,10 +2,17 @@ use crate::common;
 
 tonic::include_proto!("encoderfile.sequence_classification");
 
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
 impl From<SequenceClassificationRequest> for common::SequenceClassificationRequest {
     fn from(val: SequenceClassificationRequest) -> Self {
         Self {
             inputs: val.inputs,
+            threshold: val.threshold,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
             metadata: Some(val.metadata),
         }
     }
@@ -16,6 +23,7 @@ impl From<common::SequenceClassificationResponse> for SequenceClassificationResp
         Self {
             results: val.results.into_iter().map(|i| i.into()).collect(),
             model_id: val.model_id,
+            threshold: val.threshold,
             metadata: val.metadata.unwrap_or_default(),
         }
     }
@@ -28,6 +36,30 @@ impl From<common::SequenceClassificationResult> for SequenceClassificationResult
             scores: val.scores,
             predicted_index: val.predicted_index,
             predicted_label: val.predicted_label,
+            predicted_labels: val.predicted_labels,
+            top_labels: val
+                .top_labels
+                .unwrap_or_default()
+                .into_iter()
+                .map(|i| i.into())
+                .collect(),
+            num_tokens: val.num_tokens.map(|n| n as u32),
+            truncated: val.truncated,
+            outputs: val
+                .outputs
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, output)| (name, output.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<common::LabelScore> for LabelScore {
+    fn from(val: common::LabelScore) -> Self {
+        Self {
+            label: val.label,
+            score: val.score,
         }
     }
 }
@@ -0,0 +1,43 @@
+use crate::common;
+
+tonic::include_proto!("encoderfile.sparse_embedding");
+
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
+impl From<SparseEmbeddingRequest> for common::SparseEmbeddingRequest {
+    fn from(val: SparseEmbeddingRequest) -> Self {
+        Self {
+            inputs: val.inputs,
+            top_k: val.top_k.map(|k| k as usize),
+            threshold: val.threshold,
+            return_tokens: val.return_tokens,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::SparseEmbeddingResponse> for SparseEmbeddingResponse {
+    fn from(val: common::SparseEmbeddingResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::SparseEmbedding> for SparseEmbedding {
+    fn from(val: common::SparseEmbedding) -> Self {
+        Self {
+            indices: val.indices,
+            values: val.values,
+            tokens: val.tokens.unwrap_or_default(),
+            num_tokens: val.num_tokens as u32,
+            truncated: val.truncated,
+        }
+    }
+}
@@ -2,10 +2,20 @@ use crate::common;
 
 tonic::include_proto!("encoderfile.token_classification");
 
+pub use super::tokenize::{
+    CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+    TokenizeRequest, TokenizeResponse,
+};
+
 impl From<TokenClassificationRequest> for common::TokenClassificationRequest {
     fn from(val: TokenClassificationRequest) -> Self {
         Self {
+            aggregation_strategy: val.aggregation_strategy().into(),
             inputs: val.inputs,
+            words: (!val.words.is_empty())
+                .then(|| val.words.into_iter().map(|w| w.words).collect()),
+            ignore_labels: (!val.ignore_labels.is_empty()).then_some(val.ignore_labels),
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
             metadata: Some(val.metadata),
         }
     }
@@ -25,6 +35,37 @@ impl From<common::TokenClassificationResult> for TokenClassificationResult {
     fn from(val: common::TokenClassificationResult) -> Self {
         Self {
             tokens: val.tokens.into_iter().map(|i| i.into()).collect(),
+            entities: val
+                .entities
+                .unwrap_or_default()
+                .into_iter()
+                .map(|i| i.into())
+                .collect(),
+            num_tokens: val.num_tokens as u32,
+            truncated: val.truncated,
+            outputs: val
+                .outputs
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, output)| (name, output.into()))
+                .collect(),
+            words: val
+                .words
+                .unwrap_or_default()
+                .into_iter()
+                .map(|i| i.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<common::WordClassification> for WordClassification {
+    fn from(val: common::WordClassification) -> Self {
+        Self {
+            word_index: val.word_index as u32,
+            word: val.word,
+            label: val.label,
+            score: val.score,
         }
     }
 }
@@ -39,3 +80,82 @@ impl From<common::TokenClassification> for TokenClassification {
         }
     }
 }
+
+impl From<common::TokenClassificationEntity> for TokenClassificationEntity {
+    fn from(val: common::TokenClassificationEntity) -> Self {
+        Self {
+            entity_group: val.entity_group,
+            score: val.score,
+            word: val.word,
+            start: val.start as u32,
+            end: val.end as u32,
+        }
+    }
+}
+
+impl From<AggregationStrategy> for Option<common::AggregationStrategy> {
+    fn from(val: AggregationStrategy) -> Self {
+        match val {
+            AggregationStrategy::Unspecified => None,
+            AggregationStrategy::None => Some(common::AggregationStrategy::None),
+            AggregationStrategy::Simple => Some(common::AggregationStrategy::Simple),
+            AggregationStrategy::First => Some(common::AggregationStrategy::First),
+            AggregationStrategy::Average => Some(common::AggregationStrategy::Average),
+            AggregationStrategy::Max => Some(common::AggregationStrategy::Max),
+        }
+    }
+}
+
+impl From<RedactRequest> for common::RedactRequest {
+    fn from(val: RedactRequest) -> Self {
+        let mode = match val.mode() {
+            RedactionMode::Unspecified => None,
+            RedactionMode::Label => Some(common::RedactionMode::Label),
+            RedactionMode::Mask => Some(common::RedactionMode::Mask),
+            RedactionMode::Pseudonym => Some(common::RedactionMode::Pseudonym),
+        };
+
+        Self {
+            mode,
+            aggregation_strategy: val.aggregation_strategy().into(),
+            inputs: val.inputs,
+            mask_char: val.mask_char,
+            labels: (!val.labels.is_empty()).then_some(val.labels),
+            ignore_labels: (!val.ignore_labels.is_empty()).then_some(val.ignore_labels),
+            threshold: val.threshold,
+            options: val.options.map(|o| o.into()).unwrap_or_default(),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::RedactResponse> for RedactResponse {
+    fn from(val: common::RedactResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::Redaction> for Redaction {
+    fn from(val: common::Redaction) -> Self {
+        Self {
+            text: val.text,
+            spans: val.spans.into_iter().map(|i| i.into()).collect(),
+        }
+    }
+}
+
+impl From<common::RedactedSpan> for RedactedSpan {
+    fn from(val: common::RedactedSpan) -> Self {
+        Self {
+            entity_group: val.entity_group,
+            score: val.score,
+            start: val.start as u32,
+            end: val.end as u32,
+            replacement: val.replacement,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::options::OffsetMapping;
+use crate::common;
+
+tonic::include_proto!("encoderfile.tokenize");
+
+impl From<TokenizeRequest> for common::TokenizeRequest {
+    fn from(val: TokenizeRequest) -> Self {
+        let truncate = val.truncate.map(|truncate| match truncate {
+            tokenize_request::Truncate::TruncationEnabled(enabled) => {
+                common::Truncate::Enabled(enabled)
+            }
+            tokenize_request::Truncate::MaxLength(max_length) => {
+                common::Truncate::MaxLength(max_length as usize)
+            }
+        });
+
+        Self {
+            inputs: val.inputs,
+            truncate,
+            offset_mapping: val
+                .offset_mapping
+                .and_then(|m| OffsetMapping::try_from(m).ok())
+                .and_then(|m| m.into()),
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::TokenizeResponse> for TokenizeResponse {
+    fn from(val: common::TokenizeResponse) -> Self {
+        Self {
+            results: val.results.into_iter().map(|i| i.into()).collect(),
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<common::Tokenization> for Tokenization {
+    fn from(val: common::Tokenization) -> Self {
+        Self {
+            ids: val.ids,
+            tokens: val.tokens,
+            offsets: val
+                .offsets
+                .into_iter()
+                .map(|[start, end]| TokenOffsets {
+                    start: start as u32,
+                    end: end as u32,
+                })
+                .collect(),
+            special_tokens_mask: val.special_tokens_mask,
+            truncated: val.truncated,
+        }
+    }
+}
+
+impl From<DetokenizeRequest> for common::DetokenizeRequest {
+    fn from(val: DetokenizeRequest) -> Self {
+        Self {
+            inputs: val.inputs.into_iter().map(|i| i.ids).collect(),
+            skip_special_tokens: val.skip_special_tokens,
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::DetokenizeResponse> for DetokenizeResponse {
+    fn from(val: common::DetokenizeResponse) -> Self {
+        Self {
+            results: val.results,
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<CountTokensRequest> for common::CountTokensRequest {
+    fn from(val: CountTokensRequest) -> Self {
+        Self {
+            inputs: val.inputs,
+            metadata: Some(val.metadata),
+        }
+    }
+}
+
+impl From<common::CountTokensResponse> for CountTokensResponse {
+    fn from(val: common::CountTokensResponse) -> Self {
+        Self {
+            results: val
+                .results
+                .into_iter()
+                .map(|i| TokenCount {
+                    num_tokens: i.num_tokens as u32,
+                    truncated: i.truncated,
+                })
+                .collect(),
+            max_length: val.max_length as u32,
+            model_id: val.model_id,
+            metadata: val.metadata.unwrap_or_default(),
+        }
+    }
+}
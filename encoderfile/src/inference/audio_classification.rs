@@ -0,0 +1,35 @@
+use crate::{
+    common::{ModelOutputs, SequenceClassificationResult},
+    error::ApiError,
+    inference::utils::get_output,
+    runtime::ClassifierState,
+    services::AudioFeatures,
+    transforms::{AudioClassificationTransform, Postprocessor},
+};
+use ndarray::Ix2;
+
+#[tracing::instrument(skip_all)]
+pub fn audio_classification<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &AudioClassificationTransform,
+    model_outputs: &ModelOutputs,
+    config: &ClassifierState,
+    threshold: f32,
+    features: AudioFeatures,
+) -> Result<Vec<SequenceClassificationResult>, ApiError> {
+    let raw_outputs = crate::run_audio_model!(session, features)?;
+
+    let logits = get_output(&raw_outputs, model_outputs, "logits")?
+        .into_dimensionality::<Ix2>()
+        .map_err(|_| {
+            ApiError::InternalError("Model does not return tensor of shape [n_batch, n_labels]")
+        })?
+        .into_owned();
+
+    let outputs = transform.postprocess(logits.clone())?;
+
+    // audio classifiers share the label semantics of sequence classifiers
+    Ok(super::sequence_classification::postprocess(
+        logits, outputs, config, threshold,
+    ))
+}
@@ -0,0 +1,114 @@
+use ndarray::{Array2, ArrayView3, Axis, Ix2, Ix3, s};
+use ort::session::SessionOutputs;
+
+use crate::{
+    common::{AudioEmbedding, ModelOutputs},
+    error::ApiError,
+    services::AudioFeatures,
+    transforms::{AudioEmbeddingTransform, Postprocessor},
+};
+
+use super::utils::{get_output, l2_normalize};
+
+#[tracing::instrument(skip_all)]
+pub fn audio_embedding<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &AudioEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    features: AudioFeatures,
+) -> Result<Vec<AudioEmbedding>, ApiError> {
+    let valid_fractions = features.valid_fractions();
+    let raw_outputs = crate::run_audio_model!(session, features)?;
+
+    let outputs = select_output(&raw_outputs, model_outputs, &valid_fractions)?;
+
+    let outputs = transform.postprocess(outputs)?;
+
+    Ok(postprocess(outputs))
+}
+
+/// Reads `pooler_output` if the model returns it, else mean-pools `last_hidden_state`
+/// over the frames that are not padding.
+fn select_output(
+    outputs: &SessionOutputs,
+    model_outputs: &ModelOutputs,
+    valid_fractions: &[f32],
+) -> Result<Array2<f32>, ApiError> {
+    if let Some(pooler_output) = outputs.get(model_outputs.name("pooler_output")) {
+        return Ok(pooler_output
+            .try_extract_array::<f32>()
+            .map_err(|_| {
+                ApiError::InternalError("Model does not return tensor extractable to f32")
+            })?
+            .into_dimensionality::<Ix2>()
+            .map_err(|_| {
+                ApiError::InternalError("Model does not return tensor of shape [n_batch, dim]")
+            })?
+            .into_owned());
+    }
+
+    let hidden_states = get_output(outputs, model_outputs, "last_hidden_state")?
+        .into_dimensionality::<Ix3>()
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_frames, hidden_dim]",
+            )
+        })?;
+
+    Ok(mean_pool(hidden_states, valid_fractions))
+}
+
+/// Averages each clip's frames, ignoring trailing frames that correspond to padding.
+///
+/// Models downsample their input by a fixed stride, so the number of valid output frames
+/// is estimated from the fraction of the input that is not padding.
+pub fn mean_pool(hidden_states: ArrayView3<f32>, valid_fractions: &[f32]) -> Array2<f32> {
+    let (batch_size, num_frames, hidden_dim) = hidden_states.dim();
+    let mut pooled = Array2::zeros((batch_size, hidden_dim));
+
+    for (b, mut row) in pooled.axis_iter_mut(Axis(0)).enumerate() {
+        let fraction = valid_fractions.get(b).copied().unwrap_or(1.0);
+        let valid = ((fraction * num_frames as f32).ceil() as usize).clamp(1, num_frames);
+
+        if let Some(mean) = hidden_states.slice(s![b, ..valid, ..]).mean_axis(Axis(0)) {
+            row.assign(&mean);
+        }
+    }
+
+    pooled
+}
+
+#[tracing::instrument(skip_all)]
+pub fn postprocess(outputs: Array2<f32>) -> Vec<AudioEmbedding> {
+    l2_normalize(outputs, Axis(0))
+        .axis_iter(Axis(0))
+        .map(|emb| AudioEmbedding {
+            embedding: emb.to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_mean_pool_ignores_padding() {
+        let hidden_states = array![
+            [[1.0, 1.0], [3.0, 3.0], [5.0, 5.0], [7.0, 7.0]],
+            [[2.0, 0.0], [4.0, 0.0], [100.0, 100.0], [100.0, 100.0]],
+        ];
+
+        let pooled = mean_pool(hidden_states.view(), &[1.0, 0.5]);
+
+        assert_eq!(pooled, array![[4.0, 4.0], [3.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_postprocess_normalizes() {
+        let embeddings = postprocess(array![[3.0, 4.0]]);
+
+        assert_eq!(embeddings[0].embedding, vec![0.6, 0.8]);
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashSet},
+};
+
+use ndarray::{Array2, ArrayView1};
+
+use crate::{
+    common::{CorpusIndex, HnswIndex},
+    error::ApiError,
+    runtime::CorpusState,
+};
+
+/// Top-`top_k` documents most similar to `query`, as `(document, cosine similarity)` pairs,
+/// most similar first.
+#[tracing::instrument(skip_all)]
+pub fn search(
+    corpus: &CorpusState,
+    query: ArrayView1<f32>,
+    top_k: usize,
+) -> Result<Vec<(usize, f32)>, ApiError> {
+    if query.len() != corpus.embeddings.ncols() {
+        return Err(ApiError::InternalError(
+            "Query embedding does not match the corpus embedding size",
+        ));
+    }
+
+    let norm = query.dot(&query).sqrt().max(f32::EPSILON);
+    let query = query.mapv(|v| v / norm);
+
+    let results = match &corpus.index {
+        CorpusIndex::Flat => {
+            let mut scores: Vec<(usize, f32)> = corpus
+                .embeddings
+                .dot(&query)
+                .into_iter()
+                .enumerate()
+                .collect();
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            scores.truncate(top_k);
+            scores
+        }
+        CorpusIndex::Hnsw(index) => {
+            let mut entry = vec![index.entry_point];
+            let top_layer = index.neighbors[index.entry_point].len() - 1;
+
+            for layer in (1..=top_layer).rev() {
+                entry = closest(
+                    &corpus.embeddings,
+                    &index.neighbors,
+                    query.view(),
+                    entry,
+                    layer,
+                );
+            }
+
+            search_layer(
+                &corpus.embeddings,
+                &index.neighbors,
+                query.view(),
+                &entry,
+                index.ef_search.max(top_k),
+                0,
+            )
+            .into_iter()
+            .take(top_k)
+            .map(|c| (c.id, c.score))
+            .collect()
+        }
+    };
+
+    Ok(results)
+}
+
+/// Builds an HNSW graph over L2-normalized `embeddings`. Levels are drawn from a fixed
+/// seed, so the same corpus always produces the same graph.
+pub fn build_hnsw(
+    embeddings: &Array2<f32>,
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+) -> HnswIndex {
+    let level_mult = 1.0 / (m.max(2) as f64).ln();
+
+    let mut neighbors: Vec<Vec<Vec<usize>>> = Vec::with_capacity(embeddings.nrows());
+    let mut entry_point = 0;
+    let mut top_layer = 0;
+
+    for id in 0..embeddings.nrows() {
+        let level = random_level(id, level_mult);
+        neighbors.push(vec![Vec::new(); level + 1]);
+
+        if id == 0 {
+            top_layer = level;
+            continue;
+        }
+
+        let query = embeddings.row(id);
+        let mut entry = vec![entry_point];
+
+        for layer in ((level + 1)..=top_layer).rev() {
+            entry = closest(embeddings, &neighbors, query, entry, layer);
+        }
+
+        for layer in (0..=level.min(top_layer)).rev() {
+            let found = search_layer(
+                embeddings,
+                &neighbors,
+                query,
+                &entry,
+                ef_construction,
+                layer,
+            );
+            let max_neighbors = if layer == 0 { 2 * m } else { m };
+
+            let selected: Vec<usize> = found.iter().take(m).map(|c| c.id).collect();
+            for &neighbor in &selected {
+                let links = &mut neighbors[neighbor][layer];
+                links.push(id);
+
+                // keep the most similar links of over-connected neighbors
+                if links.len() > max_neighbors {
+                    let row = embeddings.row(neighbor);
+                    links.sort_by(|a, b| {
+                        row.dot(&embeddings.row(*b))
+                            .total_cmp(&row.dot(&embeddings.row(*a)))
+                    });
+                    links.truncate(max_neighbors);
+                }
+            }
+
+            neighbors[id][layer] = selected;
+            entry = found.into_iter().map(|c| c.id).collect();
+        }
+
+        if level > top_layer {
+            top_layer = level;
+            entry_point = id;
+        }
+    }
+
+    HnswIndex {
+        ef_search,
+        entry_point,
+        neighbors,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    score: f32,
+    id: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Greedy search of one layer, returning the `ef` closest documents found, most similar first.
+fn search_layer(
+    embeddings: &Array2<f32>,
+    neighbors: &[Vec<Vec<usize>>],
+    query: ArrayView1<f32>,
+    entry: &[usize],
+    ef: usize,
+    layer: usize,
+) -> Vec<Candidate> {
+    let score = |id: usize| Candidate {
+        score: embeddings.row(id).dot(&query),
+        id,
+    };
+
+    let mut visited: HashSet<usize> = entry.iter().copied().collect();
+    let mut candidates: BinaryHeap<Candidate> = entry.iter().map(|id| score(*id)).collect();
+    let mut results: BinaryHeap<Reverse<Candidate>> =
+        candidates.iter().map(|c| Reverse(*c)).collect();
+
+    while let Some(candidate) = candidates.pop() {
+        let worst = results.peek().map_or(f32::NEG_INFINITY, |r| r.0.score);
+        if candidate.score < worst && results.len() >= ef {
+            break;
+        }
+
+        for &neighbor in neighbors[candidate.id].get(layer).into_iter().flatten() {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+
+            let next = score(neighbor);
+            let worst = results.peek().map_or(f32::NEG_INFINITY, |r| r.0.score);
+            if results.len() < ef || next.score > worst {
+                candidates.push(next);
+                results.push(Reverse(next));
+
+                if results.len() > ef {
+                    results.pop();
+                }
+            }
+        }
+    }
+
+    results.into_sorted_vec().into_iter().map(|r| r.0).collect()
+}
+
+fn closest(
+    embeddings: &Array2<f32>,
+    neighbors: &[Vec<Vec<usize>>],
+    query: ArrayView1<f32>,
+    entry: Vec<usize>,
+    layer: usize,
+) -> Vec<usize> {
+    search_layer(embeddings, neighbors, query, &entry, 1, layer)
+        .first()
+        .map_or(entry, |c| vec![c.id])
+}
+
+/// Exponentially distributed level of document `id` (splitmix64 of the id).
+fn random_level(id: usize, level_mult: f64) -> usize {
+    let mut z = (id as u64).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^= z >> 31;
+
+    // uniform in (0, 1]
+    let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+
+    (-uniform.ln() * level_mult) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::CorpusDocument;
+
+    fn corpus(num_documents: usize, dim: usize) -> Array2<f32> {
+        let embeddings = Array2::from_shape_fn((num_documents, dim), |(i, j)| {
+            ((i * 31 + j * 17) as f32 * 0.37).sin()
+        });
+        let norms = embeddings
+            .rows()
+            .into_iter()
+            .map(|row| row.dot(&row).sqrt())
+            .collect::<Vec<f32>>();
+
+        Array2::from_shape_fn((num_documents, dim), |(i, j)| embeddings[[i, j]] / norms[i])
+    }
+
+    fn state(embeddings: Array2<f32>, index: CorpusIndex) -> CorpusState {
+        CorpusState {
+            documents: (0..embeddings.nrows())
+                .map(|i| CorpusDocument {
+                    id: i.to_string(),
+                    text: format!("document {i}"),
+                    metadata: None,
+                })
+                .collect(),
+            embeddings,
+            index,
+        }
+    }
+
+    #[test]
+    fn test_flat_search() {
+        let embeddings = corpus(20, 8);
+        let query = embeddings.row(7).mapv(|v| v * 3.0);
+        let state = state(embeddings, CorpusIndex::Flat);
+
+        let results = search(&state, query.view(), 3).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, 7);
+        assert!((results[0].1 - 1.0).abs() < 1e-5);
+        assert!(results.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_hnsw_search_matches_flat() {
+        let embeddings = corpus(200, 16);
+        let index = build_hnsw(&embeddings, 8, 64, 64);
+
+        assert_eq!(index, build_hnsw(&embeddings, 8, 64, 64));
+        assert!(index.neighbors.iter().all(|layers| !layers.is_empty()));
+
+        let flat = state(embeddings.clone(), CorpusIndex::Flat);
+        let hnsw = state(embeddings.clone(), CorpusIndex::Hnsw(index));
+
+        for i in [0, 13, 99, 150, 199] {
+            let query = embeddings.row(i);
+            let ids = |results: Vec<(usize, f32)>| results.into_iter().map(|r| r.0).collect();
+            let expected: Vec<usize> = ids(search(&flat, query, 5).unwrap());
+            let found: Vec<usize> = ids(search(&hnsw, query, 5).unwrap());
+
+            assert_eq!(found[0], i);
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_search_dimension_mismatch() {
+        let state = state(corpus(4, 8), CorpusIndex::Flat);
+        let query = ndarray::Array1::<f32>::ones(4);
+
+        assert!(search(&state, query.view(), 1).is_err());
+    }
+}
@@ -2,8 +2,16 @@ use ndarray::{Array3, Axis, Ix3};
 use tokenizers::Encoding;
 
 use crate::{
-    common::{TokenEmbedding, TokenEmbeddingSequence, TokenInfo},
+    common::{
+        InferenceOptions, ModelOutputs, OffsetMapping, TokenEmbedding, TokenEmbeddingSequence,
+        TokenInfo,
+    },
     error::ApiError,
+    inference::{
+        inputs::InputBinder,
+        utils::{extra_outputs, get_output, resize_embeddings},
+    },
+    runtime::token_count,
     transforms::{EmbeddingTransform, Postprocessor},
 };
 
@@ -11,28 +19,43 @@ use crate::{
 pub fn embedding<'a>(
     mut session: crate::runtime::Model<'a>,
     transform: &EmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
     encodings: Vec<Encoding>,
+    options: &InferenceOptions,
 ) -> Result<Vec<TokenEmbeddingSequence>, ApiError> {
-    let (a_ids, a_mask, a_type_ids) = crate::prepare_text_inputs!(encodings);
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
 
-    let mut outputs = crate::run_model!(session, a_ids, a_mask, a_type_ids)?
-        .get("last_hidden_state")
-        .expect("Model does not return last_hidden_state")
-        .try_extract_array::<f32>()
-        .expect("Model does not return tensor extractable to f32")
+    let mut outputs = get_output(&raw_outputs, model_outputs, "last_hidden_state")?
         .into_dimensionality::<Ix3>()
-        .expect("Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]")
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]",
+            )
+        })?
         .into_owned();
 
+    let extra = extra_outputs(&raw_outputs, model_outputs, transform, &encodings)?;
+
     outputs = transform.postprocess(outputs)?;
 
-    let embeddings = postprocess(outputs, encodings);
+    outputs = resize_embeddings(outputs, options)?;
+
+    let mut embeddings = postprocess(outputs, encodings, options);
+
+    for (embedding, extra) in embeddings.iter_mut().zip(extra) {
+        embedding.outputs = extra;
+    }
 
     Ok(embeddings)
 }
 
 #[tracing::instrument(skip_all)]
-pub fn postprocess(outputs: Array3<f32>, encodings: Vec<Encoding>) -> Vec<TokenEmbeddingSequence> {
+pub fn postprocess(
+    outputs: Array3<f32>,
+    encodings: Vec<Encoding>,
+    options: &InferenceOptions,
+) -> Vec<TokenEmbeddingSequence> {
     let mut embeddings = Vec::new();
 
     for (encoding, embs) in encodings.iter().zip(outputs.axis_iter(Axis(0))) {
@@ -41,29 +64,61 @@ pub fn postprocess(outputs: Array3<f32>, encodings: Vec<Encoding>) -> Vec<TokenE
         let mut results = Vec::new();
 
         for i in 0..encoding.len() {
-            if encoding.get_special_tokens_mask()[i] == 1 {
+            // padding is a special token too, but is never returned
+            if encoding.get_attention_mask()[i] == 0
+                || (encoding.get_special_tokens_mask()[i] == 1 && !options.include_special_tokens())
+            {
                 continue;
             }
 
             let (start, end) = encoding.get_offsets()[i];
-            let token_info = TokenInfo {
+            let token_info = options.return_offsets().then(|| TokenInfo {
                 token: encoding.get_tokens()[i].clone(),
                 token_id: encoding.get_ids()[i],
                 start,
                 end,
-            };
+            });
+
+            let embedding = transformed
+                .index_axis(Axis(0), i)
+                .to_owned()
+                .into_raw_vec_and_offset()
+                .0;
 
-            let e = transformed.index_axis(Axis(0), i);
             results.push(TokenEmbedding {
-                embedding: e.to_owned().into_raw_vec_and_offset().0,
-                token_info: Some(token_info),
+                embedding,
+                token_info,
             });
         }
 
+        let count = token_count(encoding);
+
         embeddings.push(TokenEmbeddingSequence {
             embeddings: results,
+            num_tokens: count.num_tokens,
+            truncated: count.truncated,
+            outputs: None,
         })
     }
 
     embeddings
 }
+
+/// Converts the byte offsets of the tokens of `sequence` to `mapping`. `text` is the input
+/// `sequence` was produced from.
+pub fn convert_offsets(sequence: &mut TokenEmbeddingSequence, text: &str, mapping: OffsetMapping) {
+    if mapping == OffsetMapping::Byte {
+        return;
+    }
+
+    let converter = mapping.converter(text);
+
+    for token_info in sequence
+        .embeddings
+        .iter_mut()
+        .filter_map(|e| e.token_info.as_mut())
+    {
+        token_info.start = converter.convert(token_info.start);
+        token_info.end = converter.convert(token_info.end);
+    }
+}
@@ -2,7 +2,10 @@ use ndarray::{Array2, Array4, Axis, Ix2};
 
 use crate::error::ApiError;
 
-use crate::common::ImageLabelScore;
+use crate::common::{ImageLabelScore, ModelOutputs};
+use crate::inference::sequence_classification::{log_add_exp, remap};
+use crate::inference::utils::get_output;
+use crate::runtime::ClassifierState;
 
 /*
 fn logit_to_prob(logit: f32) -> f32 {
@@ -13,19 +16,16 @@ fn logit_to_prob(logit: f32) -> f32 {
 #[tracing::instrument(skip_all)]
 pub fn image_classification<'a>(
     mut session: crate::runtime::Model<'a>,
+    model_outputs: &ModelOutputs,
     // CHECK if this is a vec of flattened rgb images with num_channels X height X width
     images: Array4<f32>,
-    classes: Vec<String>,
+    config: &ClassifierState,
 ) -> Result<Vec<Vec<ImageLabelScore>>, ApiError> {
     let grouped_images = ort::value::TensorRef::from_array_view(&images)
         .unwrap()
         .to_owned();
     let raw_outputs = crate::run_cv_model!(session, grouped_images)?;
-    let /*mut*/ outputs = raw_outputs
-        .get("logits")
-        .ok_or(ApiError::InternalError("Model does not return logits"))?
-        .try_extract_array::<f32>()
-        .map_err(|_| ApiError::InternalError("Model does not return tensor extractable to f32"))?
+    let /*mut*/ outputs = get_output(&raw_outputs, model_outputs, "logits")?
         .into_dimensionality::<Ix2>()
         .map_err(|_| {
             ApiError::InternalError("Model does not return tensor of shape [n_batch, n_classes]")
@@ -33,21 +33,28 @@ pub fn image_classification<'a>(
         .into_owned();
     // outputs.mapv_inplace(logit_to_prob);
 
-    Ok(postprocess(outputs, classes))
+    Ok(postprocess(outputs, config))
 }
 
+/// Scores of each label, after build-time label overrides: the logits of merged labels are
+/// combined into the logit of their summed probabilities, and hidden labels are dropped.
 #[tracing::instrument(skip_all)]
-pub fn postprocess(outputs: Array2<f32>, classes: Vec<String>) -> Vec<Vec<ImageLabelScore>> {
+pub fn postprocess(outputs: Array2<f32>, config: &ClassifierState) -> Vec<Vec<ImageLabelScore>> {
+    let classes = config.labels_by_id();
+
     outputs
         .axis_iter(Axis(0))
         .map(|logs| {
+            let logs = match &config.label_map {
+                Some(label_map) => remap(logs, label_map, classes.len(), log_add_exp),
+                None => logs.to_owned(),
+            };
+
             logs.iter()
-                .enumerate()
-                .map(|(idx, score)| {
-                    ImageLabelScore {
-                        label: classes[idx].to_string(), // TODO: get label from config
-                        score: Some(*score),
-                    }
+                .zip(&classes)
+                .map(|(score, label)| ImageLabelScore {
+                    label: label.clone(),
+                    score: Some(*score),
                 })
                 .collect()
         })
@@ -56,5 +63,52 @@ pub fn postprocess(outputs: Array2<f32>, classes: Vec<String>) -> Vec<Vec<ImageL
 
 #[cfg(test)]
 mod tests {
-    // Add your test cases here
+    use super::*;
+    use ndarray::array;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_postprocess_label_overrides() {
+        // LABEL_1 and LABEL_2 are merged into "dog", and LABEL_3 is hidden
+        let config = ClassifierState {
+            id2label: Some(HashMap::from([
+                (0, "cat".to_string()),
+                (1, "dog".to_string()),
+            ])),
+            label2id: None,
+            num_labels: Some(2),
+            problem_type: None,
+            calibration: None,
+            label_map: Some(vec![Some(0), Some(1), Some(1), None]),
+            label_descriptions: None,
+            label_thresholds: None,
+        };
+
+        let results = postprocess(array![[1.0, 2.0, 2.0, 5.0]], &config);
+
+        let labels: Vec<_> = results[0].iter().map(|l| l.label.as_str()).collect();
+        assert_eq!(labels, ["cat", "dog"]);
+        assert_eq!(results[0][0].score, Some(1.0));
+        assert!((results[0][1].score.unwrap() - (2.0 + 2f32.ln())).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_postprocess_without_overrides() {
+        let config = ClassifierState {
+            id2label: Some(HashMap::from([(1, "dog".to_string())])),
+            label2id: None,
+            num_labels: None,
+            problem_type: None,
+            calibration: None,
+            label_map: None,
+            label_descriptions: None,
+            label_thresholds: None,
+        };
+
+        let results = postprocess(array![[0.5, 1.5]], &config);
+
+        let labels: Vec<_> = results[0].iter().map(|l| l.label.as_str()).collect();
+        assert_eq!(labels, ["LABEL_0", "dog"]);
+        assert_eq!(results[0][1].score, Some(1.5));
+    }
 }
@@ -0,0 +1,111 @@
+use ndarray::{Array2, Array4, Axis, Ix2, Ix3, s};
+use ort::session::SessionOutputs;
+
+use crate::{
+    common::{ImageEmbedding, ImageEmbeddingConfig, ImageEmbeddingOutput, ModelOutputs},
+    error::ApiError,
+    inference::utils::get_output,
+    transforms::{ImageEmbeddingTransform, Postprocessor},
+};
+
+#[tracing::instrument(skip_all)]
+pub fn image_embedding<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &ImageEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    config: &ImageEmbeddingConfig,
+    images: Array4<f32>,
+) -> Result<Vec<ImageEmbedding>, ApiError> {
+    let grouped_images = ort::value::TensorRef::from_array_view(&images)
+        .map_err(|_| ApiError::InternalError("Failed to create image tensor"))?
+        .to_owned();
+    let raw_outputs = crate::run_cv_model!(session, grouped_images)?;
+
+    let outputs = select_output(&raw_outputs, model_outputs, config.output)?;
+
+    let outputs = transform.postprocess(outputs)?;
+
+    Ok(postprocess(outputs, config.normalize))
+}
+
+/// Reads the configured output, or the first supported output the model returns.
+fn select_output(
+    outputs: &SessionOutputs,
+    model_outputs: &ModelOutputs,
+    output: Option<ImageEmbeddingOutput>,
+) -> Result<Array2<f32>, ApiError> {
+    let output = match output {
+        Some(output) => output,
+        None => ImageEmbeddingOutput::FALLBACK_ORDER
+            .into_iter()
+            .find(|o| outputs.get(model_outputs.name(o.output_name())).is_some())
+            .ok_or(ApiError::InternalError(
+                "Model does not return image_embeds, pooler_output or last_hidden_state",
+            ))?,
+    };
+
+    let array = get_output(outputs, model_outputs, output.output_name())?;
+
+    match output {
+        ImageEmbeddingOutput::ClsToken => Ok(array
+            .into_dimensionality::<Ix3>()
+            .map_err(|_| {
+                ApiError::InternalError(
+                    "Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]",
+                )
+            })?
+            .slice(s![.., 0, ..])
+            .into_owned()),
+        ImageEmbeddingOutput::ImageEmbeds | ImageEmbeddingOutput::PoolerOutput => Ok(array
+            .into_dimensionality::<Ix2>()
+            .map_err(|_| {
+                ApiError::InternalError("Model does not return tensor of shape [n_batch, dim]")
+            })?
+            .into_owned()),
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn postprocess(mut outputs: Array2<f32>, normalize: bool) -> Vec<ImageEmbedding> {
+    if normalize {
+        for mut row in outputs.axis_iter_mut(Axis(0)) {
+            let norm = row.dot(&row).sqrt();
+            if norm > 0.0 {
+                row.mapv_inplace(|x| x / norm);
+            }
+        }
+    }
+
+    outputs
+        .axis_iter(Axis(0))
+        .map(|emb| ImageEmbedding {
+            embedding: emb.to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postprocess_normalize() {
+        let outputs = ndarray::array![[3.0, 4.0], [0.0, 0.0]];
+
+        let embeddings = postprocess(outputs, true);
+
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(embeddings[0].embedding, vec![0.6, 0.8]);
+        // zero vectors are left untouched
+        assert_eq!(embeddings[1].embedding, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_postprocess_no_normalize() {
+        let outputs = ndarray::array![[3.0, 4.0]];
+
+        let embeddings = postprocess(outputs, false);
+
+        assert_eq!(embeddings[0].embedding, vec![3.0, 4.0]);
+    }
+}
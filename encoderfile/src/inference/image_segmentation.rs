@@ -0,0 +1,215 @@
+use ndarray::{Array4, ArrayView3, Axis, Ix4};
+
+use crate::{
+    common::{ImageSegment, ImageSegmentationResult, ModelOutputs, SegmentMask},
+    error::ApiError,
+    inference::utils::get_output,
+    transforms::{ImageSegmentationTransform, Postprocessor},
+};
+
+#[tracing::instrument(skip_all)]
+pub fn image_segmentation<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &ImageSegmentationTransform,
+    model_outputs: &ModelOutputs,
+    images: Array4<f32>,
+    image_sizes: &[(u32, u32)],
+    labels: &[String],
+) -> Result<Vec<ImageSegmentationResult>, ApiError> {
+    let grouped_images = ort::value::TensorRef::from_array_view(&images)
+        .map_err(|_| ApiError::InternalError("Failed to create image tensor"))?
+        .to_owned();
+    let raw_outputs = crate::run_cv_model!(session, grouped_images)?;
+
+    let logits = get_output(&raw_outputs, model_outputs, "logits")?
+        .into_dimensionality::<Ix4>()
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_labels, height, width]",
+            )
+        })?
+        .into_owned();
+
+    let logits = transform.postprocess(logits)?;
+
+    Ok(postprocess(logits, image_sizes, labels))
+}
+
+/// Upsamples logits to the size of each original image, assigns every pixel its
+/// highest-scoring label and returns one mask per label present in the image.
+#[tracing::instrument(skip_all)]
+pub fn postprocess(
+    logits: Array4<f32>,
+    image_sizes: &[(u32, u32)],
+    labels: &[String],
+) -> Vec<ImageSegmentationResult> {
+    logits
+        .axis_iter(Axis(0))
+        .zip(image_sizes)
+        .map(|(logits, &(width, height))| segment(logits, width, height, labels))
+        .collect()
+}
+
+fn segment(
+    logits: ArrayView3<f32>,
+    width: u32,
+    height: u32,
+    labels: &[String],
+) -> ImageSegmentationResult {
+    let num_labels = logits.shape()[0];
+    let rows = interpolation_coords(logits.shape()[1], height as usize);
+    let cols = interpolation_coords(logits.shape()[2], width as usize);
+
+    let mut label_map = Vec::with_capacity(rows.len() * cols.len());
+    let mut score_sums = vec![0.0f32; num_labels];
+    let mut pixel_counts = vec![0u32; num_labels];
+    let mut pixel_logits = vec![0.0f32; num_labels];
+
+    for &(y0, y1, ly) in &rows {
+        for &(x0, x1, lx) in &cols {
+            // bilinear interpolation of every label's logit at this pixel
+            for (label, value) in pixel_logits.iter_mut().enumerate() {
+                let top = logits[[label, y0, x0]] * (1.0 - lx) + logits[[label, y0, x1]] * lx;
+                let bottom = logits[[label, y1, x0]] * (1.0 - lx) + logits[[label, y1, x1]] * lx;
+                *value = top * (1.0 - ly) + bottom * ly;
+            }
+
+            let (label, max) = pixel_logits
+                .iter()
+                .copied()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .unwrap_or((0, 0.0));
+
+            // softmax probability of the winning label
+            let denom: f32 = pixel_logits.iter().map(|v| (v - max).exp()).sum();
+
+            label_map.push(label);
+            score_sums[label] += 1.0 / denom;
+            pixel_counts[label] += 1;
+        }
+    }
+
+    let segments = (0..num_labels)
+        .filter(|&label| pixel_counts[label] > 0)
+        .map(|label| ImageSegment {
+            label: labels
+                .get(label)
+                .cloned()
+                .unwrap_or_else(|| format!("LABEL_{label}")),
+            score: score_sums[label] / pixel_counts[label] as f32,
+            mask: SegmentMask::from_pixels(width, height, label_map.iter().map(|&l| l == label)),
+        })
+        .collect();
+
+    ImageSegmentationResult { segments }
+}
+
+/// Source indices and weight for each output position, matching `align_corners=False`.
+fn interpolation_coords(input_len: usize, output_len: usize) -> Vec<(usize, usize, f32)> {
+    let scale = input_len as f32 / output_len as f32;
+
+    (0..output_len)
+        .map(|i| {
+            let src = ((i as f32 + 0.5) * scale - 0.5).max(0.0);
+            let lower = (src.floor() as usize).min(input_len - 1);
+            let upper = (lower + 1).min(input_len - 1);
+            (lower, upper, src - lower as f32)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postprocess_upsamples_to_image_size() {
+        // 2 labels on a 2x2 grid: label 1 on the right column
+        let logits = ndarray::array![[[[5.0, -5.0], [5.0, -5.0]], [[-5.0, 5.0], [-5.0, 5.0]]]];
+        let labels = vec!["sky".to_string(), "tree".to_string()];
+
+        let results = postprocess(logits, &[(4, 3)], &labels);
+
+        assert_eq!(results.len(), 1);
+        let segments = &results[0].segments;
+        assert_eq!(segments.len(), 2);
+
+        assert_eq!(segments[0].label, "sky");
+        assert_eq!(segments[0].mask.width, 4);
+        assert_eq!(segments[0].mask.height, 3);
+        // each row is [sky, sky, tree, tree]
+        assert_eq!(segments[0].mask.counts, vec![0, 2, 2, 2, 2, 2, 2]);
+
+        assert_eq!(segments[1].label, "tree");
+        assert_eq!(segments[1].mask.counts, vec![2, 2, 2, 2, 2, 2]);
+        assert!(segments[1].score > 0.9 && segments[1].score <= 1.0);
+    }
+
+    #[test]
+    fn test_postprocess_skips_absent_labels() {
+        let logits =
+            ndarray::Array4::<f32>::from_shape_fn(
+                (1, 3, 2, 2),
+                |(_, l, _, _)| {
+                    if l == 2 { 1.0 } else { 0.0 }
+                },
+            );
+
+        let results = postprocess(logits, &[(2, 2)], &[]);
+
+        let segments = &results[0].segments;
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].label, "LABEL_2");
+        assert_eq!(segments[0].mask.counts, vec![0, 4]);
+    }
+
+    #[test]
+    fn test_postprocess_batch_of_image_sizes() {
+        // 4 labels on a 2x2 grid, one per quadrant: 0 top-left, 1 top-right, 2 bottom-left,
+        // 3 bottom-right
+        let grid = ndarray::Array3::<f32>::from_shape_fn((4, 2, 2), |(l, y, x)| {
+            if l == y * 2 + x { 10.0 } else { 0.0 }
+        });
+        let logits = ndarray::stack(Axis(0), &[grid.view(), grid.view()]).unwrap();
+        let labels: Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+
+        // a wide and a tall image
+        let results = postprocess(logits, &[(6, 4), (2, 4)], &labels);
+        assert_eq!(results.len(), 2);
+
+        let wide = &results[0].segments;
+        assert_eq!(
+            wide.iter().map(|s| s.label.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert!(wide.iter().all(|s| s.mask.width == 6 && s.mask.height == 4));
+        // each quadrant is 3x2 pixels
+        assert_eq!(wide[0].mask.counts, vec![0, 3, 3, 3, 15]);
+        assert_eq!(wide[3].mask.counts, vec![15, 3, 3, 3]);
+        assert!(wide.iter().all(|s| s.score > 0.5 && s.score <= 1.0));
+
+        let tall = &results[1].segments;
+        assert!(tall.iter().all(|s| s.mask.width == 2 && s.mask.height == 4));
+        // each quadrant is 1x2 pixels
+        assert_eq!(tall[0].mask.counts, vec![0, 1, 1, 1, 5]);
+        assert_eq!(tall[1].mask.counts, vec![1, 1, 1, 1, 4]);
+    }
+
+    #[test]
+    fn test_postprocess_argmax_per_pixel() {
+        // label 1 only wins the top-left logit, by a small margin
+        let logits = ndarray::array![[[[1.0, 2.0], [2.0, 2.0]], [[1.5, 0.0], [0.0, 0.0]]]];
+
+        let results = postprocess(logits, &[(2, 2)], &["x".to_string(), "y".to_string()]);
+
+        let segments = &results[0].segments;
+        assert_eq!(segments[0].label, "x");
+        assert_eq!(segments[0].mask.counts, vec![1, 3]);
+        assert_eq!(segments[1].label, "y");
+        assert_eq!(segments[1].mask.counts, vec![0, 1, 3]);
+        // softmax of [1.0, 1.5] for the winning label
+        let expected = 1.0 / (1.0 + (-0.5f32).exp());
+        assert!((segments[1].score - expected).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{Result, bail};
+use ort::{
+    session::Input,
+    tensor::TensorElementType,
+    value::{DynValue, Tensor},
+};
+use tokenizers::Encoding;
+
+use crate::{
+    common::{InputRole, ModelInputs},
+    error::ApiError,
+};
+
+/// Element type of a graph input. Token tensors are built as i64 and cast on binding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputDType {
+    Int64,
+    Int32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct InputBinding {
+    name: String,
+    role: InputRole,
+    dtype: InputDType,
+}
+
+/// Binds tokenized inputs to the graph inputs of a text model.
+///
+/// Built once from the session's signature, so graphs taking inputs that cannot be filled from
+/// an encoding are rejected before the first request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputBinder {
+    bindings: Vec<InputBinding>,
+}
+
+impl InputBinder {
+    pub fn new(inputs: &[Input], names: &ModelInputs) -> Result<Self> {
+        let mut bindings = Vec::with_capacity(inputs.len());
+
+        for input in inputs {
+            let Some(role) = names.role(&input.name) else {
+                bail!(
+                    "Model input {:?} is not supported, expected one of {:?} (map graph inputs with `inputs`)",
+                    input.name,
+                    InputRole::ALL
+                        .iter()
+                        .map(|role| names.name(*role))
+                        .collect::<Vec<_>>()
+                );
+            };
+
+            let dtype = match input.input_type.tensor_type() {
+                Some(TensorElementType::Int64) => InputDType::Int64,
+                Some(TensorElementType::Int32) => InputDType::Int32,
+                Some(ty) => bail!(
+                    "Model input {:?} ({}) must be an int64 or int32 tensor, found {}",
+                    input.name,
+                    role,
+                    ty
+                ),
+                None => bail!("Model input {:?} ({}) must be a tensor", input.name, role),
+            };
+
+            bindings.push(InputBinding {
+                name: input.name.clone(),
+                role,
+                dtype,
+            });
+        }
+
+        for (role, name) in names.names.iter() {
+            if !bindings.iter().any(|b| &b.name == name) {
+                bail!("Model does not take {:?} (configured as {})", name, role);
+            }
+        }
+
+        if !bindings.iter().any(|b| b.role == InputRole::InputIds) {
+            bail!(
+                "Model must take {} as input",
+                names.name(InputRole::InputIds)
+            );
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Graph inputs for a batch of encodings padded to the same length.
+    pub fn bind(&self, encodings: &[Encoding]) -> Result<Vec<(String, DynValue)>, ApiError> {
+        if self.bindings.is_empty() {
+            return Err(ApiError::InternalError("Model inputs are not bound"));
+        }
+
+        let shape = [
+            encodings.len(),
+            encodings.first().map(Encoding::len).unwrap_or(0),
+        ];
+
+        self.bindings
+            .iter()
+            .map(|binding| {
+                let values = input_values(binding.role, encodings);
+
+                match binding.dtype {
+                    InputDType::Int64 => Tensor::from_array((shape, values)).map(|t| t.into_dyn()),
+                    InputDType::Int32 => Tensor::from_array((
+                        shape,
+                        values.into_iter().map(|v| v as i32).collect::<Vec<_>>(),
+                    ))
+                    .map(|t| t.into_dyn()),
+                }
+                .map(|value| (binding.name.clone(), value))
+                .map_err(|e| {
+                    tracing::error!("Failed to create {} tensor: {:?}", binding.role, e);
+                    ApiError::InternalError("Failed to create input tensor")
+                })
+            })
+            .collect()
+    }
+}
+
+/// Flattened `[batch_size, seq_len]` values of the tensor of `role`.
+fn input_values(role: InputRole, encodings: &[Encoding]) -> Vec<i64> {
+    encodings
+        .iter()
+        .flat_map(|e| -> Vec<i64> {
+            match role {
+                InputRole::InputIds => e.get_ids().iter().map(|i| *i as i64).collect(),
+                InputRole::AttentionMask => {
+                    e.get_attention_mask().iter().map(|i| *i as i64).collect()
+                }
+                InputRole::TokenTypeIds => e.get_type_ids().iter().map(|i| *i as i64).collect(),
+                InputRole::PositionIds => position_ids(e.get_attention_mask()),
+            }
+        })
+        .collect()
+}
+
+/// Positions counted over non-padding tokens, so left and right padding both start at 0.
+/// Padding is given position 0.
+fn position_ids(attention_mask: &[u32]) -> Vec<i64> {
+    let mut position = 0;
+
+    attention_mask
+        .iter()
+        .map(|&m| match m {
+            0 => 0,
+            _ => {
+                position += 1;
+                position - 1
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_ids() {
+        assert_eq!(position_ids(&[1, 1, 1, 0, 0]), vec![0, 1, 2, 0, 0]);
+        assert_eq!(position_ids(&[0, 0, 1, 1]), vec![0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,195 @@
+use ndarray::{Array2, Array3, ArrayView2, Axis, Ix3};
+use tokenizers::Encoding;
+
+use crate::{
+    common::{LateInteractionEmbedding, ModelOutputs},
+    error::ApiError,
+    inference::{inputs::InputBinder, utils::get_output},
+    runtime::{ProjectionState, token_count},
+    transforms::{LateInteractionTransform, Postprocessor},
+};
+
+#[tracing::instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
+pub fn late_interaction<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &LateInteractionTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
+    projection: Option<&ProjectionState>,
+    keep_padding: bool,
+    mask_punctuation: bool,
+    encodings: Vec<Encoding>,
+) -> Result<Vec<LateInteractionEmbedding>, ApiError> {
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
+
+    let mut outputs = get_output(&raw_outputs, model_outputs, "last_hidden_state")?
+        .into_dimensionality::<Ix3>()
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]",
+            )
+        })?
+        .into_owned();
+
+    outputs = transform.postprocess(outputs)?;
+
+    postprocess(
+        outputs,
+        &encodings,
+        projection,
+        keep_padding,
+        mask_punctuation,
+    )
+}
+
+/// Selects the token vectors of each sequence, projects them and L2-normalizes them.
+///
+/// Padding is dropped unless `keep_padding` is set (augmented queries), and punctuation
+/// is dropped if `mask_punctuation` is set (documents). Special tokens are always kept.
+#[tracing::instrument(skip_all)]
+pub fn postprocess(
+    outputs: Array3<f32>,
+    encodings: &[Encoding],
+    projection: Option<&ProjectionState>,
+    keep_padding: bool,
+    mask_punctuation: bool,
+) -> Result<Vec<LateInteractionEmbedding>, ApiError> {
+    let mut results = Vec::with_capacity(encodings.len());
+
+    for (encoding, embs) in encodings.iter().zip(outputs.axis_iter(Axis(0))) {
+        let indices: Vec<usize> = (0..encoding.len())
+            .filter(|&i| keep_padding || encoding.get_attention_mask()[i] == 1)
+            .filter(|&i| {
+                !mask_punctuation
+                    || encoding.get_special_tokens_mask()[i] == 1
+                    || !is_punctuation(&encoding.get_tokens()[i])
+            })
+            .collect();
+
+        let selected = embs.select(Axis(0), &indices);
+
+        let mut matrix = match projection {
+            Some(p) => project(selected, p)?,
+            None => selected,
+        };
+
+        for mut row in matrix.axis_iter_mut(Axis(0)) {
+            let norm = row.dot(&row).sqrt();
+            if norm > 0.0 {
+                row.mapv_inplace(|x| x / norm);
+            }
+        }
+
+        let (n_tokens, dim) = matrix.dim();
+        let count = token_count(encoding);
+
+        results.push(LateInteractionEmbedding {
+            n_tokens,
+            dim,
+            values: matrix.into_raw_vec_and_offset().0,
+            num_tokens: count.num_tokens,
+            truncated: count.truncated,
+        });
+    }
+
+    Ok(results)
+}
+
+fn project(matrix: Array2<f32>, projection: &ProjectionState) -> Result<Array2<f32>, ApiError> {
+    if matrix.ncols() != projection.weight.nrows() {
+        tracing::error!(
+            "Projection expects token vectors of dim {}, got {}",
+            projection.weight.nrows(),
+            matrix.ncols()
+        );
+        return Err(ApiError::InternalError(
+            "Token vectors do not match projection shape",
+        ));
+    }
+
+    let mut projected = matrix.dot(&projection.weight);
+
+    if let Some(bias) = &projection.bias {
+        projected += bias;
+    }
+
+    Ok(projected)
+}
+
+fn is_punctuation(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_punctuation())
+}
+
+fn as_matrix(embedding: &LateInteractionEmbedding) -> ArrayView2<'_, f32> {
+    ArrayView2::from_shape((embedding.n_tokens, embedding.dim), &embedding.values)
+        .expect("Late interaction embedding does not match its shape")
+}
+
+/// ColBERT MaxSim: for each query token, the best dot product against any document
+/// token, summed over the query.
+pub fn max_sim(query: ArrayView2<f32>, document: ArrayView2<f32>) -> f32 {
+    if document.nrows() == 0 {
+        return 0.0;
+    }
+
+    query
+        .dot(&document.t())
+        .axis_iter(Axis(0))
+        .map(|sims| sims.fold(f32::NEG_INFINITY, |acc, x| acc.max(*x)))
+        .sum()
+}
+
+#[tracing::instrument(skip_all)]
+pub fn score(query: &LateInteractionEmbedding, documents: &[LateInteractionEmbedding]) -> Vec<f32> {
+    let query = as_matrix(query);
+
+    documents
+        .iter()
+        .map(|document| max_sim(query, as_matrix(document)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_sim() {
+        let query = ndarray::array![[1.0, 0.0], [0.0, 1.0]];
+        let document = ndarray::array![[1.0, 0.0], [0.6, 0.8]];
+
+        // 1.0 (first doc token) + 0.8 (second doc token)
+        assert!((max_sim(query.view(), document.view()) - 1.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_max_sim_empty_document() {
+        let query = ndarray::array![[1.0, 0.0]];
+        let document = Array2::<f32>::zeros((0, 2));
+
+        assert_eq!(max_sim(query.view(), document.view()), 0.0);
+    }
+
+    #[test]
+    fn test_project() {
+        let projection = ProjectionState {
+            // [hidden_dim = 3, dim = 2]
+            weight: ndarray::array![[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+            bias: Some(ndarray::array![0.5, -0.5]),
+        };
+
+        let projected = project(ndarray::array![[1.0, 2.0, 3.0]], &projection).unwrap();
+        assert_eq!(projected, ndarray::array![[4.5, 4.5]]);
+
+        assert!(project(ndarray::array![[1.0, 2.0]], &projection).is_err());
+    }
+
+    #[test]
+    fn test_is_punctuation() {
+        assert!(is_punctuation("."));
+        assert!(is_punctuation("?!"));
+        assert!(!is_punctuation("a."));
+        assert!(!is_punctuation(""));
+    }
+}
@@ -1,8 +1,20 @@
 // text
+pub mod corpus;
 pub mod embedding;
+pub mod late_interaction;
 pub mod sentence_embedding;
 pub mod sequence_classification;
+pub mod sparse_embedding;
 pub mod token_classification;
 // cv
 pub mod image_classification;
+pub mod image_embedding;
+pub mod image_segmentation;
+pub mod object_detection;
+// audio
+pub mod audio_classification;
+pub mod audio_embedding;
+// multimodal
+pub mod inputs;
+pub mod multimodal_embedding;
 pub mod utils;
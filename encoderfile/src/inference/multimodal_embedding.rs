@@ -0,0 +1,170 @@
+use ndarray::{Array2, Array4, Axis, Ix2};
+use ort::session::SessionOutputs;
+use tokenizers::Encoding;
+
+use crate::{
+    common::{ImageLabelScore, ImageLabels, ModelOutputs, MultimodalEmbedding},
+    error::ApiError,
+    inference::{
+        inputs::InputBinder,
+        utils::{get_output, l2_normalize},
+    },
+    transforms::{MultimodalEmbeddingTransform, Postprocessor},
+};
+
+/// Embeds tokenized texts with the text tower. Returns L2-normalized `[batch_size, dim]` embeddings.
+#[tracing::instrument(skip_all)]
+pub fn text_embeddings<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &MultimodalEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
+    encodings: Vec<Encoding>,
+) -> Result<Array2<f32>, ApiError> {
+    // CLIP text towers are often exported without an attention_mask input, so only the inputs
+    // the graph takes are bound
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
+
+    let outputs = extract_embeds(&raw_outputs, model_outputs, "text_embeds")?;
+
+    Ok(l2_normalize(transform.postprocess(outputs)?, Axis(0)))
+}
+
+/// Embeds preprocessed images with the vision tower. Returns L2-normalized `[batch_size, dim]`
+/// embeddings.
+#[tracing::instrument(skip_all)]
+pub fn image_embeddings<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &MultimodalEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    images: Array4<f32>,
+) -> Result<Array2<f32>, ApiError> {
+    let grouped_images = ort::value::TensorRef::from_array_view(&images)
+        .map_err(|_| ApiError::InternalError("Failed to create image tensor"))?
+        .to_owned();
+    let raw_outputs = crate::run_cv_model!(session, grouped_images)?;
+
+    let outputs = extract_embeds(&raw_outputs, model_outputs, "image_embeds")?;
+
+    Ok(l2_normalize(transform.postprocess(outputs)?, Axis(0)))
+}
+
+fn extract_embeds(
+    outputs: &SessionOutputs,
+    model_outputs: &ModelOutputs,
+    role: &str,
+) -> Result<Array2<f32>, ApiError> {
+    Ok(get_output(outputs, model_outputs, role)?
+        .into_dimensionality::<Ix2>()
+        .map_err(|_| {
+            ApiError::InternalError("Model does not return tensor of shape [n_batch, dim]")
+        })?
+        .into_owned())
+}
+
+#[tracing::instrument(skip_all)]
+pub fn postprocess(embeddings: Array2<f32>) -> Vec<MultimodalEmbedding> {
+    embeddings
+        .axis_iter(Axis(0))
+        .map(|emb| MultimodalEmbedding {
+            embedding: emb.to_vec(),
+        })
+        .collect()
+}
+
+/// Scores each image against every label as a softmax over `logit_scale * cosine similarity`,
+/// sorted by descending score. Embeddings must already be L2-normalized.
+#[tracing::instrument(skip_all)]
+pub fn similarity(
+    image_embeds: &Array2<f32>,
+    text_embeds: &Array2<f32>,
+    labels: &[String],
+    logit_scale: f32,
+) -> Result<Vec<ImageLabels>, ApiError> {
+    if image_embeds.ncols() != text_embeds.ncols() {
+        tracing::error!(
+            "Image embedding dim {} does not match text embedding dim {}",
+            image_embeds.ncols(),
+            text_embeds.ncols()
+        );
+        return Err(ApiError::InternalError(
+            "Image and text embeddings have different dimensions",
+        ));
+    }
+
+    let logits = image_embeds.dot(&text_embeds.t()) * logit_scale;
+
+    Ok(logits
+        .axis_iter(Axis(0))
+        .map(|row| {
+            let max = row.fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            let exp = row.mapv(|x| (x - max).exp());
+            let sum = exp.sum();
+
+            let mut scores: Vec<ImageLabelScore> = labels
+                .iter()
+                .zip(exp.iter())
+                .map(|(label, e)| ImageLabelScore {
+                    label: label.clone(),
+                    score: Some(e / sum),
+                })
+                .collect();
+
+            scores.sort_by(|a, b| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            ImageLabels { labels: scores }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn labels() -> Vec<String> {
+        vec!["a cat".to_string(), "a dog".to_string()]
+    }
+
+    #[test]
+    fn test_similarity_softmax_over_labels() {
+        let images = array![[1.0, 0.0], [0.0, 1.0]];
+        let texts = array![[1.0, 0.0], [0.0, 1.0]];
+
+        let results = similarity(&images, &texts, &labels(), 1.0).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].labels[0].label, "a cat");
+        assert_eq!(results[1].labels[0].label, "a dog");
+
+        let total: f32 = results[0].labels.iter().filter_map(|l| l.score).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        let expected = 1.0_f32.exp() / (1.0_f32.exp() + 1.0);
+        assert!((results[0].labels[0].score.unwrap() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_similarity_logit_scale_sharpens_scores() {
+        let images = array![[0.8, 0.6]];
+        let texts = array![[1.0, 0.0], [0.0, 1.0]];
+
+        let flat = similarity(&images, &texts, &labels(), 1.0).unwrap();
+        let sharp = similarity(&images, &texts, &labels(), 100.0).unwrap();
+
+        assert!(sharp[0].labels[0].score.unwrap() > flat[0].labels[0].score.unwrap());
+        assert!(sharp[0].labels[0].score.unwrap() > 0.999);
+    }
+
+    #[test]
+    fn test_similarity_dim_mismatch() {
+        let images = array![[1.0, 0.0, 0.0]];
+        let texts = array![[1.0, 0.0], [0.0, 1.0]];
+
+        assert!(similarity(&images, &texts, &labels(), 100.0).is_err());
+    }
+}
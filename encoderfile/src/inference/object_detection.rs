@@ -0,0 +1,297 @@
+use ndarray::{Array3, Array4, Axis, Ix3};
+
+use crate::{
+    common::{
+        BoundingBox, DetectedObject, ModelOutputs, ObjectDetectionConfig, ObjectDetectionResult,
+    },
+    error::ApiError,
+    inference::utils::get_output,
+    transforms::{ObjectDetectionTransform, Postprocessor},
+};
+
+/// Where an image was placed in the model input, to map boxes back onto the original image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageGeometry {
+    /// `(width, height)` of the original image.
+    pub original: (u32, u32),
+    /// `(width, height)` of the resized image in the model input, before padding.
+    pub resized: (usize, usize),
+    /// `(width, height)` of the padded model input.
+    pub padded: (usize, usize),
+}
+
+#[tracing::instrument(skip_all)]
+pub fn object_detection<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &ObjectDetectionTransform,
+    model_outputs: &ModelOutputs,
+    config: &ObjectDetectionConfig,
+    images: Array4<f32>,
+    geometry: &[ImageGeometry],
+    labels: &[String],
+) -> Result<Vec<ObjectDetectionResult>, ApiError> {
+    let grouped_images = ort::value::TensorRef::from_array_view(&images)
+        .map_err(|_| ApiError::InternalError("Failed to create image tensor"))?
+        .to_owned();
+    let raw_outputs = crate::run_cv_model!(session, grouped_images)?;
+
+    let logits = get_output(&raw_outputs, model_outputs, "logits")?
+        .into_dimensionality::<Ix3>()
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_queries, n_classes]",
+            )
+        })?
+        .into_owned();
+
+    let boxes = get_output(&raw_outputs, model_outputs, "pred_boxes")?
+        .into_dimensionality::<Ix3>()
+        .map_err(|_| {
+            ApiError::InternalError("Model does not return tensor of shape [n_batch, n_queries, 4]")
+        })?
+        .into_owned();
+
+    let scores = transform.postprocess(logits)?;
+
+    Ok(postprocess(scores, boxes, geometry, labels, config))
+}
+
+/// Converts per-query class scores and `(center_x, center_y, width, height)` boxes relative
+/// to the padded model input into labeled boxes in pixel coordinates of the original image.
+/// Boxes are undone from the padding and resize of `geometry`, and clamped to the image.
+#[tracing::instrument(skip_all)]
+pub fn postprocess(
+    scores: Array3<f32>,
+    boxes: Array3<f32>,
+    geometry: &[ImageGeometry],
+    labels: &[String],
+    config: &ObjectDetectionConfig,
+) -> Vec<ObjectDetectionResult> {
+    // DETR-style models predict an extra trailing "no object" class
+    let num_classes = match scores.shape()[2] {
+        n if n == labels.len() + 1 => labels.len(),
+        n => n,
+    };
+
+    scores
+        .axis_iter(Axis(0))
+        .zip(boxes.axis_iter(Axis(0)))
+        .zip(geometry)
+        .map(|((scores, boxes), geometry)| {
+            let (width, height) = (geometry.original.0 as f32, geometry.original.1 as f32);
+            // model input pixels per relative unit, divided by input pixels per original pixel
+            let scale_x = geometry.padded.0 as f32 * width / geometry.resized.0.max(1) as f32;
+            let scale_y = geometry.padded.1 as f32 * height / geometry.resized.1.max(1) as f32;
+
+            let mut objects: Vec<DetectedObject> = scores
+                .axis_iter(Axis(0))
+                .zip(boxes.axis_iter(Axis(0)))
+                .filter_map(|(query_scores, query_box)| {
+                    let (label_id, score) = query_scores
+                        .iter()
+                        .take(num_classes)
+                        .copied()
+                        .enumerate()
+                        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+                    if score < config.threshold {
+                        return None;
+                    }
+
+                    let (cx, cy, w, h) = (query_box[0], query_box[1], query_box[2], query_box[3]);
+                    let to_pixels =
+                        |v: f32, scale: f32, max: f32| (v * scale).round().clamp(0.0, max) as i32;
+
+                    Some(DetectedObject {
+                        label: labels
+                            .get(label_id)
+                            .cloned()
+                            .unwrap_or_else(|| format!("LABEL_{label_id}")),
+                        score,
+                        bounding_box: BoundingBox {
+                            xmin: to_pixels(cx - w / 2.0, scale_x, width),
+                            ymin: to_pixels(cy - h / 2.0, scale_y, height),
+                            xmax: to_pixels(cx + w / 2.0, scale_x, width),
+                            ymax: to_pixels(cy + h / 2.0, scale_y, height),
+                        },
+                    })
+                })
+                .collect();
+
+            objects.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+            if let Some(iou_threshold) = config.nms_iou_threshold {
+                objects = non_max_suppression(objects, iou_threshold);
+            }
+
+            ObjectDetectionResult { objects }
+        })
+        .collect()
+}
+
+/// Greedy per-label non-maximum suppression. Expects objects sorted by descending score.
+fn non_max_suppression(objects: Vec<DetectedObject>, iou_threshold: f32) -> Vec<DetectedObject> {
+    let mut kept: Vec<DetectedObject> = Vec::with_capacity(objects.len());
+
+    for object in objects {
+        let suppressed = kept.iter().any(|k| {
+            k.label == object.label && k.bounding_box.iou(&object.bounding_box) > iou_threshold
+        });
+
+        if !suppressed {
+            kept.push(object);
+        }
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> Vec<String> {
+        vec!["cat".to_string(), "dog".to_string()]
+    }
+
+    /// An image stretched to a square model input, without padding.
+    fn stretched(width: u32, height: u32) -> ImageGeometry {
+        ImageGeometry {
+            original: (width, height),
+            resized: (800, 800),
+            padded: (800, 800),
+        }
+    }
+
+    #[test]
+    fn test_postprocess_threshold_and_boxes() {
+        // 3 queries, 2 labels + "no object"
+        let scores = ndarray::array![[[0.9, 0.05, 0.05], [0.1, 0.2, 0.7], [0.2, 0.6, 0.2]]];
+        let boxes = ndarray::array![[
+            [0.5, 0.5, 0.5, 0.5],
+            [0.1, 0.1, 0.1, 0.1],
+            [0.9, 0.9, 0.4, 0.4]
+        ]];
+        let config = ObjectDetectionConfig::default();
+
+        let results = postprocess(scores, boxes, &[stretched(200, 100)], &labels(), &config);
+
+        assert_eq!(results.len(), 1);
+        let objects = &results[0].objects;
+        assert_eq!(objects.len(), 2);
+
+        assert_eq!(objects[0].label, "cat");
+        assert_eq!(
+            objects[0].bounding_box,
+            BoundingBox {
+                xmin: 50,
+                ymin: 25,
+                xmax: 150,
+                ymax: 75
+            }
+        );
+
+        // boxes are clamped to the image
+        assert_eq!(objects[1].label, "dog");
+        assert_eq!(
+            objects[1].bounding_box,
+            BoundingBox {
+                xmin: 140,
+                ymin: 70,
+                xmax: 200,
+                ymax: 100
+            }
+        );
+    }
+
+    #[test]
+    fn test_postprocess_nms() {
+        let scores = ndarray::array![[[0.9, 0.1], [0.8, 0.2], [0.1, 0.7]]];
+        let boxes = ndarray::array![[
+            [0.5, 0.5, 0.5, 0.5],
+            [0.51, 0.5, 0.5, 0.5],
+            [0.5, 0.5, 0.5, 0.5]
+        ]];
+
+        let config = ObjectDetectionConfig {
+            threshold: 0.5,
+            nms_iou_threshold: Some(0.5),
+        };
+
+        let results = postprocess(scores, boxes, &[stretched(100, 100)], &labels(), &config);
+        let objects = &results[0].objects;
+
+        // the overlapping "cat" is suppressed, the "dog" on the same spot is kept
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].label, "cat");
+        assert_eq!(objects[0].score, 0.9);
+        assert_eq!(objects[1].label, "dog");
+    }
+
+    #[test]
+    fn test_postprocess_unknown_label() {
+        let scores = ndarray::array![[[0.1, 0.1, 0.1, 0.7]]];
+        let boxes = ndarray::array![[[0.5, 0.5, 0.2, 0.2]]];
+
+        let results = postprocess(
+            scores,
+            boxes,
+            &[stretched(10, 10)],
+            &labels(),
+            &ObjectDetectionConfig::default(),
+        );
+
+        assert_eq!(results[0].objects[0].label, "LABEL_3");
+    }
+
+    #[test]
+    fn test_postprocess_undoes_resize_and_padding() {
+        let scores = ndarray::array![[[0.9, 0.05, 0.05]], [[0.9, 0.05, 0.05]]];
+        // a box over the top-left quarter of the padded input
+        let boxes = ndarray::array![[[0.25, 0.25, 0.5, 0.5]], [[0.25, 0.25, 0.5, 0.5]]];
+
+        // a 640x480 image resized to 800x600 keeping its aspect ratio, and a 300x400 image
+        // resized to 600x800, both padded to 800x800
+        let geometry = [
+            ImageGeometry {
+                original: (640, 480),
+                resized: (800, 600),
+                padded: (800, 800),
+            },
+            ImageGeometry {
+                original: (300, 400),
+                resized: (600, 800),
+                padded: (800, 800),
+            },
+        ];
+
+        let results = postprocess(
+            scores,
+            boxes,
+            &geometry,
+            &labels(),
+            &ObjectDetectionConfig::default(),
+        );
+
+        // 400x400 input pixels, scaled by 640/800 and 480/600
+        assert_eq!(
+            results[0].objects[0].bounding_box,
+            BoundingBox {
+                xmin: 0,
+                ymin: 0,
+                xmax: 320,
+                ymax: 320
+            }
+        );
+        // 400x400 input pixels, scaled by 300/600 and 400/800
+        assert_eq!(
+            results[1].objects[0].bounding_box,
+            BoundingBox {
+                xmin: 0,
+                ymin: 0,
+                xmax: 200,
+                ymax: 200
+            }
+        );
+    }
+}
@@ -1,50 +1,500 @@
-use ndarray::{Array2, Axis, Ix2, Ix3};
+use ndarray::{Array2, ArrayView2, ArrayView3, Axis, Ix2, Ix3};
 use tokenizers::Encoding;
 
 use crate::{
-    common::SentenceEmbedding,
+    common::{
+        EmbeddingValues, EncodingFormat, InferenceOptions, ModelOutputs, PoolingConfig,
+        PoolingMode, QuantizationRanges, SentenceEmbedding, TokenCount,
+    },
     error::ApiError,
-    transforms::{Postprocessor, SentenceEmbeddingTransform},
+    inference::{
+        inputs::InputBinder,
+        utils::{BatchOutputs, attention_mask, extra_outputs, get_output, resize_embeddings},
+    },
+    runtime::token_count,
+    transforms::{Postprocessor, SentenceEmbeddingTransform, Tensor, TransformSpec},
 };
 
 #[tracing::instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
 pub fn sentence_embedding<'a>(
-    mut session: crate::runtime::Model<'a>,
+    session: crate::runtime::Model<'a>,
     transform: &SentenceEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
     encodings: Vec<Encoding>,
+    options: &InferenceOptions,
+    pooling: Option<&PoolingConfig>,
+    ranges: Option<&QuantizationRanges>,
 ) -> Result<Vec<SentenceEmbedding>, ApiError> {
-    let (a_ids, a_mask, a_type_ids) = crate::prepare_text_inputs!(encodings);
+    let counts: Vec<TokenCount> = encodings.iter().map(token_count).collect();
+    let (embeddings, extra) = run(
+        session,
+        transform,
+        model_outputs,
+        inputs,
+        encodings,
+        options,
+        pooling,
+    )?;
 
-    let a_mask_arr = a_mask
-        .try_extract_array::<i64>()
-        .expect("Failed to extract attention mask into i64")
-        .into_dimensionality::<Ix2>()
-        .expect("a_mask is not in Ix2")
-        .into_owned()
-        .mapv(|i| i as f32);
-
-    let outputs = crate::run_model!(session, a_ids, a_mask, a_type_ids)?
-        .get("last_hidden_state")
-        .expect("Model does not return last_hidden_state")
-        .try_extract_array::<f32>()
-        .expect("Model does not return tensor extractable to f32")
+    let mut results = postprocess(embeddings, &counts, options, ranges)?;
+
+    for (result, extra) in results.iter_mut().zip(extra) {
+        result.outputs = extra;
+    }
+
+    Ok(results)
+}
+
+/// Pooled `[batch_size, dim]` float embeddings. A Lua `Postprocess` function takes precedence
+/// over the pooling mode; `pooling.normalize` applies either way.
+#[tracing::instrument(skip_all)]
+pub fn embeddings<'a>(
+    session: crate::runtime::Model<'a>,
+    transform: &SentenceEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
+    encodings: Vec<Encoding>,
+    options: &InferenceOptions,
+    pooling: Option<&PoolingConfig>,
+) -> Result<Array2<f32>, ApiError> {
+    let (embeddings, _) = run(
+        session,
+        transform,
+        model_outputs,
+        inputs,
+        encodings,
+        options,
+        pooling,
+    )?;
+
+    Ok(embeddings)
+}
+
+/// Pooled embeddings and the extra outputs of each input.
+fn run<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &SentenceEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
+    encodings: Vec<Encoding>,
+    options: &InferenceOptions,
+    pooling: Option<&PoolingConfig>,
+) -> Result<(Array2<f32>, BatchOutputs), ApiError> {
+    let a_mask_arr = attention_mask(&encodings);
+
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
+
+    let outputs = get_output(&raw_outputs, model_outputs, "last_hidden_state")?
         .into_dimensionality::<Ix3>()
-        .expect("Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]")
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_tokens, hidden_dim]",
+            )
+        })?
         .into_owned();
 
-    let pooled_outputs = transform.postprocess((outputs, a_mask_arr))?;
+    let extra = extra_outputs(&raw_outputs, model_outputs, transform, &encodings)?;
 
-    let embeddings = postprocess(pooled_outputs, encodings);
+    let default_pooling = PoolingConfig::default();
+    let pooling = pooling.unwrap_or(&default_pooling);
 
-    Ok(embeddings)
+    let mut pooled_outputs = match transform.has_postprocessor() {
+        true => transform.postprocess((outputs, a_mask_arr))?,
+        false => pool(outputs.view(), a_mask_arr.view(), pooling.mode),
+    };
+
+    if pooling.normalize {
+        for mut row in pooled_outputs.axis_iter_mut(Axis(0)) {
+            let norm = row.dot(&row).sqrt();
+            if norm > 0.0 {
+                row.mapv_inplace(|x| x / norm);
+            }
+        }
+    }
+
+    Ok((resize_embeddings(pooled_outputs, options)?, extra))
 }
 
+/// Pools `[batch_size, n_tokens, dim]` token embeddings into `[batch_size, dim]`, skipping
+/// masked tokens. Inputs with no unmasked tokens pool to zeros.
+pub fn pool(outputs: ArrayView3<f32>, mask: ArrayView2<f32>, mode: PoolingMode) -> Array2<f32> {
+    let (batch_size, _, dim) = outputs.dim();
+    let mut pooled = Array2::<f32>::zeros((batch_size, dim));
+
+    for ((tokens, mask), mut row) in outputs
+        .outer_iter()
+        .zip(mask.outer_iter())
+        .zip(pooled.outer_iter_mut())
+    {
+        let mut valid = (0..tokens.nrows()).filter(|&i| mask[i] > 0.0);
+
+        match mode {
+            PoolingMode::Cls => row.assign(&tokens.row(0)),
+            PoolingMode::LastToken => {
+                if let Some(last) = valid.next_back() {
+                    row.assign(&tokens.row(last));
+                }
+            }
+            PoolingMode::Max => {
+                if let Some(first) = valid.next() {
+                    row.assign(&tokens.row(first));
+                    for i in valid {
+                        row.zip_mut_with(&tokens.row(i), |max, v| *max = max.max(*v));
+                    }
+                }
+            }
+            PoolingMode::Mean | PoolingMode::WeightedMean => {
+                let mut total = 0.0;
+                for i in valid {
+                    let weight = match mode {
+                        PoolingMode::WeightedMean => (i + 1) as f32 * mask[i],
+                        _ => mask[i],
+                    };
+                    row.scaled_add(weight, &tokens.row(i));
+                    total += weight;
+                }
+
+                if total > 0.0 {
+                    row.mapv_inplace(|v| v / total);
+                }
+            }
+        }
+    }
+
+    pooled
+}
+
+/// Encodes each embedding in the requested `encoding_format`. int8 and uint8 embeddings are
+/// quantized over `ranges`, or over `[-1.0, 1.0]` if no ranges were set at build time.
 #[tracing::instrument(skip_all)]
-pub fn postprocess(outputs: Array2<f32>, _encodings: Vec<Encoding>) -> Vec<SentenceEmbedding> {
+pub fn postprocess(
+    outputs: Array2<f32>,
+    counts: &[TokenCount],
+    options: &InferenceOptions,
+    ranges: Option<&QuantizationRanges>,
+) -> Result<Vec<SentenceEmbedding>, ApiError> {
+    let default_ranges = QuantizationRanges::default();
+    let ranges = ranges.unwrap_or(&default_ranges);
+
     outputs
         .axis_iter(Axis(0))
-        .map(|emb| SentenceEmbedding {
-            embedding: emb.to_owned().into_raw_vec_and_offset().0,
+        .zip(counts)
+        .map(|(emb, count)| {
+            Ok(SentenceEmbedding {
+                embedding: encode(emb.to_vec(), options, ranges)?,
+                num_tokens: count.num_tokens,
+                truncated: count.truncated,
+                outputs: None,
+            })
+        })
+        .collect()
+}
+
+fn encode(
+    embedding: Vec<f32>,
+    options: &InferenceOptions,
+    ranges: &QuantizationRanges,
+) -> Result<EmbeddingValues, ApiError> {
+    let values = match options.encoding_format() {
+        EncodingFormat::Float => EmbeddingValues::Float(embedding),
+        EncodingFormat::Int8 => EmbeddingValues::Int8(to_signed(quantize(&embedding, ranges)?)),
+        EncodingFormat::Uint8 => EmbeddingValues::Uint8(quantize(&embedding, ranges)?),
+        EncodingFormat::Binary => EmbeddingValues::Int8(to_signed(pack_bits(&embedding))),
+        EncodingFormat::Ubinary => EmbeddingValues::Uint8(pack_bits(&embedding)),
+    };
+
+    if !options.base64() {
+        return Ok(values);
+    }
+
+    let bytes = match values {
+        EmbeddingValues::Float(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        EmbeddingValues::Int8(values) => values.into_iter().map(|v| v as u8).collect(),
+        EmbeddingValues::Uint8(bytes) | EmbeddingValues::Base64(bytes) => bytes,
+    };
+
+    Ok(EmbeddingValues::Base64(bytes))
+}
+
+/// Maps each value onto one of 256 buckets spanning its dimension's range.
+fn quantize(embedding: &[f32], ranges: &QuantizationRanges) -> Result<Vec<u8>, ApiError> {
+    embedding
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let (min, max) = ranges.range(i).ok_or(ApiError::InternalError(
+                "Quantization ranges do not cover every embedding dimension",
+            ))?;
+
+            Ok(((v - min) / (max - min) * 255.0).floor().clamp(0.0, 255.0) as u8)
+        })
+        .collect()
+}
+
+/// Packs the sign of each value into bits, most significant bit first. The last byte is
+/// zero-padded.
+fn pack_bits(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| **v > 0.0)
+                .fold(0u8, |byte, (i, _)| byte | (0x80 >> i))
         })
         .collect()
 }
+
+/// Cosine similarity of every row of `sources` against every row of `targets`.
+#[tracing::instrument(skip_all)]
+pub fn similarity(sources: Array2<f32>, targets: Array2<f32>) -> Result<Array2<f32>, ApiError> {
+    let sources = Tensor(sources.into_dyn()).lp_normalize(2.0, 2);
+    let targets = Tensor(targets.into_dyn())
+        .lp_normalize(2.0, 2)
+        .and_then(|targets| targets.transpose());
+
+    sources
+        .and_then(|sources| sources.matmul(&targets?))
+        .map_err(|e| ApiError::LuaError(e.to_string()))?
+        .into_inner()
+        .into_dimensionality::<Ix2>()
+        .map_err(|_| ApiError::InternalError("Failed to compute similarities"))
+}
+
+/// Clusters inputs linked by a chain of pairwise `similarities` of at least `threshold`.
+/// Returns the cluster of each input, numbered in order of first appearance.
+#[tracing::instrument(skip_all)]
+pub fn dedupe(similarities: &Array2<f32>, threshold: f32) -> Vec<usize> {
+    let n = similarities.nrows();
+
+    // union-find, rooted at the first input of each cluster
+    let mut parents: Vec<usize> = (0..n).collect();
+
+    fn find(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if similarities[[i, j]] >= threshold {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut clusters = vec![usize::MAX; n];
+    let mut n_clusters = 0;
+
+    (0..n)
+        .map(|i| {
+            let root = find(&mut parents, i);
+            if clusters[root] == usize::MAX {
+                clusters[root] = n_clusters;
+                n_clusters += 1;
+            }
+            clusters[root]
+        })
+        .collect()
+}
+
+/// Shifts unsigned buckets into the int8 range.
+fn to_signed(bytes: Vec<u8>) -> Vec<i8> {
+    bytes
+        .into_iter()
+        .map(|b| b.wrapping_sub(128) as i8)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn options(encoding_format: EncodingFormat, base64: bool) -> InferenceOptions {
+        InferenceOptions {
+            encoding_format: Some(encoding_format),
+            base64: Some(base64),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pool() {
+        // second input has one padding token
+        let outputs = array![
+            [[1.0, 4.0], [3.0, 0.0], [5.0, 2.0]],
+            [[2.0, 2.0], [4.0, 6.0], [9.0, 9.0]],
+        ];
+        let mask = array![[1.0, 1.0, 1.0], [1.0, 1.0, 0.0]];
+
+        let pooled = |mode| pool(outputs.view(), mask.view(), mode);
+
+        assert_eq!(pooled(PoolingMode::Mean), array![[3.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(pooled(PoolingMode::Cls), array![[1.0, 4.0], [2.0, 2.0]]);
+        assert_eq!(pooled(PoolingMode::Max), array![[5.0, 4.0], [4.0, 6.0]]);
+        assert_eq!(
+            pooled(PoolingMode::LastToken),
+            array![[5.0, 2.0], [4.0, 6.0]]
+        );
+        // weights 1, 2, 3 and 1, 2
+        assert_eq!(
+            pooled(PoolingMode::WeightedMean),
+            array![[22.0 / 6.0, 10.0 / 6.0], [10.0 / 3.0, 14.0 / 3.0]]
+        );
+    }
+
+    #[test]
+    fn test_pool_fully_masked() {
+        let outputs = array![[[1.0, 2.0]]];
+        let mask = array![[0.0]];
+
+        for mode in [PoolingMode::Mean, PoolingMode::Max, PoolingMode::LastToken] {
+            assert_eq!(pool(outputs.view(), mask.view(), mode), array![[0.0, 0.0]]);
+        }
+    }
+
+    #[test]
+    fn test_similarity() {
+        let sources = array![[1.0, 0.0], [3.0, 4.0]];
+        let targets = array![[2.0, 0.0], [0.0, 1.0], [-1.0, 0.0]];
+
+        let similarities = similarity(sources, targets).unwrap();
+
+        assert_eq!(similarities.shape(), [2, 3]);
+        assert_eq!(similarities.row(0).to_vec(), vec![1.0, 0.0, -1.0]);
+        assert!((similarities[[1, 0]] - 0.6).abs() < 1e-6);
+        assert!((similarities[[1, 1]] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dedupe() {
+        // 0 ~ 2 and 2 ~ 3 chain into one cluster; 1 and 4 stand alone
+        let similarities = array![
+            [1.0, 0.1, 0.95, 0.5, 0.0],
+            [0.1, 1.0, 0.2, 0.1, 0.3],
+            [0.95, 0.2, 1.0, 0.91, 0.0],
+            [0.5, 0.1, 0.91, 1.0, 0.0],
+            [0.0, 0.3, 0.0, 0.0, 1.0],
+        ];
+
+        assert_eq!(dedupe(&similarities, 0.9), vec![0, 1, 0, 0, 2]);
+        assert_eq!(dedupe(&similarities, 0.99), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_quantize_fixed_range() {
+        let outputs = array![[-1.0, 0.0, 1.0, 2.0]];
+
+        let results = postprocess(
+            outputs.clone(),
+            &[TokenCount::default()],
+            &options(EncodingFormat::Uint8, false),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            results[0].embedding,
+            EmbeddingValues::Uint8(vec![0, 127, 255, 255])
+        );
+
+        let results = postprocess(
+            outputs,
+            &[TokenCount::default()],
+            &options(EncodingFormat::Int8, false),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            results[0].embedding,
+            EmbeddingValues::Int8(vec![-128, -1, 127, 127])
+        );
+    }
+
+    #[test]
+    fn test_quantize_calibrated_ranges() {
+        let ranges = QuantizationRanges {
+            min: vec![0.0, -2.0],
+            max: vec![1.0, 0.0],
+        };
+
+        let results = postprocess(
+            array![[0.5, -2.0]],
+            &[TokenCount::default()],
+            &options(EncodingFormat::Uint8, false),
+            Some(&ranges),
+        )
+        .unwrap();
+        assert_eq!(results[0].embedding, EmbeddingValues::Uint8(vec![127, 0]));
+
+        assert!(
+            postprocess(
+                array![[0.5, -2.0, 1.0]],
+                &[TokenCount::default()],
+                &options(EncodingFormat::Uint8, false),
+                Some(&ranges),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let outputs = array![[0.5, -0.5, 0.1, 0.0, -1.0, 1.0, 1.0, 1.0, 0.3]];
+
+        let results = postprocess(
+            outputs.clone(),
+            &[TokenCount::default()],
+            &options(EncodingFormat::Ubinary, false),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            results[0].embedding,
+            EmbeddingValues::Uint8(vec![0b1010_0111, 0b1000_0000])
+        );
+
+        let results = postprocess(
+            outputs,
+            &[TokenCount::default()],
+            &options(EncodingFormat::Binary, false),
+            None,
+        )
+        .unwrap();
+        assert_eq!(results[0].embedding, EmbeddingValues::Int8(vec![39, 0]));
+    }
+
+    #[test]
+    fn test_base64() {
+        let outputs = array![[1.0, -2.0]];
+
+        let results = postprocess(
+            outputs.clone(),
+            &[TokenCount::default()],
+            &options(EncodingFormat::Float, true),
+            None,
+        )
+        .unwrap();
+        let bytes = [1.0f32.to_le_bytes(), (-2.0f32).to_le_bytes()].concat();
+        assert_eq!(results[0].embedding, EmbeddingValues::Base64(bytes));
+
+        let results = postprocess(
+            outputs,
+            &[TokenCount::default()],
+            &options(EncodingFormat::Ubinary, true),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            results[0].embedding,
+            EmbeddingValues::Base64(vec![0b1000_0000])
+        );
+    }
+}
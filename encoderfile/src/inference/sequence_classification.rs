@@ -1,10 +1,17 @@
 use crate::{
-    common::SequenceClassificationResult,
+    common::{
+        Calibration, InferenceOptions, LabelScore, ModelOutputs, ProblemType,
+        SequenceClassificationResult,
+    },
     error::ApiError,
-    runtime::ClassifierState,
+    inference::{
+        inputs::InputBinder,
+        utils::{extra_outputs, get_output},
+    },
+    runtime::{ClassifierState, token_count},
     transforms::{Postprocessor, SequenceClassificationTransform},
 };
-use ndarray::{Array2, Axis, Ix2};
+use ndarray::{Array1, Array2, ArrayView1, Axis, Ix2};
 use ndarray_stats::QuantileExt;
 use tokenizers::Encoding;
 
@@ -12,45 +19,483 @@ use tokenizers::Encoding;
 pub fn sequence_classification<'a>(
     mut session: crate::runtime::Model<'a>,
     transform: &SequenceClassificationTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
     config: &ClassifierState,
+    threshold: f32,
     encodings: Vec<Encoding>,
 ) -> Result<Vec<SequenceClassificationResult>, ApiError> {
-    let (a_ids, a_mask, a_type_ids) = crate::prepare_text_inputs!(encodings);
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
 
-    let mut outputs = crate::run_model!(session, a_ids, a_mask, a_type_ids)?
-        .get("logits")
-        .expect("Model does not return logits")
-        .try_extract_array::<f32>()
-        .expect("Model does not return tensor extractable to f32")
+    let logits = get_output(&raw_outputs, model_outputs, "logits")?
         .into_dimensionality::<Ix2>()
-        .expect("Model does not return tensor of shape [n_batch, n_labels]")
+        .map_err(|_| {
+            ApiError::InternalError("Model does not return tensor of shape [n_batch, n_labels]")
+        })?
         .into_owned();
 
-    outputs = transform.postprocess(outputs)?;
+    let extra = extra_outputs(&raw_outputs, model_outputs, transform, &encodings)?;
 
-    let results = postprocess(outputs, config);
+    // calibration maps the model's logits, which the transform may have turned into scores
+    let outputs = transform.postprocess(logits.clone())?;
+
+    let mut results = postprocess(logits, outputs, config, threshold);
+
+    for ((result, encoding), extra) in results.iter_mut().zip(encodings.iter()).zip(extra) {
+        let count = token_count(encoding);
+        result.num_tokens = Some(count.num_tokens);
+        result.truncated = Some(count.truncated);
+        result.outputs = extra;
+    }
 
     Ok(results)
 }
 
+/// Turns the transformed `outputs` of the model into results according to the model's problem
+/// type. `logits` are the model's outputs before the transform.
+///
+/// Single-label models predict the top-scoring label. Multi-label models apply a sigmoid
+/// and predict every label scoring at least `threshold`. Regression models predict no label.
+/// Classifiers calibrated at build time score labels with calibrated probabilities of
+/// `logits`, in place of the transformed outputs.
+/// Labels overridden at build time are remapped last: hidden labels are dropped and the
+/// probabilities of merged labels, from a softmax of `logits` or the calibration, are summed
+/// (or their maximum score taken, for multi-label models).
 #[tracing::instrument(skip_all)]
 pub fn postprocess(
+    logits: Array2<f32>,
     outputs: Array2<f32>,
     config: &ClassifierState,
+    threshold: f32,
 ) -> Vec<SequenceClassificationResult> {
+    let problem_type = config.problem_type();
+
     outputs
         .axis_iter(Axis(0))
-        .zip(outputs.axis_iter(Axis(0)))
-        .map(|(logs, probs)| {
-            let predicted_index = probs.argmax().expect("Model has 0 labels");
+        .zip(logits.axis_iter(Axis(0)))
+        .map(|(logs, logits)| {
+            if problem_type == ProblemType::Regression {
+                let values = logs.to_owned().into_raw_vec_and_offset().0;
+
+                return SequenceClassificationResult {
+                    scores: values.clone(),
+                    logits: values,
+                    predicted_index: None,
+                    predicted_label: None,
+                    predicted_labels: Vec::new(),
+                    top_labels: None,
+                    num_tokens: None,
+                    truncated: None,
+                    outputs: None,
+                };
+            }
+
+            let probs = match (&config.calibration, problem_type) {
+                (Some(calibration), _) => calibrate(logits, calibration, problem_type),
+                (None, ProblemType::MultiLabelClassification) => logs.mapv(sigmoid),
+                (None, _) => logs.to_owned(),
+            };
+
+            let (logits, probs) = match &config.label_map {
+                Some(label_map) => {
+                    let num_labels = config.num_labels().unwrap_or_default();
+                    let merged = label_map.iter().flatten().count() > num_labels;
+
+                    match problem_type {
+                        ProblemType::MultiLabelClassification => (
+                            remap(logs, label_map, num_labels, f32::max),
+                            remap(probs.view(), label_map, num_labels, f32::max),
+                        ),
+                        // probabilities of merged labels are summed, so they are taken from the
+                        // model's logits whatever the transform returns. The softmax of merged
+                        // logits is the sum of the merged probabilities.
+                        _ => {
+                            let (logs, probs) = match (merged, &config.calibration) {
+                                (false, _) => (logs, probs),
+                                (true, Some(_)) => (logits, probs),
+                                (true, None) => (logits, probabilities(logits, problem_type)),
+                            };
+
+                            (
+                                remap(logs, label_map, num_labels, log_add_exp),
+                                remap(probs.view(), label_map, num_labels, |a, b| a + b),
+                            )
+                        }
+                    }
+                }
+                None => (logs.to_owned(), probs),
+            };
+
+            let predicted_index = probs.argmax().expect("Model has 0 labels") as u32;
+            let predicted_label = config.id2label(predicted_index).map(|i| i.to_string());
+
+            let predicted_labels = match problem_type {
+                ProblemType::MultiLabelClassification => probs
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, score)| **score >= config.label_threshold(*idx, threshold))
+                    .filter_map(|(idx, _)| config.id2label(idx as u32).map(|i| i.to_string()))
+                    .collect(),
+                _ => predicted_label.clone().into_iter().collect(),
+            };
+
             SequenceClassificationResult {
-                logits: logs.to_owned().into_raw_vec_and_offset().0,
-                scores: probs.to_owned().into_raw_vec_and_offset().0,
-                predicted_index: (predicted_index as u32),
-                predicted_label: config
-                    .id2label(predicted_index as u32)
-                    .map(|i| i.to_string()),
+                logits: logits.into_raw_vec_and_offset().0,
+                scores: probs.into_raw_vec_and_offset().0,
+                predicted_index: Some(predicted_index),
+                predicted_label,
+                predicted_labels,
+                top_labels: None,
+                num_tokens: None,
+                truncated: None,
+                outputs: None,
             }
         })
         .collect()
 }
+
+/// Applies per-request options to `results`: lists the `top_k` highest-scoring labels
+/// and drops logits unless they are requested.
+#[tracing::instrument(skip_all)]
+pub fn apply_options(
+    results: &mut [SequenceClassificationResult],
+    config: &ClassifierState,
+    options: &InferenceOptions,
+) {
+    for result in results.iter_mut() {
+        if let Some(top_k) = options.top_k {
+            let mut top_labels: Vec<LabelScore> = result
+                .scores
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, score)| {
+                    config.id2label(idx as u32).map(|label| LabelScore {
+                        label: label.to_string(),
+                        score: *score,
+                    })
+                })
+                .collect();
+
+            top_labels.sort_by(|a, b| b.score.total_cmp(&a.score));
+            top_labels.truncate(top_k);
+
+            result.top_labels = Some(top_labels);
+        }
+
+        if !options.return_logits() {
+            result.logits.clear();
+        }
+    }
+}
+
+/// Calibrated probabilities of one input's logits. Single-label probabilities sum to 1.
+pub fn calibrate(
+    logits: ArrayView1<f32>,
+    calibration: &Calibration,
+    problem_type: ProblemType,
+) -> Array1<f32> {
+    let scores: Array1<f32> = match calibration {
+        Calibration::Temperature { temperature } => {
+            return probabilities(logits.mapv(|z| z / temperature).view(), problem_type);
+        }
+        Calibration::PerClassTemperature { temperatures } => {
+            let scaled: Array1<f32> = logits
+                .iter()
+                .zip(temperatures)
+                .map(|(z, t)| z / t)
+                .collect();
+
+            return probabilities(scaled.view(), problem_type);
+        }
+        Calibration::Platt { slopes, intercepts } => logits
+            .iter()
+            .zip(slopes.iter().zip(intercepts))
+            .map(|(z, (a, b))| sigmoid(a * z + b))
+            .collect(),
+        Calibration::Isotonic { curves } => probabilities(logits, problem_type)
+            .iter()
+            .zip(curves)
+            .map(|(p, curve)| curve.interpolate(*p))
+            .collect(),
+    };
+
+    if problem_type == ProblemType::MultiLabelClassification {
+        return scores;
+    }
+
+    // per-class maps do not sum to 1 across labels
+    let sum = scores.sum();
+
+    if sum > 0.0 {
+        scores / sum
+    } else {
+        Array1::from_elem(scores.len(), 1.0 / scores.len() as f32)
+    }
+}
+
+/// Uncalibrated probabilities of one input's logits: a softmax over labels, or a sigmoid per
+/// label for multi-label models.
+pub fn probabilities(logits: ArrayView1<f32>, problem_type: ProblemType) -> Array1<f32> {
+    if problem_type == ProblemType::MultiLabelClassification {
+        return logits.mapv(sigmoid);
+    }
+
+    let max = logits.fold(f32::NEG_INFINITY, |max, z| max.max(*z));
+    let exp = logits.mapv(|z| (z - max).exp());
+
+    &exp / exp.sum()
+}
+
+/// Maps the per-label values of the model onto the labels overridden at build time,
+/// combining the values of merged labels with `merge`.
+pub(crate) fn remap(
+    values: ArrayView1<f32>,
+    label_map: &[Option<u32>],
+    num_labels: usize,
+    merge: impl Fn(f32, f32) -> f32,
+) -> Array1<f32> {
+    let mut remapped: Vec<Option<f32>> = vec![None; num_labels];
+
+    for (value, id) in values.iter().zip(label_map) {
+        if let Some(slot) = id.and_then(|id| remapped.get_mut(id as usize)) {
+            *slot = Some(slot.map_or(*value, |merged| merge(merged, *value)));
+        }
+    }
+
+    remapped
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect()
+}
+
+/// `ln(exp(a) + exp(b))`, without overflow.
+pub(crate) fn log_add_exp(a: f32, b: f32) -> f32 {
+    let max = a.max(b);
+    max + (-(a - b).abs()).exp().ln_1p()
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::IsotonicCurve;
+    use ndarray::array;
+    use std::collections::HashMap;
+
+    fn classifier_state(problem_type: Option<ProblemType>, n_labels: u32) -> ClassifierState {
+        ClassifierState {
+            id2label: Some((0..n_labels).map(|i| (i, format!("LABEL_{i}"))).collect()),
+            label2id: None,
+            num_labels: None,
+            problem_type,
+            calibration: None,
+            label_map: None,
+            label_descriptions: None,
+            label_thresholds: None,
+        }
+    }
+
+    /// Results of a model without a transform, whose outputs are its logits.
+    fn classify(
+        logits: Array2<f32>,
+        config: &ClassifierState,
+        threshold: f32,
+    ) -> Vec<SequenceClassificationResult> {
+        postprocess(logits.clone(), logits, config, threshold)
+    }
+
+    #[test]
+    fn test_single_label() {
+        let config = classifier_state(None, 3);
+        let results = classify(array![[0.1, 0.7, 0.2]], &config, 0.5);
+
+        assert_eq!(results[0].predicted_index, Some(1));
+        assert_eq!(results[0].predicted_labels, vec!["LABEL_1".to_string()]);
+    }
+
+    #[test]
+    fn test_multi_label() {
+        let config = classifier_state(Some(ProblemType::MultiLabelClassification), 3);
+        let results = classify(array![[2.0, -2.0, 1.0]], &config, 0.5);
+
+        assert_eq!(
+            results[0].predicted_labels,
+            vec!["LABEL_0".to_string(), "LABEL_2".to_string()]
+        );
+        assert!(results[0].scores.iter().all(|s| (0.0..=1.0).contains(s)));
+
+        let results = classify(array![[2.0, -2.0, 1.0]], &config, 0.8);
+        assert_eq!(results[0].predicted_labels, vec!["LABEL_0".to_string()]);
+    }
+
+    #[test]
+    fn test_multi_label_thresholds() {
+        let mut config = classifier_state(Some(ProblemType::MultiLabelClassification), 3);
+        config.label_thresholds = Some(vec![Some(0.95), Some(0.1), None]);
+
+        // sigmoid scores are about 0.88, 0.12 and 0.73; LABEL_2 falls back to the threshold
+        let results = classify(array![[2.0, -2.0, 1.0]], &config, 0.5);
+        assert_eq!(
+            results[0].predicted_labels,
+            vec!["LABEL_1".to_string(), "LABEL_2".to_string()]
+        );
+
+        let results = classify(array![[2.0, -2.0, 1.0]], &config, 0.8);
+        assert_eq!(results[0].predicted_labels, vec!["LABEL_1".to_string()]);
+    }
+
+    #[test]
+    fn test_regression() {
+        // a single label is regression unless stated otherwise
+        let config = classifier_state(None, 1);
+        let results = classify(array![[3.5]], &config, 0.5);
+
+        assert_eq!(results[0].scores, vec![3.5]);
+        assert_eq!(results[0].predicted_index, None);
+        assert_eq!(results[0].predicted_label, None);
+        assert!(results[0].predicted_labels.is_empty());
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut config = classifier_state(None, 3);
+        config.calibration = Some(Calibration::Temperature { temperature: 2.0 });
+
+        let results = classify(array![[2.0, 4.0, 0.0]], &config, 0.5);
+        let expected = probabilities(
+            array![1.0, 2.0, 0.0].view(),
+            ProblemType::SingleLabelClassification,
+        );
+
+        assert_eq!(results[0].predicted_index, Some(1));
+        assert_eq!(results[0].scores, expected.to_vec());
+        assert!((results[0].scores.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        // a higher temperature softens the top score
+        assert!(
+            results[0].scores[1]
+                < probabilities(
+                    array![2.0, 4.0, 0.0].view(),
+                    ProblemType::SingleLabelClassification
+                )[1]
+        );
+
+        config.calibration = Some(Calibration::Isotonic {
+            curves: vec![
+                IsotonicCurve {
+                    x: vec![0.0, 1.0],
+                    y: vec![0.0, 0.5],
+                };
+                3
+            ],
+        });
+        let results = classify(array![[2.0, 4.0, 0.0]], &config, 0.5);
+        assert!((results[0].scores.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+
+        let mut config = classifier_state(Some(ProblemType::MultiLabelClassification), 2);
+        config.calibration = Some(Calibration::Platt {
+            slopes: vec![1.0, 2.0],
+            intercepts: vec![0.0, -1.0],
+        });
+
+        let results = classify(array![[0.0, 1.0]], &config, 0.6);
+        assert_eq!(results[0].scores, vec![0.5, sigmoid(1.0)]);
+        assert_eq!(results[0].predicted_labels, vec!["LABEL_1".to_string()]);
+    }
+
+    #[test]
+    fn test_calibration_with_transform() {
+        let transform = SequenceClassificationTransform::new(
+            crate::transforms::DEFAULT_LIBS.to_vec(),
+            Some("function Postprocess(arr) return arr:softmax(2) end".to_string()),
+        )
+        .expect("Failed to create transform");
+
+        let logits = array![[2.0, 4.0, 0.0], [1.0, -1.0, 0.5]];
+        let outputs = transform.postprocess(logits.clone()).unwrap();
+
+        let mut config = classifier_state(None, 3);
+        let uncalibrated = postprocess(logits.clone(), outputs.clone(), &config, 0.5);
+
+        // calibration maps the logits, not the probabilities returned by the transform
+        config.calibration = Some(Calibration::Temperature { temperature: 1.0 });
+        let calibrated = postprocess(logits, outputs, &config, 0.5);
+
+        for (calibrated, uncalibrated) in calibrated.iter().zip(&uncalibrated) {
+            assert_eq!(calibrated.predicted_index, uncalibrated.predicted_index);
+            for (a, b) in calibrated.scores.iter().zip(&uncalibrated.scores) {
+                assert!((a - b).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_label_overrides() {
+        let mut config = classifier_state(None, 4);
+        config.label_map = Some(vec![Some(0), None, Some(1), Some(1)]);
+        config.id2label = Some(HashMap::from([
+            (0, "negative".to_string()),
+            (1, "positive".to_string()),
+        ]));
+        config.num_labels = Some(2);
+        config.problem_type = Some(ProblemType::SingleLabelClassification);
+
+        let logits = array![[0.1, 0.4, 0.3, 0.2]];
+        let probs = probabilities(logits.row(0), ProblemType::SingleLabelClassification);
+        let results = classify(logits.clone(), &config, 0.5);
+
+        // merged labels sum the softmax of the logits
+        assert_eq!(results[0].scores, vec![probs[0], probs[2] + probs[3]]);
+        assert_eq!(results[0].predicted_label, Some("positive".to_string()));
+        assert!((results[0].logits[1] - log_add_exp(0.3, 0.2)).abs() < 1e-6);
+
+        // a softmax transform is not applied twice
+        let outputs = probs.insert_axis(Axis(0));
+        let transformed = postprocess(logits, outputs, &config, 0.5);
+        assert_eq!(transformed[0].scores, results[0].scores);
+
+        config.problem_type = Some(ProblemType::MultiLabelClassification);
+        let results = classify(array![[2.0, 3.0, -1.0, 1.0]], &config, 0.5);
+
+        assert_eq!(results[0].scores, vec![sigmoid(2.0), sigmoid(1.0)]);
+        assert_eq!(results[0].logits, vec![2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_isotonic_curve() {
+        let curve = IsotonicCurve {
+            x: vec![0.2, 0.6, 0.8],
+            y: vec![0.1, 0.5, 0.5],
+        };
+
+        assert_eq!(curve.interpolate(0.0), 0.1);
+        assert!((curve.interpolate(0.4) - 0.3).abs() < 1e-6);
+        assert_eq!(curve.interpolate(0.7), 0.5);
+        assert_eq!(curve.interpolate(1.0), 0.5);
+    }
+
+    #[test]
+    fn test_apply_options() {
+        let config = classifier_state(None, 3);
+        let mut results = classify(array![[0.1, 0.7, 0.2]], &config, 0.5);
+
+        let options = InferenceOptions {
+            top_k: Some(2),
+            return_logits: Some(false),
+            ..Default::default()
+        };
+        apply_options(&mut results, &config, &options);
+
+        let top_labels = results[0].top_labels.as_ref().unwrap();
+        assert_eq!(
+            top_labels
+                .iter()
+                .map(|l| l.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["LABEL_1", "LABEL_2"]
+        );
+        assert!(results[0].logits.is_empty());
+    }
+}
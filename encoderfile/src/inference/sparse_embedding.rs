@@ -0,0 +1,115 @@
+use ndarray::{Array2, Axis, Ix3};
+use tokenizers::Encoding;
+
+use crate::{
+    common::{ModelOutputs, SparseEmbedding, TokenCount},
+    error::ApiError,
+    inference::{
+        inputs::InputBinder,
+        utils::{attention_mask, get_output},
+    },
+    runtime::token_count,
+    transforms::{Postprocessor, SparseEmbeddingTransform},
+};
+
+#[tracing::instrument(skip_all)]
+pub fn sparse_embedding<'a>(
+    mut session: crate::runtime::Model<'a>,
+    transform: &SparseEmbeddingTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
+    top_k: Option<usize>,
+    threshold: f32,
+    encodings: Vec<Encoding>,
+) -> Result<Vec<SparseEmbedding>, ApiError> {
+    let counts: Vec<TokenCount> = encodings.iter().map(token_count).collect();
+
+    let a_mask_arr = attention_mask(&encodings);
+
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
+
+    let outputs = get_output(&raw_outputs, model_outputs, "logits")?
+        .into_dimensionality::<Ix3>()
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_tokens, vocab_size]",
+            )
+        })?
+        .into_owned();
+
+    let pooled_outputs = transform.postprocess((outputs, a_mask_arr))?;
+
+    Ok(postprocess(pooled_outputs, &counts, top_k, threshold))
+}
+
+#[tracing::instrument(skip_all)]
+pub fn postprocess(
+    outputs: Array2<f32>,
+    counts: &[TokenCount],
+    top_k: Option<usize>,
+    threshold: f32,
+) -> Vec<SparseEmbedding> {
+    outputs
+        .axis_iter(Axis(0))
+        .zip(counts)
+        .map(|(weights, count)| {
+            let mut terms: Vec<(u32, f32)> = weights
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| **w > threshold)
+                .map(|(idx, w)| (idx as u32, *w))
+                .collect();
+
+            if let Some(k) = top_k.filter(|k| *k < terms.len()) {
+                terms.select_nth_unstable_by(k, |a, b| b.1.total_cmp(&a.1));
+                terms.truncate(k);
+                terms.sort_unstable_by_key(|(idx, _)| *idx);
+            }
+
+            let (indices, values) = terms.into_iter().unzip();
+
+            SparseEmbedding {
+                indices,
+                values,
+                tokens: None,
+                num_tokens: count.num_tokens,
+                truncated: count.truncated,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postprocess_threshold() {
+        let outputs = ndarray::array![[0.0, 0.5, 0.0, 2.0], [0.1, 0.0, 0.0, 0.0]];
+
+        let results = postprocess(outputs, &[TokenCount::default(); 2], None, 0.0);
+
+        assert_eq!(results[0].indices, vec![1, 3]);
+        assert_eq!(results[0].values, vec![0.5, 2.0]);
+        assert_eq!(results[1].indices, vec![0]);
+
+        let results = postprocess(
+            ndarray::array![[0.0, 0.5, 0.0, 2.0]],
+            &[TokenCount::default()],
+            None,
+            1.0,
+        );
+        assert_eq!(results[0].indices, vec![3]);
+    }
+
+    #[test]
+    fn test_postprocess_top_k() {
+        let outputs = ndarray::array![[0.3, 0.5, 0.0, 2.0, 0.1]];
+
+        let results = postprocess(outputs, &[TokenCount::default()], Some(2), 0.0);
+
+        // sorted by index, not weight
+        assert_eq!(results[0].indices, vec![1, 3]);
+        assert_eq!(results[0].values, vec![0.5, 2.0]);
+    }
+}
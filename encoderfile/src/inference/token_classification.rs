@@ -1,34 +1,53 @@
 use crate::{
-    common::{TokenClassification, TokenClassificationResult, TokenInfo},
+    common::{
+        AggregationStrategy, InferenceOptions, ModelOutputs, OffsetMapping, RedactedSpan,
+        Redaction, RedactionMode, TokenClassification, TokenClassificationEntity,
+        TokenClassificationResult, TokenInfo, WordClassification,
+    },
     error::ApiError,
-    runtime::ClassifierState,
+    inference::{
+        inputs::InputBinder,
+        sequence_classification::remap,
+        utils::{extra_outputs, get_output},
+    },
+    runtime::{ClassifierState, token_count},
     transforms::{Postprocessor, TokenClassificationTransform},
 };
-use ndarray::{Array3, Axis, Ix3};
+use ndarray::{Array1, Array3, ArrayView1, Axis, Ix3};
 use ndarray_stats::QuantileExt;
+use std::collections::HashMap;
 use tokenizers::Encoding;
 
 #[tracing::instrument(skip_all)]
 pub fn token_classification<'a>(
     mut session: crate::runtime::Model<'a>,
     transform: &TokenClassificationTransform,
+    model_outputs: &ModelOutputs,
+    inputs: &InputBinder,
     config: &ClassifierState,
     encodings: Vec<Encoding>,
+    options: &InferenceOptions,
 ) -> Result<Vec<TokenClassificationResult>, ApiError> {
-    let (a_ids, a_mask, a_type_ids) = crate::prepare_text_inputs!(encodings);
+    let raw_outputs = crate::run_model!(session, inputs, encodings)?;
 
-    let mut outputs = crate::run_model!(session, a_ids, a_mask, a_type_ids)?
-        .get("logits")
-        .expect("Model does not return logits")
-        .try_extract_array::<f32>()
-        .expect("Model does not return tensor extractable to f32")
+    let mut outputs = get_output(&raw_outputs, model_outputs, "logits")?
         .into_dimensionality::<Ix3>()
-        .expect("Model does not return tensor of shape [n_batch, n_tokens, n_labels]")
+        .map_err(|_| {
+            ApiError::InternalError(
+                "Model does not return tensor of shape [n_batch, n_tokens, n_labels]",
+            )
+        })?
         .into_owned();
 
+    let extra = extra_outputs(&raw_outputs, model_outputs, transform, &encodings)?;
+
     outputs = transform.postprocess(outputs)?;
 
-    let predictions = postprocess(outputs, encodings, config);
+    let mut predictions = postprocess(outputs, encodings, config, options);
+
+    for (prediction, extra) in predictions.iter_mut().zip(extra) {
+        prediction.outputs = extra;
+    }
 
     Ok(predictions)
 }
@@ -38,6 +57,7 @@ pub fn postprocess(
     outputs: Array3<f32>,
     encodings: Vec<Encoding>,
     config: &ClassifierState,
+    options: &InferenceOptions,
 ) -> Vec<TokenClassificationResult> {
     let mut predictions = Vec::new();
 
@@ -45,22 +65,16 @@ pub fn postprocess(
         let mut results = Vec::new();
 
         for i in 0..encoding.len() {
-            let argmax = logits
-                .index_axis(Axis(0), i)
-                .argmax()
-                .expect("Model has 0 labels");
-            let score = logits.index_axis(Axis(0), i)[argmax];
-            let label = match config.id2label(argmax as u32) {
-                Some(l) => l.to_string(),
-                None => {
-                    panic!(
-                        "FATAL: No label found for ID {argmax}. Check to make sure that your config is correct."
-                    )
-                }
-            };
+            let scores = label_scores(logits.index_axis(Axis(0), i), config);
+            let argmax = scores.argmax().expect("Model has 0 labels");
+            let score = scores[argmax];
+            let label = label_for(config, argmax);
             let (start, end) = encoding.get_offsets()[i];
 
-            if encoding.get_special_tokens_mask()[i] == 1 {
+            // padding is a special token too, but is never returned
+            if encoding.get_attention_mask()[i] == 0
+                || (encoding.get_special_tokens_mask()[i] == 1 && !options.include_special_tokens())
+            {
                 continue;
             }
 
@@ -73,16 +87,515 @@ pub fn postprocess(
                 },
                 score,
                 label,
-                scores: logits
-                    .index_axis(Axis(0), i)
-                    .to_owned()
-                    .into_raw_vec_and_offset()
-                    .0,
+                scores: scores.into_raw_vec_and_offset().0,
             })
         }
 
-        predictions.push(TokenClassificationResult { tokens: results });
+        let count = token_count(encoding);
+
+        predictions.push(TokenClassificationResult {
+            tokens: results,
+            entities: None,
+            words: None,
+            num_tokens: count.num_tokens,
+            truncated: count.truncated,
+            outputs: None,
+        });
     }
 
     predictions
 }
+
+/// Groups the token predictions of `result` into entities.
+///
+/// `result` must be the output of [`postprocess`] for `encoding`, and `text` the input
+/// `encoding` was produced from.
+#[tracing::instrument(skip_all)]
+pub fn aggregate_entities(
+    result: &TokenClassificationResult,
+    encoding: &Encoding,
+    text: &str,
+    config: &ClassifierState,
+    strategy: AggregationStrategy,
+    ignore_labels: &[String],
+) -> Vec<TokenClassificationEntity> {
+    let (tokens, word_ids) = word_tokens(result, encoding);
+    let tokens = tokens.as_slice();
+
+    let spans: Vec<Span> = match strategy {
+        AggregationStrategy::None | AggregationStrategy::Simple => {
+            tokens.iter().map(|token| Span::from(*token)).collect()
+        }
+        AggregationStrategy::First | AggregationStrategy::Max | AggregationStrategy::Average => {
+            group_words(tokens, &word_ids)
+                .into_iter()
+                .map(|word| word_span(word, config, strategy))
+                .collect()
+        }
+    };
+
+    let mut groups: Vec<EntityGroup> = Vec::new();
+
+    for span in spans.iter() {
+        let (is_begin, tag) = match strategy {
+            AggregationStrategy::None => (true, span.label.as_str()),
+            _ => split_tag(span.label.as_str()),
+        };
+
+        match groups.last_mut() {
+            Some(group) if !is_begin && group.tag == tag => {
+                group.score_sum += span.score;
+                group.n_spans += 1;
+                group.end = span.end;
+            }
+            _ => groups.push(EntityGroup {
+                tag,
+                score_sum: span.score,
+                n_spans: 1,
+                start: span.start,
+                end: span.end,
+            }),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|group| !ignore_labels.iter().any(|l| l == group.tag))
+        .map(|group| TokenClassificationEntity {
+            entity_group: group.tag.to_string(),
+            score: group.score_sum / group.n_spans as f32,
+            word: text
+                .get(group.start..group.end)
+                .unwrap_or_default()
+                .to_string(),
+            start: group.start,
+            end: group.end,
+        })
+        .collect()
+}
+
+/// The non-special tokens of `result`, and their word ids.
+fn word_tokens<'a>(
+    result: &'a TokenClassificationResult,
+    encoding: &Encoding,
+) -> (Vec<&'a TokenClassification>, Vec<Option<u32>>) {
+    // `result` only holds special tokens if they were requested; they never form entities
+    let special_tokens_mask: Vec<u32> = encoding
+        .get_special_tokens_mask()
+        .iter()
+        .zip(encoding.get_attention_mask())
+        .filter(|(_, attention)| **attention == 1)
+        .map(|(special, _)| *special)
+        .collect();
+    let tokens: Vec<&TokenClassification> = match result.tokens.len() == special_tokens_mask.len() {
+        true => result
+            .tokens
+            .iter()
+            .zip(special_tokens_mask.iter())
+            .filter(|(_, special)| **special == 0)
+            .map(|(token, _)| token)
+            .collect(),
+        false => result.tokens.iter().collect(),
+    };
+
+    // word ids of the tokens kept above, i.e. excluding special tokens
+    let word_ids: Vec<Option<u32>> = encoding
+        .get_word_ids()
+        .iter()
+        .zip(encoding.get_special_tokens_mask())
+        .filter(|(_, special)| **special == 0)
+        .map(|(word_id, _)| *word_id)
+        .collect();
+
+    (tokens, word_ids)
+}
+
+/// Labels each word of a pre-tokenized input from its sub-word tokens, with the `max` or
+/// `average` strategy, or else from its first token.
+///
+/// `result` must be the output of [`postprocess`] for `encoding`, and `words` the words
+/// `encoding` was produced from. Words cut by truncation are left out.
+#[tracing::instrument(skip_all)]
+pub fn aggregate_words(
+    result: &TokenClassificationResult,
+    encoding: &Encoding,
+    words: &[String],
+    config: &ClassifierState,
+    strategy: AggregationStrategy,
+) -> Vec<WordClassification> {
+    let (tokens, word_ids) = word_tokens(result, encoding);
+    let strategy = match strategy {
+        AggregationStrategy::Max | AggregationStrategy::Average => strategy,
+        _ => AggregationStrategy::First,
+    };
+
+    let mut begin = 0;
+    let mut predictions = Vec::new();
+
+    for word in group_words(&tokens, &word_ids) {
+        let word_id = word_ids[begin];
+        begin += word.len();
+
+        if let Some(word_index) = word_id.map(|i| i as usize) {
+            let span = word_span(word, config, strategy);
+
+            predictions.push(WordClassification {
+                word_index,
+                word: words[word_index].clone(),
+                label: span.label,
+                score: span.score,
+            });
+        }
+    }
+
+    predictions
+}
+
+/// Converts the byte offsets of the tokens and entities of `result` to `mapping`. `text` is
+/// the input `result` was produced from.
+pub fn convert_offsets(result: &mut TokenClassificationResult, text: &str, mapping: OffsetMapping) {
+    if mapping == OffsetMapping::Byte {
+        return;
+    }
+
+    let converter = mapping.converter(text);
+
+    for token in result.tokens.iter_mut() {
+        token.token_info.start = converter.convert(token.token_info.start);
+        token.token_info.end = converter.convert(token.token_info.end);
+    }
+
+    for entity in result.entities.iter_mut().flatten() {
+        entity.start = converter.convert(entity.start);
+        entity.end = converter.convert(entity.end);
+    }
+}
+
+/// Replaces `entities` in `text`. Offsets of `entities` and of the returned spans are byte
+/// offsets.
+///
+/// `pseudonyms` holds the words seen so far per entity group, so that an entity gets the same
+/// pseudonym in every input of a request.
+#[tracing::instrument(skip_all)]
+pub fn redact(
+    text: &str,
+    entities: Vec<TokenClassificationEntity>,
+    mode: RedactionMode,
+    mask_char: char,
+    pseudonyms: &mut HashMap<String, Vec<String>>,
+) -> Redaction {
+    let mut redacted = String::with_capacity(text.len());
+    let mut spans = Vec::new();
+    let mut last = 0;
+
+    for entity in entities {
+        let word = match text.get(entity.start..entity.end) {
+            Some(word) if entity.start >= last => word,
+            _ => continue,
+        };
+
+        let replacement = match mode {
+            RedactionMode::Label => format!("[{}]", entity.entity_group),
+            RedactionMode::Mask => mask_char.to_string().repeat(word.chars().count()),
+            RedactionMode::Pseudonym => {
+                let seen = pseudonyms.entry(entity.entity_group.clone()).or_default();
+                let n = match seen.iter().position(|w| w == word) {
+                    Some(i) => i + 1,
+                    None => {
+                        seen.push(word.to_string());
+                        seen.len()
+                    }
+                };
+                format!("[{}_{}]", entity.entity_group, n)
+            }
+        };
+
+        redacted.push_str(&text[last..entity.start]);
+        redacted.push_str(&replacement);
+        last = entity.end;
+
+        spans.push(RedactedSpan {
+            entity_group: entity.entity_group,
+            score: entity.score,
+            start: entity.start,
+            end: entity.end,
+            replacement,
+        });
+    }
+
+    redacted.push_str(&text[last..]);
+
+    Redaction {
+        text: redacted,
+        spans,
+    }
+}
+
+/// A labeled span of text, either a single token or a whole word.
+struct Span {
+    label: String,
+    score: f32,
+    start: usize,
+    end: usize,
+}
+
+impl From<&TokenClassification> for Span {
+    fn from(token: &TokenClassification) -> Self {
+        Span {
+            label: token.label.clone(),
+            score: token.score,
+            start: token.token_info.start,
+            end: token.token_info.end,
+        }
+    }
+}
+
+impl Span {
+    fn spanning(self, word: &[&TokenClassification]) -> Self {
+        Span {
+            start: word[0].token_info.start,
+            end: word[word.len() - 1].token_info.end,
+            ..self
+        }
+    }
+}
+
+struct EntityGroup<'a> {
+    tag: &'a str,
+    score_sum: f32,
+    n_spans: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Splits consecutive tokens into words. Tokens without a word id are their own word.
+fn group_words<'a, 'b>(
+    tokens: &'a [&'b TokenClassification],
+    word_ids: &[Option<u32>],
+) -> Vec<&'a [&'b TokenClassification]> {
+    let mut words = Vec::new();
+    let mut begin = 0;
+
+    for i in 1..=tokens.len() {
+        if i == tokens.len() || word_ids[i].is_none() || word_ids[i] != word_ids[i - 1] {
+            words.push(&tokens[begin..i]);
+            begin = i;
+        }
+    }
+
+    words
+}
+
+/// Labels `word` with the `max` or `average` strategy, or else from its first token.
+fn word_span(
+    word: &[&TokenClassification],
+    config: &ClassifierState,
+    strategy: AggregationStrategy,
+) -> Span {
+    match strategy {
+        AggregationStrategy::Max => {
+            let best = word
+                .iter()
+                .max_by(|a, b| a.score.total_cmp(&b.score))
+                .expect("Words have at least one token");
+            Span::from(*best).spanning(word)
+        }
+        AggregationStrategy::Average => average_word(word, config),
+        _ => Span::from(word[0]).spanning(word),
+    }
+}
+
+fn average_word(word: &[&TokenClassification], config: &ClassifierState) -> Span {
+    let mut scores = vec![0.0f32; word[0].scores.len()];
+
+    for token in word {
+        for (acc, score) in scores.iter_mut().zip(token.scores.iter()) {
+            *acc += score / word.len() as f32;
+        }
+    }
+
+    let (argmax, score) = scores
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("Model has 0 labels");
+
+    Span {
+        label: label_for(config, argmax),
+        score,
+        start: word[0].token_info.start,
+        end: word[word.len() - 1].token_info.end,
+    }
+}
+
+/// Splits an IOB tag into whether it begins an entity and the entity type.
+/// Labels without a `B-` or `I-` prefix are treated as inside tags.
+fn split_tag(label: &str) -> (bool, &str) {
+    if let Some(tag) = label.strip_prefix("B-") {
+        (true, tag)
+    } else if let Some(tag) = label.strip_prefix("I-") {
+        (false, tag)
+    } else {
+        (false, label)
+    }
+}
+
+/// Scores of a token by label, after build-time label overrides: the probabilities of merged
+/// labels are summed and hidden labels are dropped.
+fn label_scores(scores: ArrayView1<f32>, config: &ClassifierState) -> Array1<f32> {
+    match &config.label_map {
+        Some(label_map) => remap(
+            scores,
+            label_map,
+            config.num_labels().unwrap_or_default(),
+            |a, b| a + b,
+        ),
+        None => scores.to_owned(),
+    }
+}
+
+fn label_for(config: &ClassifierState, id: usize) -> String {
+    match config.id2label(id as u32) {
+        Some(l) => l.to_string(),
+        None => {
+            panic!(
+                "FATAL: No label found for ID {id}. Check to make sure that your config is correct."
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(start: usize, end: usize) -> TokenClassification {
+        TokenClassification {
+            token_info: TokenInfo {
+                token: String::new(),
+                token_id: 0,
+                start,
+                end,
+            },
+            scores: vec![1.0],
+            label: "O".to_string(),
+            score: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_label_scores_with_overrides() {
+        // B-PER and I-PER are merged into PER, and the last label is hidden
+        let config = ClassifierState {
+            id2label: Some(HashMap::from([
+                (0, "O".to_string()),
+                (1, "PER".to_string()),
+            ])),
+            label2id: None,
+            num_labels: Some(2),
+            problem_type: None,
+            calibration: None,
+            label_map: Some(vec![Some(0), Some(1), Some(1), None]),
+            label_descriptions: None,
+            label_thresholds: None,
+        };
+
+        let scores = label_scores(ndarray::arr1(&[0.1, 0.3, 0.25, 0.35]).view(), &config);
+        let argmax = scores.argmax().unwrap();
+
+        assert_eq!(scores.len(), 2);
+        assert!((scores[1] - 0.55).abs() < 1e-6);
+        assert_eq!(label_for(&config, argmax), "PER");
+    }
+
+    #[test]
+    fn test_split_tag() {
+        assert_eq!(split_tag("B-PER"), (true, "PER"));
+        assert_eq!(split_tag("I-PER"), (false, "PER"));
+        assert_eq!(split_tag("O"), (false, "O"));
+    }
+
+    #[test]
+    fn test_group_words() {
+        let tokens: Vec<TokenClassification> = [(0, 2), (2, 4), (5, 8), (8, 9)]
+            .map(|(s, e)| token(s, e))
+            .into();
+        let tokens: Vec<&TokenClassification> = tokens.iter().collect();
+        let word_ids = [Some(0), Some(0), Some(1), None];
+
+        let words = group_words(&tokens, &word_ids);
+
+        assert_eq!(
+            words.iter().map(|w| w.len()).collect::<Vec<_>>(),
+            vec![2, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_redact() {
+        let text = "Ana met Bob and Ana";
+        let entities = || {
+            Vec::from(
+                [(0, 3), (8, 11), (16, 19)].map(|(start, end)| TokenClassificationEntity {
+                    entity_group: "PER".to_string(),
+                    score: 0.9,
+                    word: text[start..end].to_string(),
+                    start,
+                    end,
+                }),
+            )
+        };
+
+        let redaction = redact(
+            text,
+            entities(),
+            RedactionMode::Label,
+            '*',
+            &mut HashMap::new(),
+        );
+        assert_eq!(redaction.text, "[PER] met [PER] and [PER]");
+        assert_eq!(redaction.spans.len(), 3);
+        assert_eq!((redaction.spans[1].start, redaction.spans[1].end), (8, 11));
+
+        let redaction = redact(
+            text,
+            entities(),
+            RedactionMode::Mask,
+            '#',
+            &mut HashMap::new(),
+        );
+        assert_eq!(redaction.text, "### met ### and ###");
+
+        // pseudonyms carry over between inputs of a request
+        let mut pseudonyms = HashMap::new();
+        let redaction = redact(
+            text,
+            entities(),
+            RedactionMode::Pseudonym,
+            '*',
+            &mut pseudonyms,
+        );
+        assert_eq!(redaction.text, "[PER_1] met [PER_2] and [PER_1]");
+
+        let redaction = redact(
+            "Bob",
+            vec![TokenClassificationEntity {
+                entity_group: "PER".to_string(),
+                score: 0.9,
+                word: "Bob".to_string(),
+                start: 0,
+                end: 3,
+            }],
+            RedactionMode::Pseudonym,
+            '*',
+            &mut pseudonyms,
+        );
+        assert_eq!(redaction.text, "[PER_2]");
+    }
+
+    #[test]
+    fn test_group_words_empty() {
+        assert!(group_words(&[], &[]).is_empty());
+    }
+}
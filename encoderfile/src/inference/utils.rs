@@ -1,48 +1,23 @@
-use ndarray::{Array2, Axis};
-use ort::session::Session;
+use std::collections::HashMap;
+
+use ndarray::{Array, Array2, ArrayD, ArrayViewD, Axis, Dimension};
+use ort::session::{Session, SessionOutputs};
 use parking_lot::MutexGuard;
+use tokenizers::Encoding;
 
-#[macro_export]
-macro_rules! prepare_text_inputs {
-    ($encodings:ident) => {{
-        let padded_token_length = $encodings[0].len();
-
-        // Get token IDs & mask as a flattened array.
-        let ids: Vec<i64> = $encodings
-            .iter()
-            .flat_map(|e| e.get_ids().iter().map(|i| *i as i64))
-            .collect();
-        let mask: Vec<i64> = $encodings
-            .iter()
-            .flat_map(|e| e.get_attention_mask().iter().map(|i| *i as i64))
-            .collect();
-        let type_ids: Vec<i64> = $encodings
-            .iter()
-            .flat_map(|e| e.get_type_ids().iter().map(|i| *i as i64))
-            .collect();
-
-        // Convert flattened arrays into 2-dimensional tensors of shape [N, L].
-        let a_ids = ort::value::TensorRef::from_array_view((
-            [$encodings.len(), padded_token_length],
-            &*ids,
-        ))
-        .unwrap()
-        .to_owned();
-        let a_mask = ort::value::TensorRef::from_array_view((
-            [$encodings.len(), padded_token_length],
-            &*mask,
-        ))
-        .unwrap()
-        .to_owned();
-        let a_type_ids = ort::value::TensorRef::from_array_view((
-            [$encodings.len(), padded_token_length],
-            &*type_ids,
-        ))
-        .unwrap()
-        .to_owned();
-
-        (a_ids, a_mask, a_type_ids)
-    }};
+use crate::{
+    common::{InferenceOptions, ModelOutputs, OutputTensor, model_type::ModelTypeSpec},
+    error::ApiError,
+    transforms::{Tensor, Transform},
+};
+
+/// `[batch_size, seq_len]` attention mask of encodings padded to the same length.
+pub fn attention_mask(encodings: &[Encoding]) -> Array2<f32> {
+    let seq_len = encodings.first().map(Encoding::len).unwrap_or(0);
+
+    Array2::from_shape_fn((encodings.len(), seq_len), |(i, j)| {
+        encodings[i].get_attention_mask()[j] as f32
+    })
 }
 
 pub fn l2_normalize(mut x: Array2<f32>, axis: Axis) -> Array2<f32> {
@@ -55,21 +30,157 @@ pub fn l2_normalize(mut x: Array2<f32>, axis: Axis) -> Array2<f32> {
     x
 }
 
-pub fn requires_token_type_ids<'a>(session: &MutexGuard<'a, Session>) -> bool {
+/// Keeps the first `dimensions` values of each embedding along the last axis (Matryoshka
+/// truncation) and L2-normalizes them if requested.
+pub fn resize_embeddings<D: Dimension>(
+    embeddings: Array<f32, D>,
+    options: &InferenceOptions,
+) -> Result<Array<f32, D>, ApiError> {
+    if options.dimensions.is_none() && !options.normalize() {
+        return Ok(embeddings);
+    }
+
+    // tensor ops take 1-indexed axes
+    let last_axis = embeddings.ndim() as isize;
+    let mut tensor = Tensor(embeddings.into_dyn());
+
+    if let Some(dimensions) = options.dimensions {
+        if dimensions > tensor.0.shape()[tensor.0.ndim() - 1] {
+            return Err(ApiError::InputError(
+                "dimensions must not exceed the embedding size",
+            ));
+        }
+
+        tensor = tensor
+            .truncate_axis(last_axis, dimensions)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+    }
+
+    if options.normalize() {
+        tensor = tensor
+            .lp_normalize(2.0, last_axis)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+    }
+
+    tensor
+        .into_inner()
+        .into_dimensionality::<D>()
+        .map_err(|_| ApiError::InternalError("Failed to resize embeddings"))
+}
+
+/// Reads the graph output filling `role`, as configured at build time.
+pub fn get_output<'s>(
+    outputs: &'s SessionOutputs,
+    names: &ModelOutputs,
+    role: &str,
+) -> Result<ArrayViewD<'s, f32>, ApiError> {
+    let name = names.name(role);
+
+    outputs
+        .get(name)
+        .ok_or_else(|| {
+            tracing::error!("Model does not return {} (read as {})", name, role);
+            ApiError::InternalError("Model does not return a required output")
+        })?
+        .try_extract_array::<f32>()
+        .map_err(|_| ApiError::InternalError("Model does not return tensor extractable to f32"))
+}
+
+/// Extra outputs of each input of a batch, by output name.
+pub type BatchOutputs = Vec<Option<HashMap<String, OutputTensor>>>;
+
+/// Reads the extra outputs configured at build time, exposes them to `Postprocess` and
+/// splits them by input. Inputs get no outputs if none are configured.
+pub fn extra_outputs<T: ModelTypeSpec>(
+    outputs: &SessionOutputs,
+    names: &ModelOutputs,
+    transform: &Transform<T>,
+    encodings: &[Encoding],
+) -> Result<BatchOutputs, ApiError> {
+    if names.extra.is_empty() {
+        return Ok(vec![None; encodings.len()]);
+    }
+
+    let extra = names
+        .extra
+        .iter()
+        .map(|name| {
+            let output = outputs
+                .get(name.as_str())
+                .ok_or_else(|| {
+                    tracing::error!("Model does not return extra output {}", name);
+                    ApiError::InternalError("Model does not return a configured extra output")
+                })?
+                .try_extract_array::<f32>()
+                .map_err(|_| {
+                    ApiError::InternalError("Model does not return tensor extractable to f32")
+                })?
+                .into_owned();
+
+            Ok((name.clone(), output))
+        })
+        .collect::<Result<HashMap<_, _>, ApiError>>()?;
+
+    transform.set_outputs(&extra)?;
+
+    let attention_masks: Vec<&[u32]> = encodings.iter().map(|e| e.get_attention_mask()).collect();
+
+    Ok(split_outputs(&extra, &attention_masks)
+        .into_iter()
+        .map(Some)
+        .collect())
+}
+
+/// Splits batched extra outputs by input. Outputs with a token axis keep only the
+/// positions of each input's attention mask, dropping padding.
+pub fn split_outputs(
+    outputs: &HashMap<String, ArrayD<f32>>,
+    attention_masks: &[&[u32]],
+) -> Vec<HashMap<String, OutputTensor>> {
+    attention_masks
+        .iter()
+        .enumerate()
+        .map(|(i, mask)| {
+            let tokens: Vec<usize> = mask
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| **m == 1)
+                .map(|(j, _)| j)
+                .collect();
+
+            outputs
+                .iter()
+                .map(|(name, output)| {
+                    let mut output = output.index_axis(Axis(0), i).to_owned();
+
+                    if output.ndim() > 0 && output.shape()[0] == mask.len() {
+                        output = output.select(Axis(0), &tokens);
+                    }
+
+                    let tensor = OutputTensor {
+                        shape: output.shape().to_vec(),
+                        values: output.iter().copied().collect(),
+                    };
+
+                    (name.clone(), tensor)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn requires_attention_mask<'a>(session: &MutexGuard<'a, Session>) -> bool {
     session
         .inputs
         .iter()
-        .any(|inp| inp.name == "token_type_ids")
+        .any(|inp| inp.name == "attention_mask")
 }
 
+/// Runs a text model on `encodings`, bound to its graph inputs by `inputs`.
 #[macro_export]
 macro_rules! run_model {
-    ($session:expr, $a_ids:expr, $a_mask:expr, $a_type_ids:expr) => {{
-        match $crate::inference::utils::requires_token_type_ids(&$session) {
-            true => $session.run(ort::inputs!($a_ids, $a_mask, $a_type_ids)),
-            false => $session.run(ort::inputs!($a_ids, $a_mask)),
-        }
-        .map_err(|e| {
+    ($session:expr, $inputs:expr, $encodings:expr) => {{
+        $session.run($inputs.bind(&$encodings)?).map_err(|e| {
             tracing::error!("Error running model: {:?}", e);
             $crate::error::ApiError::InternalError("Error running model")
         })
@@ -85,3 +196,86 @@ macro_rules! run_cv_model {
         })
     }};
 }
+
+/// Runs an audio model on `input_values`, plus `attention_mask` if the model takes one.
+#[macro_export]
+macro_rules! run_audio_model {
+    ($session:expr, $features:expr) => {{
+        let input_values = ort::value::TensorRef::from_array_view(&$features.input_values)
+            .map_err(|_| $crate::error::ApiError::InternalError("Failed to create audio tensor"))?
+            .to_owned();
+
+        match $crate::inference::utils::requires_attention_mask(&$session) {
+            true => {
+                let attention_mask =
+                    ort::value::TensorRef::from_array_view(&$features.attention_mask)
+                        .map_err(|_| {
+                            $crate::error::ApiError::InternalError(
+                                "Failed to create attention mask tensor",
+                            )
+                        })?
+                        .to_owned();
+                $session.run(ort::inputs! {
+                    "input_values" => input_values,
+                    "attention_mask" => attention_mask,
+                })
+            }
+            false => $session.run(ort::inputs! { "input_values" => input_values }),
+        }
+        .map_err(|e| {
+            tracing::error!("Error running model: {:?}", e);
+            $crate::error::ApiError::InternalError("Error running model")
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{Array1, Array3};
+
+    #[test]
+    fn test_split_outputs() {
+        let outputs = HashMap::from([
+            (
+                "pooler_output".to_string(),
+                Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0])
+                    .unwrap()
+                    .into_dyn(),
+            ),
+            (
+                "hidden_states".to_string(),
+                Array3::from_shape_fn((2, 3, 2), |(i, j, k)| (i * 6 + j * 2 + k) as f32).into_dyn(),
+            ),
+        ]);
+        let masks: [&[u32]; 2] = [&[1, 1, 1], &[1, 1, 0]];
+
+        let results = split_outputs(&outputs, &masks);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[1]["pooler_output"],
+            OutputTensor {
+                shape: vec![2],
+                values: vec![3.0, 4.0],
+            }
+        );
+        assert_eq!(results[0]["hidden_states"].shape, vec![3, 2]);
+        // padding is dropped from token-level outputs
+        assert_eq!(
+            results[1]["hidden_states"],
+            OutputTensor {
+                shape: vec![2, 2],
+                values: vec![6.0, 7.0, 8.0, 9.0],
+            }
+        );
+
+        let scalars = HashMap::from([(
+            "score".to_string(),
+            Array1::from_vec(vec![0.5, 0.25]).into_dyn(),
+        )]);
+        let results = split_outputs(&scalars, &masks);
+        assert_eq!(results[1]["score"].shape, Vec::<usize>::new());
+        assert_eq!(results[1]["score"].values, vec![0.25]);
+    }
+}
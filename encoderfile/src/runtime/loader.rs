@@ -5,10 +5,16 @@ use std::io::{Read, Seek};
 use ort::session::{Session, builder::GraphOptimizationLevel};
 
 use crate::{
-    common::{Config, LuaLibs, ModelConfig, model_type::ModelType},
+    common::{
+        Config, Corpus, LuaLibs, ModelConfig, ModelInputs, ModelOutputs, Projection,
+        model_type::ModelType,
+    },
     format::{assets::AssetKind, codec::EncoderfileCodec, container::Encoderfile},
     generated::manifest::{self, TransformType},
-    runtime::{ImagePreprocessing, ORTExecutionProvider, ORTSessionBuilder, TokenizerService},
+    runtime::{
+        AudioFeatureExtractor, ImagePreprocessing, ORTExecutionProvider, ORTSessionBuilder,
+        TokenizerService,
+    },
 };
 
 pub struct EncoderfileLoader<'a, R: Read + Seek> {
@@ -56,6 +62,35 @@ impl<'a, R: Read + Seek> EncoderfileLoader<'a, R> {
         Ok(session)
     }
 
+    /// Session for the vision tower of dual-tower models, if the encoderfile has one.
+    pub fn vision_session(
+        &mut self,
+        execution_provider: ORTExecutionProvider,
+        enable_cpu_fallback: bool,
+        graph_optimization_level: Option<GraphOptimizationLevel>,
+    ) -> Result<Option<Session>> {
+        let session = match self
+            .encoderfile
+            .open_optional(self.reader, AssetKind::VisionModelWeights)
+        {
+            Some(mut r) => {
+                let mut buf = vec![0u8; r.len() as usize];
+                r.read_exact(&mut buf)?;
+
+                let builder = ORTSessionBuilder {
+                    execution_provider,
+                    enable_cpu_fallback,
+                    graph_optimization_level,
+                };
+
+                Some(builder.from_memory(buf.as_slice())?)
+            }
+            None => None,
+        };
+
+        Ok(session)
+    }
+
     pub fn tokenizer(&mut self) -> Result<TokenizerService> {
         match self
             .encoderfile
@@ -130,6 +165,16 @@ impl<'a, R: Read + Seek> EncoderfileLoader<'a, R> {
         }
     }
 
+    /// Graph output names and extra outputs set at build time.
+    pub fn model_outputs(&mut self) -> Result<ModelOutputs> {
+        Ok(self.model_config()?.outputs.unwrap_or_default())
+    }
+
+    /// Graph input names of text models set at build time.
+    pub fn model_inputs(&mut self) -> Result<ModelInputs> {
+        Ok(self.model_config()?.inputs.unwrap_or_default())
+    }
+
     pub fn image_preprocessor_config(&mut self) -> Result<ImagePreprocessing> {
         match self
             .encoderfile
@@ -144,6 +189,55 @@ impl<'a, R: Read + Seek> EncoderfileLoader<'a, R> {
             Err(e) => bail!("Error loading image preprocessor config: {e:?}"),
         }
     }
+
+    pub fn audio_feature_extractor_config(&mut self) -> Result<AudioFeatureExtractor> {
+        match self
+            .encoderfile
+            .open_required(self.reader, AssetKind::AudioFeatureExtractor)
+        {
+            Ok(mut r) => {
+                let mut buf = vec![0u8; r.len() as usize];
+                r.read_exact(&mut buf)?;
+
+                Ok(serde_json::from_slice(buf.as_slice())?)
+            }
+            Err(e) => bail!("Error loading audio feature extractor config: {e:?}"),
+        }
+    }
+
+    pub fn projection(&mut self) -> Result<Option<Projection>> {
+        let projection = match self
+            .encoderfile
+            .open_optional(self.reader, AssetKind::Projection)
+        {
+            Some(mut r) => {
+                let mut buf = vec![0u8; r.len() as usize];
+                r.read_exact(&mut buf)?;
+
+                Some(serde_json::from_slice(buf.as_slice())?)
+            }
+            None => None,
+        };
+
+        Ok(projection)
+    }
+
+    pub fn corpus(&mut self) -> Result<Option<Corpus>> {
+        let corpus = match self
+            .encoderfile
+            .open_optional(self.reader, AssetKind::Corpus)
+        {
+            Some(mut r) => {
+                let mut buf = vec![0u8; r.len() as usize];
+                r.read_exact(&mut buf)?;
+
+                Some(serde_json::from_slice(buf.as_slice())?)
+            }
+            None => None,
+        };
+
+        Ok(corpus)
+    }
 }
 
 pub fn load_assets<'a, R: Read + Seek>(file: &'a mut R) -> Result<EncoderfileLoader<'a, R>> {
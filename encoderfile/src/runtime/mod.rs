@@ -9,10 +9,12 @@ mod tokenizer;
 pub use loader::{EncoderfileLoader, load_assets};
 pub use session::{ORTExecutionProvider, ORTSessionBuilder};
 pub use state::{
-    AppState, ClassifierState, EncoderfileState, FeatureExtractorState, ImageConfig,
-    ImageInputState, ImagePreprocessing, ImageSize, Input, InputType, Task, TaskType,
-    TextInputState,
+    AppState, AudioFeatureExtractor, AudioInputState, ClassifierState, CorpusState,
+    EncoderfileState, FeatureExtractorState, ImageConfig, ImageEmbeddingState, ImageInputState,
+    ImagePreprocessing, ImageSize, Input, InputType, LateInteractionState,
+    MultimodalEmbeddingState, MultimodalInputState, ObjectDetectionState, ProjectionState,
+    SentenceEmbeddingState, Task, TaskType, TextInputState,
 };
-pub use tokenizer::TokenizerService;
+pub use tokenizer::{TokenizerService, token_count};
 
 pub type Model<'a> = MutexGuard<'a, Session>;
@@ -7,14 +7,20 @@ use std::{
     sync::Arc,
 };
 
+use ndarray::{Array1, Array2};
 use ort::session::Session;
 use parking_lot::Mutex;
 
 use crate::{
     common::{
-        Config, ModelConfig,
+        Calibration, Config, Corpus, CorpusDocument, CorpusIndex, DEFAULT_MULTI_LABEL_THRESHOLD,
+        ImageEmbeddingConfig, InferenceOptions, LabelConfig, LateInteractionConfig, ModelConfig,
+        ModelInputs, ModelOutputs, MultimodalEmbeddingConfig, ObjectDetectionConfig, ProblemType,
+        Projection,
         model_type::{self, ModelType, ModelTypeSpec},
     },
+    error::ApiError,
+    inference::inputs::InputBinder,
     runtime::TokenizerService,
     runtime::loader::EncoderfileLoader,
     transforms::DEFAULT_LIBS,
@@ -32,6 +38,8 @@ pub enum Task {
 pub enum Input {
     Text,
     Image,
+    Audio,
+    Multimodal,
 }
 
 pub trait TaskType {
@@ -92,6 +100,37 @@ pub struct ImageSize {
     pub height: Option<u32>,
     pub width: Option<u32>,
     pub shortest_edge: Option<u32>,
+    pub longest_edge: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudioInputState {
+    pub feature_extractor: AudioFeatureExtractor,
+}
+
+/// Feature extractor settings read from `preprocessor_config.json`.
+///
+/// Models with `num_mel_bins` (e.g. AST) take log-mel filter bank features; all others
+/// (e.g. wav2vec2, HuBERT) take the raw waveform.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudioFeatureExtractor {
+    pub feature_extractor_type: Option<String>,
+    pub sampling_rate: u32,
+    pub feature_size: Option<u32>,
+    pub num_mel_bins: Option<usize>,
+    pub do_normalize: Option<bool>,
+    pub padding_value: Option<f32>,
+    pub return_attention_mask: Option<bool>,
+    pub max_length: Option<usize>,
+    pub mean: Option<f32>,
+    pub std: Option<f32>,
+}
+
+/// Text and image inputs of dual-tower models, embedded into a shared space.
+#[derive(Debug)]
+pub struct MultimodalInputState {
+    pub text: TextInputState,
+    pub image: ImageInputState,
 }
 
 impl LuaUserData for ImageInputState {
@@ -125,6 +164,13 @@ impl LuaUserData for ImageInputState {
                 .as_ref()
                 .and_then(|s| s.shortest_edge))
         });
+        fields.add_field_method_get("size_longest_edge", |_, this| {
+            Ok(this
+                .preprocessing
+                .size
+                .as_ref()
+                .and_then(|s| s.longest_edge))
+        });
     }
 }
 
@@ -152,8 +198,132 @@ pub struct ClassifierState {
     pub id2label: Option<std::collections::HashMap<u32, String>>,
     pub label2id: Option<std::collections::HashMap<String, u32>>,
     pub num_labels: Option<usize>,
+    pub problem_type: Option<ProblemType>,
+    pub calibration: Option<Calibration>,
+    /// Label id of each of the model's labels after build-time label overrides, or `None` for
+    /// hidden labels. `id2label` and `label2id` describe the overridden labels.
+    pub label_map: Option<Vec<Option<u32>>>,
+    /// Descriptions of labels set at build time, by label.
+    pub label_descriptions: Option<std::collections::HashMap<String, String>>,
+    /// Thresholds set at build time, by label id (multi-label models only). Labels without
+    /// one use the request's threshold.
+    pub label_thresholds: Option<Vec<Option<f32>>>,
 }
 impl ClassifierState {
+    /// The labels of `model_config`, with its build-time label overrides applied.
+    pub fn from_model_config(model_config: &ModelConfig) -> Result<Self, anyhow::Error> {
+        let state = Self {
+            id2label: model_config.id2label.clone(),
+            label2id: model_config.label2id.clone(),
+            num_labels: model_config.num_labels(),
+            problem_type: model_config.problem_type,
+            calibration: model_config.calibration.clone(),
+            label_map: None,
+            label_descriptions: None,
+            label_thresholds: None,
+        };
+
+        match &model_config.label_overrides {
+            Some(overrides) => state.with_overrides(overrides),
+            None => Ok(state),
+        }
+    }
+
+    fn with_overrides(
+        self,
+        overrides: &std::collections::HashMap<String, LabelConfig>,
+    ) -> Result<Self, anyhow::Error> {
+        // merging may leave a single label, which must not turn the model into a regressor
+        let problem_type = self.problem_type();
+        if problem_type == ProblemType::Regression {
+            anyhow::bail!("labels are not supported for regression models");
+        }
+
+        let labels = self.labels_by_id();
+        if let Some(unknown) = overrides.keys().find(|label| !labels.contains(label)) {
+            anyhow::bail!("{:?} is not a label of the model", unknown);
+        }
+
+        if problem_type != ProblemType::MultiLabelClassification
+            && overrides.values().any(|config| config.threshold.is_some())
+        {
+            anyhow::bail!("label thresholds are only supported for multi-label models");
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        let mut label_map = Vec::with_capacity(labels.len());
+        let mut descriptions = std::collections::HashMap::new();
+        let mut thresholds: Vec<Option<f32>> = Vec::new();
+
+        for label in labels {
+            let config = overrides.get(&label).cloned().unwrap_or_default();
+
+            if config.hidden {
+                label_map.push(None);
+                continue;
+            }
+
+            let name = config.name.unwrap_or(label);
+            let id = match names.iter().position(|n| *n == name) {
+                Some(id) => id,
+                None => {
+                    names.push(name.clone());
+                    thresholds.push(None);
+                    names.len() - 1
+                }
+            };
+
+            match (thresholds[id], config.threshold) {
+                (Some(a), Some(b)) if a != b => {
+                    anyhow::bail!("labels merged into {:?} have different thresholds", name);
+                }
+                (None, threshold) => thresholds[id] = threshold,
+                _ => {}
+            }
+
+            if let Some(description) = config.description {
+                descriptions.entry(name).or_insert(description);
+            }
+
+            label_map.push(Some(id as u32));
+        }
+
+        if names.is_empty() {
+            anyhow::bail!("at least one label must not be hidden");
+        }
+
+        Ok(Self {
+            id2label: Some(
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(id, name)| (id as u32, name.clone()))
+                    .collect(),
+            ),
+            label2id: Some(
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(id, name)| (name.clone(), id as u32))
+                    .collect(),
+            ),
+            num_labels: Some(names.len()),
+            problem_type: Some(problem_type),
+            calibration: self.calibration,
+            label_map: Some(label_map),
+            label_descriptions: (!descriptions.is_empty()).then_some(descriptions),
+            label_thresholds: thresholds.iter().any(Option::is_some).then_some(thresholds),
+        })
+    }
+
+    /// The threshold of label `id`: its build-time threshold, or `threshold`.
+    pub fn label_threshold(&self, id: usize, threshold: f32) -> f32 {
+        self.label_thresholds
+            .as_ref()
+            .and_then(|thresholds| thresholds.get(id).copied().flatten())
+            .unwrap_or(threshold)
+    }
+
     pub fn id2label(&self, id: u32) -> Option<&str> {
         self.id2label.as_ref()?.get(&id).map(|s| s.as_str())
     }
@@ -162,6 +332,26 @@ impl ClassifierState {
         self.label2id.as_ref()?.get(label).copied()
     }
 
+    /// Labels indexed by class id. Ids missing from `id2label` are named `LABEL_{id}`.
+    pub fn labels_by_id(&self) -> Vec<String> {
+        let num_labels = self
+            .id2label
+            .iter()
+            .flat_map(|id2label| id2label.keys())
+            .map(|id| *id as usize + 1)
+            .chain(self.num_labels())
+            .max()
+            .unwrap_or_default();
+
+        (0..num_labels as u32)
+            .map(|id| {
+                self.id2label(id)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("LABEL_{id}"))
+            })
+            .collect()
+    }
+
     pub fn num_labels(&self) -> Option<usize> {
         if self.num_labels.is_some() {
             return self.num_labels;
@@ -177,11 +367,162 @@ impl ClassifierState {
 
         None
     }
+
+    /// Resolves the problem type the same way `transformers` does when it is not set:
+    /// a single label means regression, anything else single-label classification.
+    pub fn problem_type(&self) -> ProblemType {
+        match (self.problem_type, self.num_labels()) {
+            (Some(problem_type), _) => problem_type,
+            (None, Some(1)) => ProblemType::Regression,
+            (None, _) => ProblemType::SingleLabelClassification,
+        }
+    }
+
+    /// Resolves the threshold of a request. Multi-label models default it to
+    /// `DEFAULT_MULTI_LABEL_THRESHOLD`; other models reject it.
+    pub fn threshold(&self, threshold: Option<f32>) -> Result<Option<f32>, ApiError> {
+        match (self.problem_type(), threshold) {
+            (ProblemType::MultiLabelClassification, threshold) => {
+                Ok(Some(threshold.unwrap_or(DEFAULT_MULTI_LABEL_THRESHOLD)))
+            }
+            (_, Some(_)) => Err(ApiError::InputError(
+                "threshold is only supported by multi-label classification models",
+            )),
+            (_, None) => Ok(None),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FeatureExtractorState {}
 
+#[derive(Debug, Clone)]
+pub struct SentenceEmbeddingState {
+    pub corpus: Option<CorpusState>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorpusState {
+    pub documents: Vec<CorpusDocument>,
+    /// L2-normalized document embeddings of shape `[num_documents, dim]`.
+    pub embeddings: Array2<f32>,
+    pub index: CorpusIndex,
+}
+
+impl TryFrom<Corpus> for CorpusState {
+    type Error = anyhow::Error;
+
+    fn try_from(corpus: Corpus) -> Result<Self, Self::Error> {
+        let num_documents = corpus.documents.len();
+        let dim = corpus.embeddings.first().map(|row| row.len()).unwrap_or(0);
+
+        if num_documents == 0 || dim == 0 {
+            anyhow::bail!("Corpus must contain at least one embedded document");
+        }
+
+        if corpus.embeddings.len() != num_documents {
+            anyhow::bail!(
+                "Corpus must have one embedding per document, got {} for {num_documents} documents",
+                corpus.embeddings.len()
+            );
+        }
+
+        if corpus.embeddings.iter().any(|row| row.len() != dim) {
+            anyhow::bail!("Corpus embeddings must all have length {dim}");
+        }
+
+        if let CorpusIndex::Hnsw(index) = &corpus.index
+            && (index.neighbors.len() != num_documents
+                || index
+                    .neighbors
+                    .get(index.entry_point)
+                    .is_none_or(|layers| layers.is_empty())
+                || index
+                    .neighbors
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .any(|neighbor| *neighbor >= num_documents))
+        {
+            anyhow::bail!("Corpus HNSW index does not match its documents");
+        }
+
+        let embeddings = Array2::from_shape_vec(
+            (num_documents, dim),
+            corpus.embeddings.into_iter().flatten().collect(),
+        )?;
+
+        Ok(Self {
+            documents: corpus.documents,
+            embeddings,
+            index: corpus.index,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageEmbeddingState {
+    pub config: ImageEmbeddingConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectDetectionState {
+    pub labels: ClassifierState,
+    pub config: ObjectDetectionConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct MultimodalEmbeddingState {
+    pub config: MultimodalEmbeddingConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct LateInteractionState {
+    pub config: LateInteractionConfig,
+    pub projection: Option<ProjectionState>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectionState {
+    /// Transposed projection weights of shape `[hidden_dim, dim]`.
+    pub weight: Array2<f32>,
+    pub bias: Option<Array1<f32>>,
+}
+
+impl TryFrom<Projection> for ProjectionState {
+    type Error = anyhow::Error;
+
+    fn try_from(projection: Projection) -> Result<Self, Self::Error> {
+        let dim = projection.weight.len();
+        let hidden_dim = projection.weight.first().map(|row| row.len()).unwrap_or(0);
+
+        if dim == 0 || hidden_dim == 0 {
+            anyhow::bail!("Projection weight must be a non-empty [dim, hidden_dim] matrix");
+        }
+
+        if projection.weight.iter().any(|row| row.len() != hidden_dim) {
+            anyhow::bail!("Projection weight rows must all have length {hidden_dim}");
+        }
+
+        if let Some(bias) = &projection.bias
+            && bias.len() != dim
+        {
+            anyhow::bail!("Projection bias must have length {dim}, got {}", bias.len());
+        }
+
+        let weight = Array2::from_shape_vec(
+            (dim, hidden_dim),
+            projection.weight.into_iter().flatten().collect(),
+        )?
+        .reversed_axes();
+
+        Ok(Self {
+            weight,
+            bias: projection.bias.map(Array1::from),
+        })
+    }
+}
+
 fn text_input_state_try_from_loader<'a, R>(
     loader: &mut EncoderfileLoader<'a, R>,
 ) -> Result<TextInputState, anyhow::Error>
@@ -224,17 +565,125 @@ where
     })
 }
 
+fn multimodal_input_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<MultimodalInputState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let text = text_input_state_try_from_loader(loader)?;
+    let preprocessor_config = loader.image_preprocessor_config()?;
+    let vision_config = text.model_config.vision_config.as_ref();
+
+    // CLIP-style configs nest vision settings under `vision_config`; fall back to the
+    // number of normalization channels when `num_channels` is not serialized.
+    let num_channels = vision_config
+        .and_then(|c| c.num_channels)
+        .or(text.model_config.num_channels)
+        .or(preprocessor_config
+            .image_mean
+            .as_ref()
+            .map(|mean| mean.len() as u32))
+        .ok_or_else(|| anyhow::anyhow!("num_channels is required for multimodal models"))?;
+    let image_size = vision_config
+        .and_then(|c| c.image_size)
+        .or(text.model_config.image_size);
+
+    Ok(MultimodalInputState {
+        text,
+        image: ImageInputState {
+            config: ImageConfig {
+                num_channels,
+                image_size,
+            },
+            preprocessing: preprocessor_config,
+        },
+    })
+}
+
+fn audio_input_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<AudioInputState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    Ok(AudioInputState {
+        feature_extractor: loader.audio_feature_extractor_config()?,
+    })
+}
+
 fn classifier_state_try_from_loader<'a, R>(
     loader: &mut EncoderfileLoader<'a, R>,
 ) -> Result<ClassifierState, anyhow::Error>
 where
     R: Read + Seek,
 {
-    let model_config = loader.model_config()?.clone();
-    Ok(ClassifierState {
-        id2label: model_config.id2label.clone(),
-        label2id: model_config.label2id.clone(),
-        num_labels: model_config.num_labels(),
+    let model_config = loader.model_config()?;
+    ClassifierState::from_model_config(&model_config)
+}
+
+fn late_interaction_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<LateInteractionState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let model_config = loader.model_config()?;
+    let projection = loader
+        .projection()?
+        .map(ProjectionState::try_from)
+        .transpose()?;
+    Ok(LateInteractionState {
+        config: model_config.late_interaction.unwrap_or_default(),
+        projection,
+    })
+}
+
+fn sentence_embedding_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<SentenceEmbeddingState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let corpus = loader.corpus()?.map(CorpusState::try_from).transpose()?;
+    Ok(SentenceEmbeddingState { corpus })
+}
+
+fn image_embedding_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<ImageEmbeddingState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let model_config = loader.model_config()?;
+    Ok(ImageEmbeddingState {
+        config: model_config.image_embedding.unwrap_or_default(),
+    })
+}
+
+fn multimodal_embedding_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<MultimodalEmbeddingState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let model_config = loader.model_config()?;
+    Ok(MultimodalEmbeddingState {
+        config: model_config.multimodal_embedding.unwrap_or_default(),
+    })
+}
+
+fn object_detection_state_try_from_loader<'a, R>(
+    loader: &mut EncoderfileLoader<'a, R>,
+) -> Result<ObjectDetectionState, anyhow::Error>
+where
+    R: Read + Seek,
+{
+    let labels = classifier_state_try_from_loader(loader)?;
+    let model_config = loader.model_config()?;
+    Ok(ObjectDetectionState {
+        labels,
+        config: model_config.object_detection.unwrap_or_default(),
     })
 }
 
@@ -270,12 +719,47 @@ state_from_source_impl!(
     ImageInputState,
     image_input_state_try_from_loader
 );
+state_from_source_impl!(
+    InputType,
+    AudioInputState,
+    audio_input_state_try_from_loader
+);
+state_from_source_impl!(
+    InputType,
+    MultimodalInputState,
+    multimodal_input_state_try_from_loader
+);
 state_from_source_impl!(TaskType, ClassifierState, classifier_state_try_from_loader);
 state_from_source_impl!(
     TaskType,
     FeatureExtractorState,
     feature_extractor_state_try_from_loader
 );
+state_from_source_impl!(
+    TaskType,
+    LateInteractionState,
+    late_interaction_state_try_from_loader
+);
+state_from_source_impl!(
+    TaskType,
+    SentenceEmbeddingState,
+    sentence_embedding_state_try_from_loader
+);
+state_from_source_impl!(
+    TaskType,
+    ImageEmbeddingState,
+    image_embedding_state_try_from_loader
+);
+state_from_source_impl!(
+    TaskType,
+    ObjectDetectionState,
+    object_detection_state_try_from_loader
+);
+state_from_source_impl!(
+    TaskType,
+    MultimodalEmbeddingState,
+    multimodal_embedding_state_try_from_loader
+);
 
 macro_rules! input_state_impl {
     ($model_type:ty, $state_type:ty, $input:expr) => {
@@ -288,6 +772,8 @@ macro_rules! input_state_impl {
 
 input_state_impl!(model_type::Embedding, TextInputState, Input::Text);
 input_state_impl!(model_type::SentenceEmbedding, TextInputState, Input::Text);
+input_state_impl!(model_type::SparseEmbedding, TextInputState, Input::Text);
+input_state_impl!(model_type::LateInteraction, TextInputState, Input::Text);
 input_state_impl!(
     model_type::SequenceClassification,
     TextInputState,
@@ -299,6 +785,20 @@ input_state_impl!(
     ImageInputState,
     Input::Image
 );
+input_state_impl!(model_type::ImageEmbedding, ImageInputState, Input::Image);
+input_state_impl!(model_type::ObjectDetection, ImageInputState, Input::Image);
+input_state_impl!(model_type::ImageSegmentation, ImageInputState, Input::Image);
+input_state_impl!(
+    model_type::AudioClassification,
+    AudioInputState,
+    Input::Audio
+);
+input_state_impl!(model_type::AudioEmbedding, AudioInputState, Input::Audio);
+input_state_impl!(
+    model_type::MultimodalEmbedding,
+    MultimodalInputState,
+    Input::Multimodal
+);
 
 macro_rules! task_state_impl {
     ($model_type:ty, $state_type:ty, $task:expr) => {
@@ -331,9 +831,49 @@ task_state_impl!(
 );
 task_state_impl!(
     model_type::SentenceEmbedding,
+    SentenceEmbeddingState,
+    Task::FeatureExtraction
+);
+task_state_impl!(
+    model_type::SparseEmbedding,
     FeatureExtractorState,
     Task::FeatureExtraction
 );
+task_state_impl!(
+    model_type::LateInteraction,
+    LateInteractionState,
+    Task::FeatureExtraction
+);
+task_state_impl!(
+    model_type::ImageEmbedding,
+    ImageEmbeddingState,
+    Task::FeatureExtraction
+);
+task_state_impl!(
+    model_type::ObjectDetection,
+    ObjectDetectionState,
+    Task::Classification
+);
+task_state_impl!(
+    model_type::ImageSegmentation,
+    ClassifierState,
+    Task::Classification
+);
+task_state_impl!(
+    model_type::AudioClassification,
+    ClassifierState,
+    Task::Classification
+);
+task_state_impl!(
+    model_type::AudioEmbedding,
+    FeatureExtractorState,
+    Task::FeatureExtraction
+);
+task_state_impl!(
+    model_type::MultimodalEmbedding,
+    MultimodalEmbeddingState,
+    Task::FeatureExtraction
+);
 
 macro_rules! input_type_impl {
     [ $( $x:ident ),* $(,)? ] => {
@@ -360,13 +900,27 @@ input_type_impl![
     SequenceClassification,
     TokenClassification,
     SentenceEmbedding,
-    ImageClassification
+    SparseEmbedding,
+    LateInteraction,
+    ImageClassification,
+    ImageEmbedding,
+    ObjectDetection,
+    ImageSegmentation,
+    AudioClassification,
+    AudioEmbedding,
+    MultimodalEmbedding
 ];
 
 #[derive(Debug)]
 pub struct EncoderfileState<T: ModelTypeSpec + InputType + TaskType> {
     pub config: Config,
     pub session: Mutex<Session>,
+    /// Vision tower of dual-tower models; `session` runs the text tower.
+    pub vision_session: Option<Mutex<Session>>,
+    /// Graph output names by role and extra outputs, set at build time.
+    pub outputs: ModelOutputs,
+    /// Binds tokenized inputs to the graph inputs of `session` (text and multimodal models).
+    pub inputs: InputBinder,
     pub model_input_state: <T as InputType>::State,
     pub task_state: <T as TaskType>::State,
     pub lua_libs: Vec<mlua::StdLib>,
@@ -387,6 +941,9 @@ impl<T: ModelTypeSpec + InputType + TaskType> EncoderfileState<T> {
         EncoderfileState {
             config,
             session,
+            vision_session: None,
+            outputs: ModelOutputs::default(),
+            inputs: InputBinder::default(),
             model_input_state,
             task_state,
             lua_libs,
@@ -394,6 +951,25 @@ impl<T: ModelTypeSpec + InputType + TaskType> EncoderfileState<T> {
         }
     }
 
+    pub fn with_vision_session(mut self, vision_session: Option<Session>) -> Self {
+        self.vision_session = vision_session.map(Mutex::new);
+        self
+    }
+
+    pub fn with_outputs(mut self, outputs: ModelOutputs) -> Self {
+        self.outputs = outputs;
+        self
+    }
+
+    /// Binds the graph inputs of `session`, failing if the model takes inputs that cannot be
+    /// filled. Models without text inputs are left unbound.
+    pub fn with_inputs(mut self, inputs: &ModelInputs) -> anyhow::Result<Self> {
+        if matches!(T::input_type(), Input::Text | Input::Multimodal) {
+            self.inputs = InputBinder::new(&self.session.lock().inputs, inputs)?;
+        }
+        Ok(self)
+    }
+
     pub fn transform_str(&self) -> Option<String> {
         self.config.transform.clone()
     }
@@ -405,4 +981,11 @@ impl<T: ModelTypeSpec + InputType + TaskType> EncoderfileState<T> {
     pub fn model_type() -> ModelType {
         T::enum_val()
     }
+
+    /// Rejects per-request options the model type does not support.
+    pub fn validate_options(&self, options: &InferenceOptions) -> Result<(), ApiError> {
+        options
+            .validate(&T::enum_val())
+            .map_err(ApiError::InputError)
+    }
 }
@@ -1,6 +1,9 @@
-use crate::error::ApiError;
+use crate::{
+    common::{TokenCount, Tokenization, Truncate, TruncationMode},
+    error::ApiError,
+};
 use anyhow::Result;
-use tokenizers::{Encoding, tokenizer::Tokenizer};
+use tokenizers::{EncodeInput, Encoding, PaddingDirection, TruncationParams, tokenizer::Tokenizer};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct TokenizerService {
@@ -26,13 +29,419 @@ impl TokenizerService {
 
     #[tracing::instrument(skip_all)]
     pub fn encode_text(&self, text: Vec<String>) -> Result<Vec<Encoding>, ApiError> {
+        check_text(&text)?;
+
+        encode_batch(&self.tokenizer, text)
+    }
+
+    /// Encodes `text` with per-request truncation. `None` keeps the configured truncation, and
+    /// lengths past the configured `max_length` are capped to it. With [`TruncationMode::Error`],
+    /// inputs longer than the truncation length are rejected instead of truncated.
+    ///
+    /// Any other truncation than the configured one is applied to a copy of the tokenizer.
+    #[tracing::instrument(skip_all)]
+    pub fn encode_text_with_truncation(
+        &self,
+        text: Vec<String>,
+        truncate: Option<Truncate>,
+        truncation: TruncationMode,
+    ) -> Result<Vec<Encoding>, ApiError> {
+        check_text(&text)?;
+
+        self.encode_with_truncation(text, truncate, truncation)
+    }
+
+    /// Encodes pre-tokenized inputs, one list of words per input, with per-request
+    /// truncation like [`Self::encode_text_with_truncation`]. Word ids index into each
+    /// input's words, and offsets are relative to the words joined with single spaces.
+    #[tracing::instrument(skip_all)]
+    pub fn encode_words(
+        &self,
+        words: Vec<Vec<String>>,
+        truncate: Option<Truncate>,
+        truncation: TruncationMode,
+    ) -> Result<Vec<Encoding>, ApiError> {
+        if words.is_empty() || words.iter().any(|w| w.is_empty()) {
+            return Err(ApiError::InputError("Cannot tokenize empty list of words"));
+        }
+
+        if words.iter().flatten().any(|w| w.is_empty()) {
+            return Err(ApiError::InputError("Cannot tokenize empty string"));
+        }
+
+        let word_starts: Vec<Vec<usize>> = words
+            .iter()
+            .map(|words| {
+                words
+                    .iter()
+                    .scan(0, |start, word| {
+                        let word_start = *start;
+                        *start += word.len() + 1;
+                        Some(word_start)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let encodings = self.encode_with_truncation(words, truncate, truncation)?;
+
+        Ok(encodings
+            .into_iter()
+            .zip(word_starts.iter())
+            .map(|(encoding, word_starts)| join_word_offsets(encoding, word_starts))
+            .collect())
+    }
+
+    fn encode_with_truncation<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        truncate: Option<Truncate>,
+        truncation: TruncationMode,
+    ) -> Result<Vec<Encoding>, ApiError>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        if truncation == TruncationMode::Error {
+            return self.encode_or_reject(inputs, truncate);
+        }
+
+        let max_length = match truncate {
+            None | Some(Truncate::Enabled(true)) => {
+                return encode_batch(&self.tokenizer, inputs);
+            }
+            Some(Truncate::MaxLength(max_length)) if max_length >= self.max_length() => {
+                return encode_batch(&self.tokenizer, inputs);
+            }
+            Some(Truncate::MaxLength(max_length)) => Some(max_length),
+            Some(Truncate::Enabled(false)) => None,
+        };
+
+        let truncation = max_length.map(|max_length| TruncationParams {
+            max_length,
+            ..self.config.truncation.clone()
+        });
+
+        let mut tokenizer = self.tokenizer.clone();
+        tokenizer.with_truncation(truncation).map_err(|e| {
+            tracing::error!("Error applying truncation: {:?}", e);
+            ApiError::InputError("Invalid truncation length")
+        })?;
+
+        encode_batch(&tokenizer, inputs)
+    }
+
+    /// Encodes `inputs` without truncation and rejects the first input longer than the
+    /// truncation length.
+    fn encode_or_reject<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        truncate: Option<Truncate>,
+    ) -> Result<Vec<Encoding>, ApiError>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        let encodings = self.encode_with_truncation(
+            inputs,
+            Some(Truncate::Enabled(false)),
+            TruncationMode::Truncate,
+        )?;
+
+        let max_length = match truncate {
+            None | Some(Truncate::Enabled(_)) => self.max_length(),
+            Some(Truncate::MaxLength(max_length)) => max_length.min(self.max_length()),
+        };
+
+        for (index, encoding) in encodings.iter().enumerate() {
+            let num_tokens = token_count(encoding).num_tokens;
+
+            if num_tokens > max_length {
+                return Err(ApiError::InputTooLong {
+                    index,
+                    num_tokens,
+                    max_length,
+                });
+            }
+        }
+
+        Ok(encodings)
+    }
+
+    /// Encodes `text` with `prompt` prepended to each input. Offsets stay relative to the
+    /// original inputs: prompt tokens get `(0, 0)` offsets and are flagged as special tokens.
+    #[tracing::instrument(skip_all)]
+    pub fn encode_text_with_prompt(
+        &self,
+        text: Vec<String>,
+        prompt: Option<&str>,
+        truncate: Option<Truncate>,
+        truncation: TruncationMode,
+    ) -> Result<Vec<Encoding>, ApiError> {
+        let prompt = match prompt {
+            Some(prompt) if !prompt.is_empty() => prompt,
+            _ => return self.encode_text_with_truncation(text, truncate, truncation),
+        };
+
         if text.is_empty() || text.iter().any(|i| i.is_empty()) {
             return Err(ApiError::InputError("Cannot tokenize empty string"));
         }
 
-        self.tokenizer.encode_batch(text, true).map_err(|e| {
-            tracing::error!("Error tokenizing text: {}", e);
-            ApiError::InternalError("Error during tokenization")
+        let encodings = self.encode_text_with_truncation(
+            text.into_iter().map(|t| format!("{prompt}{t}")).collect(),
+            truncate,
+            truncation,
+        )?;
+
+        Ok(encodings
+            .into_iter()
+            .map(|encoding| strip_prompt_offsets(encoding, prompt.len()))
+            .collect())
+    }
+
+    /// The number of tokens inputs are truncated to by default.
+    pub fn max_length(&self) -> usize {
+        self.config.truncation.max_length
+    }
+
+    /// Encodes each text on its own and truncates or pads it to exactly `length` tokens
+    /// with `pad_token`, e.g. for ColBERT-style query augmentation with `[MASK]`.
+    ///
+    /// Inputs are truncated to `length`, or to a shorter `truncate` length, keeping special
+    /// tokens such as the trailing `[SEP]`. With [`TruncationMode::Error`], longer inputs are
+    /// rejected instead.
+    #[tracing::instrument(skip_all)]
+    pub fn encode_text_fixed_length(
+        &self,
+        text: Vec<String>,
+        length: usize,
+        pad_token: &str,
+        truncate: Option<Truncate>,
+        truncation: TruncationMode,
+    ) -> Result<Vec<Encoding>, ApiError> {
+        check_text(&text)?;
+
+        let pad_id = self.tokenizer.token_to_id(pad_token).ok_or_else(|| {
+            tracing::error!("Token {} not found in vocabulary", pad_token);
+            ApiError::InternalError("Padding token not found in vocabulary")
+        })?;
+
+        let truncate = Truncate::MaxLength(match truncate {
+            Some(Truncate::MaxLength(max_length)) => max_length.min(length),
+            _ => length,
+        });
+
+        text.into_iter()
+            .enumerate()
+            .map(|(index, t)| {
+                // one input at a time, so that inputs are not padded to the longest one
+                let mut encoding = self
+                    .encode_with_truncation(vec![t], Some(truncate), truncation)
+                    .map_err(|e| match e {
+                        ApiError::InputTooLong {
+                            num_tokens,
+                            max_length,
+                            ..
+                        } => ApiError::InputTooLong {
+                            index,
+                            num_tokens,
+                            max_length,
+                        },
+                        e => e,
+                    })?
+                    .remove(0);
+
+                encoding.pad(length, pad_id, 0, pad_token, PaddingDirection::Right);
+
+                Ok(encoding)
+            })
+            .collect()
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        self.tokenizer.id_to_token(id)
+    }
+
+    /// Tokens of each text, without padding.
+    #[tracing::instrument(skip_all)]
+    pub fn tokenize(
+        &self,
+        text: Vec<String>,
+        truncate: Option<Truncate>,
+    ) -> Result<Vec<Tokenization>, ApiError> {
+        let encodings =
+            self.encode_text_with_truncation(text, truncate, TruncationMode::Truncate)?;
+
+        Ok(encodings.iter().map(tokenization).collect())
+    }
+
+    /// Number of tokens of each text, without truncation.
+    #[tracing::instrument(skip_all)]
+    pub fn count_tokens(&self, text: Vec<String>) -> Result<Vec<TokenCount>, ApiError> {
+        let encodings = self.encode_text_with_truncation(
+            text,
+            Some(Truncate::Enabled(false)),
+            TruncationMode::Truncate,
+        )?;
+
+        Ok(encodings
+            .iter()
+            .map(|encoding| {
+                let num_tokens = token_count(encoding).num_tokens;
+
+                TokenCount {
+                    num_tokens,
+                    truncated: num_tokens > self.max_length(),
+                }
+            })
+            .collect())
+    }
+
+    /// Decodes token ids back into text.
+    #[tracing::instrument(skip_all)]
+    pub fn decode(
+        &self,
+        ids: Vec<Vec<u32>>,
+        skip_special_tokens: bool,
+    ) -> Result<Vec<String>, ApiError> {
+        let vocab_size = self.tokenizer.get_vocab_size(true);
+
+        if ids.iter().flatten().any(|id| *id as usize >= vocab_size) {
+            return Err(ApiError::InputError("Token id is out of the vocabulary"));
+        }
+
+        let ids: Vec<&[u32]> = ids.iter().map(Vec::as_slice).collect();
+
+        self.tokenizer
+            .decode_batch(&ids, skip_special_tokens)
+            .map_err(|e| {
+                tracing::error!("Error decoding tokens: {}", e);
+                ApiError::InternalError("Error during decoding")
+            })
+    }
+}
+
+/// Number of tokens of `encoding` the model attends to, and whether truncation cut the input.
+pub fn token_count(encoding: &Encoding) -> TokenCount {
+    TokenCount {
+        num_tokens: encoding
+            .get_attention_mask()
+            .iter()
+            .filter(|m| **m == 1)
+            .count(),
+        truncated: !encoding.get_overflowing().is_empty(),
+    }
+}
+
+fn tokenization(encoding: &Encoding) -> Tokenization {
+    // padding tokens are the only ones left out of the attention mask
+    let tokens: Vec<usize> = (0..encoding.len())
+        .filter(|i| encoding.get_attention_mask()[*i] == 1)
+        .collect();
+
+    Tokenization {
+        ids: tokens.iter().map(|i| encoding.get_ids()[*i]).collect(),
+        tokens: tokens
+            .iter()
+            .map(|i| encoding.get_tokens()[*i].clone())
+            .collect(),
+        offsets: tokens
+            .iter()
+            .map(|i| {
+                let (start, end) = encoding.get_offsets()[*i];
+                [start, end]
+            })
+            .collect(),
+        special_tokens_mask: tokens
+            .iter()
+            .map(|i| encoding.get_special_tokens_mask()[*i])
+            .collect(),
+        truncated: token_count(encoding).truncated,
+    }
+}
+
+fn strip_prompt_offsets(mut encoding: Encoding, prompt_len: usize) -> Encoding {
+    let overflowing = encoding
+        .take_overflowing()
+        .into_iter()
+        .map(|e| strip_prompt_offsets(e, prompt_len))
+        .collect();
+
+    let (offsets, special_tokens_mask) = encoding
+        .get_offsets()
+        .iter()
+        .zip(encoding.get_special_tokens_mask())
+        .map(|(&(start, end), &special)| match special {
+            1 => ((start, end), special),
+            _ if end <= prompt_len => ((0, 0), 1),
+            // a token straddling the end of the prompt keeps its part in the input
+            _ => (
+                (start.max(prompt_len) - prompt_len, end - prompt_len),
+                special,
+            ),
         })
+        .unzip();
+
+    Encoding::new(
+        encoding.get_ids().to_vec(),
+        encoding.get_type_ids().to_vec(),
+        encoding.get_tokens().to_vec(),
+        encoding.get_word_ids().to_vec(),
+        offsets,
+        special_tokens_mask,
+        encoding.get_attention_mask().to_vec(),
+        overflowing,
+        Default::default(),
+    )
+}
+
+/// Shifts the word-relative offsets of a pre-tokenized `encoding` by the start of each word,
+/// `word_starts`, in the joined text.
+fn join_word_offsets(mut encoding: Encoding, word_starts: &[usize]) -> Encoding {
+    let overflowing = encoding
+        .take_overflowing()
+        .into_iter()
+        .map(|e| join_word_offsets(e, word_starts))
+        .collect();
+
+    let offsets = encoding
+        .get_offsets()
+        .iter()
+        .zip(encoding.get_word_ids())
+        .map(|(&(start, end), word_id)| match word_id {
+            Some(word_id) => {
+                let word_start = word_starts[*word_id as usize];
+                (word_start + start, word_start + end)
+            }
+            None => (start, end),
+        })
+        .collect();
+
+    Encoding::new(
+        encoding.get_ids().to_vec(),
+        encoding.get_type_ids().to_vec(),
+        encoding.get_tokens().to_vec(),
+        encoding.get_word_ids().to_vec(),
+        offsets,
+        encoding.get_special_tokens_mask().to_vec(),
+        encoding.get_attention_mask().to_vec(),
+        overflowing,
+        Default::default(),
+    )
+}
+
+fn check_text(text: &[String]) -> Result<(), ApiError> {
+    if text.is_empty() || text.iter().any(|i| i.is_empty()) {
+        return Err(ApiError::InputError("Cannot tokenize empty string"));
     }
+
+    Ok(())
+}
+
+fn encode_batch<'s, E>(tokenizer: &Tokenizer, inputs: Vec<E>) -> Result<Vec<Encoding>, ApiError>
+where
+    E: Into<EncodeInput<'s>> + Send,
+{
+    tokenizer.encode_batch(inputs, true).map_err(|e| {
+        tracing::error!("Error tokenizing text: {}", e);
+        ApiError::InternalError("Error during tokenization")
+    })
 }
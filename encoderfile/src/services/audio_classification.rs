@@ -0,0 +1,49 @@
+use crate::{
+    common::{
+        AudioClassificationRequest, AudioClassificationResponse, DEFAULT_MULTI_LABEL_THRESHOLD,
+        model_type,
+    },
+    error::ApiError,
+    inference,
+    runtime::AppState,
+    transforms::AudioClassificationTransform,
+};
+
+use super::audio_preprocessing::extract_features;
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::AudioClassification> {
+    type Input = AudioClassificationRequest;
+    type Output = AudioClassificationResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+        if request.audios.is_empty() {
+            return Err(ApiError::InputError("Cannot classify empty audio list"));
+        }
+
+        let threshold = self.task_state.threshold(request.threshold)?;
+
+        let features =
+            extract_features(&self.model_input_state.feature_extractor, &request.audios)?;
+
+        let transform =
+            AudioClassificationTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let results = inference::audio_classification::audio_classification(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.task_state,
+            threshold.unwrap_or(DEFAULT_MULTI_LABEL_THRESHOLD),
+            features,
+        )?;
+
+        Ok(AudioClassificationResponse {
+            results,
+            model_id: self.config.name.clone(),
+            threshold,
+            metadata: request.metadata,
+        })
+    }
+}
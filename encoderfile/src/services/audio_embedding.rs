@@ -0,0 +1,40 @@
+use crate::{
+    common::{AudioEmbeddingRequest, AudioEmbeddingResponse, model_type},
+    error::ApiError,
+    inference,
+    runtime::AppState,
+    transforms::AudioEmbeddingTransform,
+};
+
+use super::audio_preprocessing::extract_features;
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::AudioEmbedding> {
+    type Input = AudioEmbeddingRequest;
+    type Output = AudioEmbeddingResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+        if request.audios.is_empty() {
+            return Err(ApiError::InputError("Cannot embed empty audio list"));
+        }
+
+        let features =
+            extract_features(&self.model_input_state.feature_extractor, &request.audios)?;
+
+        let transform = AudioEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let results = inference::audio_embedding::audio_embedding(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            features,
+        )?;
+
+        Ok(AudioEmbeddingResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
@@ -0,0 +1,561 @@
+use crate::{common::AudioInfo, error::ApiError, runtime::AudioFeatureExtractor};
+use ndarray::{Array2, Array3, ArrayD, Axis, s};
+use rustfft::{FftPlanner, num_complex::Complex};
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as SymphoniaError,
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+/// Longest clip accepted, in seconds of decoded audio.
+pub const MAX_AUDIO_DURATION_SECS: u32 = 600;
+
+/// Number of zero crossings on each side of the resampling filter.
+const RESAMPLE_ZEROS: f64 = 16.0;
+
+/// Kaldi fbank defaults, as used by `ASTFeatureExtractor`.
+const FRAME_LENGTH_MS: u32 = 25;
+const FRAME_SHIFT_MS: u32 = 10;
+const PREEMPHASIS: f32 = 0.97;
+const LOW_FREQ: f32 = 20.0;
+
+/// `ASTFeatureExtractor` defaults (AudioSet statistics).
+const DEFAULT_FBANK_MEAN: f32 = -4.267_739_3;
+const DEFAULT_FBANK_STD: f32 = 4.568_997_4;
+
+/// Batched model inputs for audio models.
+#[derive(Debug)]
+pub struct AudioFeatures {
+    /// Raw waveforms of shape `[batch_size, num_samples]`, or log-mel filter bank
+    /// features of shape `[batch_size, num_frames, num_mel_bins]`.
+    pub input_values: ArrayD<f32>,
+    /// `[batch_size, num_samples]` or `[batch_size, num_frames]`; 0 marks padding.
+    pub attention_mask: Array2<i64>,
+}
+
+impl AudioFeatures {
+    /// Fraction of each clip's time axis that is not padding.
+    pub fn valid_fractions(&self) -> Vec<f32> {
+        let len = self.attention_mask.ncols().max(1) as f32;
+        self.attention_mask
+            .axis_iter(Axis(0))
+            .map(|mask| mask.sum() as f32 / len)
+            .collect()
+    }
+}
+
+/// Decodes, resamples and batches audio clips as the model's feature extractor specifies.
+pub fn extract_features(
+    feature_extractor: &AudioFeatureExtractor,
+    audios: &[AudioInfo],
+) -> Result<AudioFeatures, ApiError> {
+    let sampling_rate = feature_extractor.sampling_rate;
+    let do_normalize = feature_extractor.do_normalize.unwrap_or(true);
+    let padding_value = feature_extractor.padding_value.unwrap_or(0.0);
+
+    let waveforms = audios
+        .iter()
+        .map(|audio| {
+            let (samples, source_rate) = decode_audio(audio)?;
+            if samples.is_empty() {
+                return Err(ApiError::InputError("Audio clip has no samples"));
+            }
+            Ok(resample(&samples, source_rate, sampling_rate))
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    match feature_extractor.num_mel_bins {
+        Some(num_mel_bins) => {
+            let fbanks = waveforms
+                .iter()
+                .map(|waveform| log_mel_fbank(waveform, sampling_rate, num_mel_bins))
+                .collect::<Result<Vec<_>, ApiError>>()?;
+
+            let num_frames = feature_extractor
+                .max_length
+                .or_else(|| fbanks.iter().map(|f| f.nrows()).max())
+                .unwrap_or_default();
+
+            let mut input_values =
+                Array3::from_elem((fbanks.len(), num_frames, num_mel_bins), padding_value);
+            let mut attention_mask = Array2::zeros((fbanks.len(), num_frames));
+
+            for (i, fbank) in fbanks.iter().enumerate() {
+                let valid = fbank.nrows().min(num_frames);
+                input_values
+                    .slice_mut(s![i, ..valid, ..])
+                    .assign(&fbank.slice(s![..valid, ..]));
+                attention_mask.slice_mut(s![i, ..valid]).fill(1);
+            }
+
+            if do_normalize {
+                let mean = feature_extractor.mean.unwrap_or(DEFAULT_FBANK_MEAN);
+                let std = feature_extractor.std.unwrap_or(DEFAULT_FBANK_STD);
+                input_values.mapv_inplace(|x| (x - mean) / (std * 2.0));
+            }
+
+            Ok(AudioFeatures {
+                input_values: input_values.into_dyn(),
+                attention_mask,
+            })
+        }
+        None => {
+            // clips are truncated to `max_length` samples, then padded to the longest clip
+            let max_length = feature_extractor.max_length.unwrap_or(usize::MAX);
+            let num_samples = waveforms
+                .iter()
+                .map(|w| w.len().min(max_length))
+                .max()
+                .unwrap_or_default();
+
+            let mut input_values = Array2::from_elem((waveforms.len(), num_samples), padding_value);
+            let mut attention_mask = Array2::zeros((waveforms.len(), num_samples));
+
+            for (i, mut waveform) in waveforms.into_iter().enumerate() {
+                waveform.truncate(num_samples);
+                if do_normalize {
+                    normalize_waveform(&mut waveform);
+                }
+                let valid = waveform.len();
+                input_values
+                    .slice_mut(s![i, ..valid])
+                    .assign(&ndarray::ArrayView1::from(&waveform));
+                attention_mask.slice_mut(s![i, ..valid]).fill(1);
+            }
+
+            Ok(AudioFeatures {
+                input_values: input_values.into_dyn(),
+                attention_mask,
+            })
+        }
+    }
+}
+
+/// Decodes the first audio track to mono samples, averaging channels.
+/// Returns the samples and their sampling rate. Clips longer than
+/// [`MAX_AUDIO_DURATION_SECS`] are rejected.
+pub fn decode_audio(audio: &AudioInfo) -> Result<(Vec<f32>, u32), ApiError> {
+    let mss = MediaSourceStream::new(
+        Box::new(std::io::Cursor::new(audio.audio_bytes.clone())),
+        Default::default(),
+    );
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &Hint::new(),
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|_| ApiError::InputError("Unsupported audio format"))?
+        .format;
+
+    let track = format
+        .default_track()
+        .ok_or(ApiError::InputError("Audio has no tracks"))?;
+    let track_id = track.id;
+    let sampling_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or(ApiError::InputError("Audio has no sampling rate"))?;
+    let max_samples = sampling_rate as usize * MAX_AUDIO_DURATION_SECS as usize;
+
+    if track
+        .codec_params
+        .n_frames
+        .is_some_and(|n| n > max_samples as u64)
+    {
+        return Err(ApiError::InputError("Audio clip is too long"));
+    }
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|_| ApiError::InputError("Unsupported audio codec"))?;
+
+    let mut samples = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // end of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(_) => return Err(ApiError::InputError("Failed to read audio")),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // corrupt packets are skipped, as symphonia recommends
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(_) => return Err(ApiError::InputError("Failed to decode audio")),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+
+        // the header may not state the clip's length
+        if samples.len() > max_samples {
+            return Err(ApiError::InputError("Audio clip is too long"));
+        }
+    }
+
+    Ok((samples, sampling_rate))
+}
+
+/// Band-limited resampling with a Hann-windowed sinc filter. When downsampling, the
+/// filter cutoff is lowered to the target Nyquist frequency to avoid aliasing.
+pub fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = to as f64 / from as f64;
+    let cutoff = ratio.min(1.0);
+    let half_width = RESAMPLE_ZEROS / cutoff;
+    let num_out = (samples.len() as f64 * ratio).round() as usize;
+    let last = samples.len() as isize - 1;
+
+    (0..num_out)
+        .map(|i| {
+            let t = i as f64 / ratio;
+            let start = ((t - half_width).ceil() as isize).max(0);
+            let end = ((t + half_width).floor() as isize).min(last);
+
+            (start..=end)
+                .map(|j| {
+                    let x = t - j as f64;
+                    let window = 0.5 * (1.0 + (std::f64::consts::PI * x / half_width).cos());
+                    samples[j as usize] as f64 * cutoff * sinc(cutoff * x) * window
+                })
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// Zero-mean, unit-variance normalization as done by `Wav2Vec2FeatureExtractor`.
+fn normalize_waveform(samples: &mut [f32]) {
+    let n = samples.len() as f32;
+    let mean = samples.iter().sum::<f32>() / n;
+    let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / n;
+    let denom = (var + 1e-7).sqrt();
+    samples.iter_mut().for_each(|x| *x = (*x - mean) / denom);
+}
+
+/// Kaldi-compatible log-mel filter bank features of shape `[num_frames, num_mel_bins]`,
+/// matching `torchaudio.compliance.kaldi.fbank` with a Hann window and no dithering.
+pub fn log_mel_fbank(
+    samples: &[f32],
+    sampling_rate: u32,
+    num_mel_bins: usize,
+) -> Result<Array2<f32>, ApiError> {
+    let frame_length = (sampling_rate * FRAME_LENGTH_MS / 1000) as usize;
+    let frame_shift = (sampling_rate * FRAME_SHIFT_MS / 1000) as usize;
+    let fft_size = frame_length.next_power_of_two();
+
+    if samples.len() < frame_length {
+        return Err(ApiError::InputError(
+            "Audio clip is shorter than one analysis frame",
+        ));
+    }
+
+    let num_frames = 1 + (samples.len() - frame_length) / frame_shift;
+
+    let window: Vec<f32> = (0..frame_length)
+        .map(|i| {
+            0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (frame_length - 1) as f32).cos()
+        })
+        .collect();
+    let mel_banks = kaldi_mel_banks(num_mel_bins, fft_size, sampling_rate as f32);
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(fft_size);
+
+    let mut fbank = Array2::zeros((num_frames, num_mel_bins));
+    let mut frame = vec![0.0f32; frame_length];
+    let mut buffer = vec![Complex::new(0.0f32, 0.0); fft_size];
+
+    for (f, mut row) in fbank.axis_iter_mut(Axis(0)).enumerate() {
+        let start = f * frame_shift;
+        frame.copy_from_slice(&samples[start..start + frame_length]);
+
+        // remove DC offset
+        let mean = frame.iter().sum::<f32>() / frame_length as f32;
+        frame.iter_mut().for_each(|x| *x -= mean);
+
+        // pre-emphasis, with the first sample emphasized against itself
+        for i in (1..frame_length).rev() {
+            frame[i] -= PREEMPHASIS * frame[i - 1];
+        }
+        frame[0] -= PREEMPHASIS * frame[0];
+
+        buffer.iter_mut().for_each(|c| *c = Complex::new(0.0, 0.0));
+        for (c, (x, w)) in buffer.iter_mut().zip(frame.iter().zip(window.iter())) {
+            c.re = x * w;
+        }
+        fft.process(&mut buffer);
+
+        let power: Vec<f32> = buffer[..fft_size / 2]
+            .iter()
+            .map(|c| c.norm_sqr())
+            .collect();
+
+        for (energy, bank) in row.iter_mut().zip(mel_banks.axis_iter(Axis(0))) {
+            let e: f32 = bank.iter().zip(power.iter()).map(|(w, p)| w * p).sum();
+            *energy = e.max(f32::EPSILON).ln();
+        }
+    }
+
+    Ok(fbank)
+}
+
+fn mel_scale(freq: f32) -> f32 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+/// Triangular filters of shape `[num_mel_bins, fft_size / 2]`, evenly spaced on the
+/// Kaldi mel scale between 20 Hz and the Nyquist frequency.
+fn kaldi_mel_banks(num_mel_bins: usize, fft_size: usize, sampling_rate: f32) -> Array2<f32> {
+    let num_fft_bins = fft_size / 2;
+    let fft_bin_width = sampling_rate / fft_size as f32;
+    let mel_low = mel_scale(LOW_FREQ);
+    let mel_high = mel_scale(sampling_rate / 2.0);
+    let mel_delta = (mel_high - mel_low) / (num_mel_bins + 1) as f32;
+
+    let mut banks = Array2::zeros((num_mel_bins, num_fft_bins));
+
+    for (b, mut bank) in banks.axis_iter_mut(Axis(0)).enumerate() {
+        let left = mel_low + b as f32 * mel_delta;
+        let center = left + mel_delta;
+        let right = center + mel_delta;
+
+        for (i, weight) in bank.iter_mut().enumerate() {
+            let mel = mel_scale(i as f32 * fft_bin_width);
+            if mel > left && mel < right {
+                *weight = if mel <= center {
+                    (mel - left) / (center - left)
+                } else {
+                    (right - mel) / (right - center)
+                };
+            }
+        }
+    }
+
+    banks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dev_utils::wav_bytes;
+    use bytes::Bytes;
+
+    fn sine(freq: f32, sampling_rate: u32, num_samples: usize) -> Vec<f32> {
+        (0..num_samples)
+            .map(|i| {
+                0.5 * (2.0 * std::f32::consts::PI * freq * i as f32 / sampling_rate as f32).sin()
+            })
+            .collect()
+    }
+
+    fn wav2vec2_extractor() -> AudioFeatureExtractor {
+        serde_json::from_str(
+            r#"{
+                "do_normalize": true,
+                "feature_extractor_type": "Wav2Vec2FeatureExtractor",
+                "feature_size": 1,
+                "padding_value": 0.0,
+                "return_attention_mask": true,
+                "sampling_rate": 16000
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn ast_extractor() -> AudioFeatureExtractor {
+        serde_json::from_str(
+            r#"{
+                "do_normalize": true,
+                "feature_extractor_type": "ASTFeatureExtractor",
+                "feature_size": 1,
+                "max_length": 64,
+                "mean": -4.2677393,
+                "num_mel_bins": 128,
+                "padding_value": 0.0,
+                "return_attention_mask": false,
+                "sampling_rate": 16000,
+                "std": 4.5689974
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_wav_downmixes_channels() {
+        // interleaved stereo: left 0.5, right -0.5
+        let stereo: Vec<f32> = (0..200)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+        let audio = AudioInfo {
+            audio_bytes: wav_bytes(&stereo, 8000, 2),
+        };
+
+        let (samples, sampling_rate) = decode_audio(&audio).expect("Failed to decode");
+
+        assert_eq!(sampling_rate, 8000);
+        assert_eq!(samples.len(), 100);
+        assert!(samples.iter().all(|s| s.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_decode_invalid_audio() {
+        let audio = AudioInfo {
+            audio_bytes: Bytes::from_static(b"not audio"),
+        };
+
+        assert!(decode_audio(&audio).is_err());
+    }
+
+    #[test]
+    fn test_resample_preserves_signal() {
+        let input = sine(440.0, 44100, 44100);
+        let output = resample(&input, 44100, 16000);
+        let expected = sine(440.0, 16000, 16000);
+
+        assert_eq!(output.len(), 16000);
+        // skip the filter's edge effects
+        for (a, b) in output[100..15900].iter().zip(expected[100..15900].iter()) {
+            assert!((a - b).abs() < 1e-2, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn test_resample_same_rate() {
+        let input = vec![0.1, 0.2, 0.3];
+
+        assert_eq!(resample(&input, 16000, 16000), input);
+    }
+
+    #[test]
+    fn test_log_mel_fbank_shape() {
+        let samples = sine(1000.0, 16000, 16000);
+        let fbank = log_mel_fbank(&samples, 16000, 128).unwrap();
+
+        // snip_edges: 1 + (16000 - 400) / 160
+        assert_eq!(fbank.shape(), &[98, 128]);
+        assert!(fbank.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn test_log_mel_fbank_too_short() {
+        assert!(log_mel_fbank(&[0.0; 100], 16000, 128).is_err());
+    }
+
+    #[test]
+    fn test_extract_waveform_features() {
+        let audios = vec![
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 1600), 16000, 1),
+            },
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 800), 16000, 1),
+            },
+        ];
+
+        let features = extract_features(&wav2vec2_extractor(), &audios).unwrap();
+
+        assert_eq!(features.input_values.shape(), &[2, 1600]);
+        assert_eq!(features.attention_mask.row(1).sum(), 800);
+        assert_eq!(features.valid_fractions(), vec![1.0, 0.5]);
+
+        // normalized clips have zero mean and unit variance
+        let first = features.input_values.index_axis(Axis(0), 0);
+        let mean = first.mean().unwrap();
+        let var = first.mapv(|x| (x - mean).powi(2)).mean().unwrap();
+        assert!(mean.abs() < 1e-3);
+        assert!((var - 1.0).abs() < 1e-2);
+
+        // padding keeps the padding value
+        assert!(
+            features
+                .input_values
+                .slice(s![1, 800..])
+                .iter()
+                .all(|x| *x == 0.0)
+        );
+    }
+
+    #[test]
+    fn test_extract_fbank_features_pads_and_truncates() {
+        let audios = vec![
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 16000), 16000, 1),
+            },
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 3200), 16000, 1),
+            },
+        ];
+
+        let features = extract_features(&ast_extractor(), &audios).unwrap();
+
+        assert_eq!(features.input_values.shape(), &[2, 64, 128]);
+        // 98 frames truncated to 64, 18 frames padded to 64
+        assert_eq!(features.attention_mask.row(0).sum(), 64);
+        assert_eq!(features.attention_mask.row(1).sum(), 18);
+    }
+
+    #[test]
+    fn test_extract_waveform_features_truncates() {
+        let audios = vec![
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 1600), 16000, 1),
+            },
+            AudioInfo {
+                audio_bytes: wav_bytes(&sine(440.0, 16000, 800), 16000, 1),
+            },
+        ];
+        let feature_extractor = AudioFeatureExtractor {
+            max_length: Some(1000),
+            ..wav2vec2_extractor()
+        };
+
+        let features = extract_features(&feature_extractor, &audios).unwrap();
+
+        assert_eq!(features.input_values.shape(), &[2, 1000]);
+        assert_eq!(features.attention_mask.row(0).sum(), 1000);
+        assert_eq!(features.attention_mask.row(1).sum(), 800);
+    }
+
+    #[test]
+    fn test_decode_rejects_long_audio() {
+        let sampling_rate = 100;
+        let num_samples = (sampling_rate * MAX_AUDIO_DURATION_SECS + 1) as usize;
+        let audio = AudioInfo {
+            audio_bytes: wav_bytes(&vec![0.0; num_samples], sampling_rate, 1),
+        };
+
+        assert!(matches!(
+            decode_audio(&audio),
+            Err(ApiError::InputError("Audio clip is too long"))
+        ));
+    }
+}
@@ -15,14 +15,46 @@ impl Inference for AppState<model_type::Embedding> {
     fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
         let request = request.into();
 
-        let encodings = self
+        self.validate_options(&request.options)?;
+        request
+            .options
+            .validate_dimensions(
+                self.model_input_state
+                    .model_config
+                    .matryoshka_dimensions
+                    .as_deref(),
+            )
+            .map_err(ApiError::InputError)?;
+
+        let prompt = self
             .model_input_state
-            .tokenizer
-            .encode_text(request.inputs)?;
+            .model_config
+            .prompt(request.options.prompt_name.as_deref())
+            .map_err(ApiError::InputError)?;
+
+        let encodings = self.model_input_state.tokenizer.encode_text_with_prompt(
+            request.inputs.clone(),
+            prompt,
+            request.options.truncate,
+            self.model_input_state
+                .model_config
+                .truncation(request.options.truncation_mode()),
+        )?;
 
         let transform = EmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
 
-        let results = inference::embedding::embedding(self.session.lock(), &transform, encodings)?;
+        let mut results = inference::embedding::embedding(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.inputs,
+            encodings,
+            &request.options,
+        )?;
+
+        for (result, text) in results.iter_mut().zip(request.inputs.iter()) {
+            inference::embedding::convert_offsets(result, text, request.options.offset_mapping());
+        }
 
         Ok(EmbeddingResponse {
             results,
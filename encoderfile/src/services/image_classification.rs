@@ -5,10 +5,10 @@ use crate::{
     },
     error::ApiError,
     runtime::AppState,
-    transforms::{DEFAULT_LIBS, Image, ImageClassificationTransform, Preprocessor},
+    transforms::{DEFAULT_LIBS, ImageClassificationTransform},
 };
-use ndarray::{ArrayD, Axis, Ix4, Zip};
 
+use super::image_preprocessing::{DEFAULT_PREPROCESS, preprocess_images};
 use super::inference::Inference;
 use crate::inference::image_classification::image_classification;
 
@@ -26,82 +26,20 @@ impl Inference for AppState<model_type::ImageClassification> {
             return Err(ApiError::InputError("Cannot classify empty image list"));
         }
 
-        let postprocess_code = r##"
-        function Preprocess(img)
-            return img:resize(224,224):to_array(3)
-        end
-        "##
-        .to_string();
-
-        let engine =
-            ImageClassificationTransform::new(DEFAULT_LIBS.to_vec(), Some(postprocess_code))
-                .expect("Failed to create engine");
-
-        let num_channels = self.model_input_state.config.num_channels as usize;
-        let rescale_factor = self
-            .model_input_state
-            .preprocessing
-            .rescale_factor
-            .ok_or(ApiError::InternalError("missing rescale factor"))?;
-        let image_mean = self
-            .model_input_state
-            .preprocessing
-            .image_mean
-            .as_ref()
-            .ok_or(ApiError::InternalError("missing image mean"))?;
-        let image_std = self
-            .model_input_state
-            .preprocessing
-            .image_std
-            .as_ref()
-            .ok_or(ApiError::InternalError("missing image std"))?;
-
-        let images: Vec<ArrayD<f32>> = request
-            .images
-            .iter()
-            .map(|image_info| {
-                let img = image::load_from_memory(&image_info.image_bytes)
-                    .expect("Failed to load image from bytes");
-                let mut res = engine
-                    .preprocess((Image(img), self.model_input_state.clone()))
-                    .expect("Failed")
-                    .into_inner();
-                let mean_arr =
-                    ndarray::Array::from_shape_vec((num_channels, 1, 1), image_mean.to_vec())
-                        .expect("mean shape mismatch");
-                let std_arr =
-                    ndarray::Array::from_shape_vec((num_channels, 1, 1), image_std.to_vec())
-                        .expect("std shape mismatch");
-                Zip::from(&mut res)
-                    .and_broadcast(&mean_arr)
-                    .and_broadcast(&std_arr)
-                    .for_each(|x, &m, &s| *x = (*x * rescale_factor - m) / s);
-                res
-            })
-            .collect();
-
-        let images_array = ndarray::stack(
-            Axis(0),
-            &images.iter().map(|x| x.view()).collect::<Vec<_>>(),
+        let engine = ImageClassificationTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(DEFAULT_PREPROCESS.to_string()),
         )
-        .unwrap()
-        .into_dimensionality::<Ix4>()
-        .unwrap();
-
-        // TODO overlap preprocessing and inference, but for now just do it sequentially
-        // Since we are adding gpu providers now, preprocessing could run in cpu while inference
-        // is running. Using some sort of task queue will pave the way for more efficient batch
-        // processing. However, it will not be implemented right now.
+        .expect("Failed to create engine");
 
-        let label_map = self.task_state.id2label.clone().unwrap();
-        let mut entries: Vec<_> = label_map.iter().collect();
-        entries.sort_by(|x, y| x.0.cmp(y.0));
-        let classes: Vec<String> = entries
-            .into_iter()
-            .map(|(_, label)| label.clone())
-            .collect();
+        let images_array = preprocess_images(&engine, &self.model_input_state, &request.images)?;
 
-        let labels_batch = image_classification(self.session.lock(), images_array, classes)?;
+        let labels_batch = image_classification(
+            self.session.lock(),
+            &self.outputs,
+            images_array,
+            &self.task_state,
+        )?;
 
         Ok(ImageClassificationResponse {
             results: labels_batch
@@ -0,0 +1,45 @@
+use crate::{
+    common::{ImageEmbeddingRequest, ImageEmbeddingResponse, model_type},
+    error::ApiError,
+    inference,
+    runtime::AppState,
+    transforms::ImageEmbeddingTransform,
+};
+
+use super::image_preprocessing::{DEFAULT_PREPROCESS, preprocess_images};
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::ImageEmbedding> {
+    type Input = ImageEmbeddingRequest;
+    type Output = ImageEmbeddingResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+        if request.images.is_empty() {
+            return Err(ApiError::InputError("Cannot embed empty image list"));
+        }
+
+        let preprocessor = ImageEmbeddingTransform::new(
+            self.lua_libs.clone(),
+            Some(DEFAULT_PREPROCESS.to_string()),
+        )?;
+
+        let images = preprocess_images(&preprocessor, &self.model_input_state, &request.images)?;
+
+        let transform = ImageEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let results = inference::image_embedding::image_embedding(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.task_state.config,
+            images,
+        )?;
+
+        Ok(ImageEmbeddingResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
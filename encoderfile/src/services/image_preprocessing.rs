@@ -0,0 +1,165 @@
+use crate::{
+    common::ImageInfo,
+    error::ApiError,
+    runtime::ImageInputState,
+    transforms::{Image, Preprocessor, Tensor},
+};
+use ndarray::{Array3, Array4, Axis, Ix3, Zip, s};
+
+pub const DEFAULT_PREPROCESS: &str = r##"
+function Preprocess(img)
+    return img:resize(224,224):to_array(3)
+end
+"##;
+
+/// Returns the `(width, height)` of each image without decoding it.
+pub fn image_dimensions(images: &[ImageInfo]) -> Result<Vec<(u32, u32)>, ApiError> {
+    images
+        .iter()
+        .map(|image_info| {
+            image::ImageReader::new(std::io::Cursor::new(&image_info.image_bytes))
+                .with_guessed_format()
+                .map_err(|_| ApiError::InputError("Failed to load image from bytes"))?
+                .into_dimensions()
+                .map_err(|_| ApiError::InputError("Failed to load image from bytes"))
+        })
+        .collect()
+}
+
+/// Runs `Preprocess` over each image, then rescales and normalizes pixels using the
+/// model's preprocessor config. Returns a `[batch_size, num_channels, height, width]` array.
+pub fn preprocess_images<P>(
+    engine: &P,
+    input_state: &ImageInputState,
+    images: &[ImageInfo],
+) -> Result<Array4<f32>, ApiError>
+where
+    P: Preprocessor<Input = (Image, ImageInputState), Output = Tensor>,
+{
+    let images = preprocess_each(engine, input_state, images)?;
+
+    // TODO overlap preprocessing and inference, but for now just do it sequentially
+    // Since we are adding gpu providers now, preprocessing could run in cpu while inference
+    // is running. Using some sort of task queue will pave the way for more efficient batch
+    // processing. However, it will not be implemented right now.
+    ndarray::stack(
+        Axis(0),
+        &images.iter().map(|x| x.view()).collect::<Vec<_>>(),
+    )
+    .map_err(|_| ApiError::InputError("Preprocessed images must all have the same shape"))
+}
+
+/// A batch of images zero-padded at the bottom and right to the same size.
+pub struct PaddedImages {
+    /// `[batch_size, num_channels, height, width]` array.
+    pub images: Array4<f32>,
+    /// `(width, height)` of each image before padding.
+    pub sizes: Vec<(usize, usize)>,
+}
+
+/// Like [`preprocess_images`], but images of different sizes are padded to the largest height
+/// and width of the batch.
+pub fn preprocess_images_padded<P>(
+    engine: &P,
+    input_state: &ImageInputState,
+    images: &[ImageInfo],
+) -> Result<PaddedImages, ApiError>
+where
+    P: Preprocessor<Input = (Image, ImageInputState), Output = Tensor>,
+{
+    let images = preprocess_each(engine, input_state, images)?;
+    Ok(pad_images(&images))
+}
+
+fn pad_images(images: &[Array3<f32>]) -> PaddedImages {
+    let sizes: Vec<(usize, usize)> = images.iter().map(|x| (x.dim().2, x.dim().1)).collect();
+    let num_channels = images.first().map(|x| x.dim().0).unwrap_or(0);
+    let width = sizes.iter().map(|s| s.0).max().unwrap_or(0);
+    let height = sizes.iter().map(|s| s.1).max().unwrap_or(0);
+
+    let mut batch = Array4::<f32>::zeros((images.len(), num_channels, height, width));
+    for (mut padded, image) in batch.outer_iter_mut().zip(images) {
+        let (_, h, w) = image.dim();
+        padded.slice_mut(s![.., ..h, ..w]).assign(image);
+    }
+
+    PaddedImages {
+        images: batch,
+        sizes,
+    }
+}
+
+fn preprocess_each<P>(
+    engine: &P,
+    input_state: &ImageInputState,
+    images: &[ImageInfo],
+) -> Result<Vec<Array3<f32>>, ApiError>
+where
+    P: Preprocessor<Input = (Image, ImageInputState), Output = Tensor>,
+{
+    let num_channels = input_state.config.num_channels as usize;
+    let rescale_factor = input_state
+        .preprocessing
+        .rescale_factor
+        .ok_or(ApiError::InternalError("missing rescale factor"))?;
+    let image_mean = input_state
+        .preprocessing
+        .image_mean
+        .as_ref()
+        .ok_or(ApiError::InternalError("missing image mean"))?;
+    let image_std = input_state
+        .preprocessing
+        .image_std
+        .as_ref()
+        .ok_or(ApiError::InternalError("missing image std"))?;
+
+    let mean_arr = ndarray::Array::from_shape_vec((num_channels, 1, 1), image_mean.to_vec())
+        .map_err(|_| ApiError::InternalError("image mean does not match num_channels"))?;
+    let std_arr = ndarray::Array::from_shape_vec((num_channels, 1, 1), image_std.to_vec())
+        .map_err(|_| ApiError::InternalError("image std does not match num_channels"))?;
+
+    images
+        .iter()
+        .map(|image_info| {
+            let img = image::load_from_memory(&image_info.image_bytes)
+                .map_err(|_| ApiError::InputError("Failed to load image from bytes"))?;
+            let mut res = engine
+                .preprocess((Image(img), input_state.clone()))?
+                .into_inner();
+            Zip::from(&mut res)
+                .and_broadcast(&mean_arr)
+                .and_broadcast(&std_arr)
+                .for_each(|x, &m, &s| *x = (*x * rescale_factor - m) / s);
+            res.into_dimensionality::<Ix3>().map_err(|_| {
+                ApiError::InternalError(
+                    "Preprocessed images must be of shape [num_channels, height, width]",
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_images() {
+        let images = [
+            Array3::<f32>::ones((3, 2, 4)),
+            Array3::<f32>::from_elem((3, 3, 1), 2.0),
+        ];
+
+        let PaddedImages {
+            images: batch,
+            sizes,
+        } = pad_images(&images);
+
+        assert_eq!(batch.shape(), &[2, 3, 3, 4]);
+        assert_eq!(sizes, vec![(4, 2), (1, 3)]);
+        assert_eq!(batch.slice(s![0, .., ..2, ..]).sum(), 24.0);
+        assert_eq!(batch.slice(s![0, .., 2, ..]).sum(), 0.0);
+        assert_eq!(batch.slice(s![1, .., .., 0]).sum(), 18.0);
+        assert_eq!(batch.slice(s![1, .., .., 1..]).sum(), 0.0);
+    }
+}
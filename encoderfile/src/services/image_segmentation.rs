@@ -0,0 +1,59 @@
+use crate::{
+    common::{ImageSegmentationRequest, ImageSegmentationResponse, model_type},
+    error::ApiError,
+    inference,
+    runtime::AppState,
+    transforms::ImageSegmentationTransform,
+};
+
+use super::image_preprocessing::{image_dimensions, preprocess_images};
+use super::inference::Inference;
+
+const SEGMENTATION_PREPROCESS: &str = r##"
+function Preprocess(img)
+    local height = input_config.size_height or input_config.size_shortest_edge or input_config.image_size or 512
+    local width = input_config.size_width or input_config.size_shortest_edge or input_config.image_size or 512
+    return img:resize(height, width):to_array(input_config.num_channels)
+end
+"##;
+
+impl Inference for AppState<model_type::ImageSegmentation> {
+    type Input = ImageSegmentationRequest;
+    type Output = ImageSegmentationResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+        if request.images.is_empty() {
+            return Err(ApiError::InputError("Cannot segment empty image list"));
+        }
+
+        let image_sizes = image_dimensions(&request.images)?;
+
+        let preprocessor = ImageSegmentationTransform::new(
+            self.lua_libs.clone(),
+            Some(SEGMENTATION_PREPROCESS.to_string()),
+        )?;
+
+        let images = preprocess_images(&preprocessor, &self.model_input_state, &request.images)?;
+
+        let labels = self.task_state.labels_by_id();
+
+        let transform =
+            ImageSegmentationTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let results = inference::image_segmentation::image_segmentation(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            images,
+            &image_sizes,
+            &labels,
+        )?;
+
+        Ok(ImageSegmentationResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    common::{
+        InferenceOptions, LateInteractionEmbedding, LateInteractionRequest,
+        LateInteractionResponse, LateInteractionScoreRequest, LateInteractionScoreResponse,
+        model_type,
+    },
+    error::ApiError,
+    inference,
+    runtime::{AppState, EncoderfileState},
+    transforms::LateInteractionTransform,
+};
+
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::LateInteraction> {
+    type Input = LateInteractionRequest;
+    type Output = LateInteractionResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+
+        self.validate_options(&request.options)?;
+
+        let results = self.encode(request.inputs, request.is_query, &request.options)?;
+
+        Ok(LateInteractionResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
+
+impl EncoderfileState<model_type::LateInteraction> {
+    pub fn score(
+        &self,
+        request: impl Into<LateInteractionScoreRequest>,
+    ) -> Result<LateInteractionScoreResponse, ApiError> {
+        let request = request.into();
+
+        if request.documents.is_empty() {
+            return Err(ApiError::InputError("At least one document is required"));
+        }
+
+        self.validate_options(&request.options)?;
+
+        let query = self
+            .encode(vec![request.query], true, &request.options)?
+            .pop()
+            .expect("Query encoding is missing");
+        let documents = self.encode(request.documents, false, &request.options)?;
+
+        Ok(LateInteractionScoreResponse {
+            scores: inference::late_interaction::score(&query, &documents),
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    fn encode(
+        &self,
+        inputs: Vec<String>,
+        is_query: bool,
+        options: &InferenceOptions,
+    ) -> Result<Vec<LateInteractionEmbedding>, ApiError> {
+        let config = &self.task_state.config;
+
+        let prefix = match is_query {
+            true => config.query_prefix.as_deref(),
+            false => config.document_prefix.as_deref(),
+        };
+
+        let inputs = match prefix {
+            Some(prefix) => inputs.into_iter().map(|i| format!("{prefix}{i}")).collect(),
+            None => inputs,
+        };
+
+        let tokenizer = &self.model_input_state.tokenizer;
+        let truncation = self
+            .model_input_state
+            .model_config
+            .truncation(options.truncation_mode());
+
+        // fixed-length queries are always padded to `query_length`, and truncated to it
+        // unless the request asks for a shorter length or rejects over-length inputs
+        let (encodings, keep_padding) = match (is_query, config.query_length) {
+            (true, Some(length)) => (
+                tokenizer.encode_text_fixed_length(
+                    inputs,
+                    length,
+                    &config.mask_token,
+                    options.truncate,
+                    truncation,
+                )?,
+                true,
+            ),
+            _ => (
+                tokenizer.encode_text_with_truncation(inputs, options.truncate, truncation)?,
+                false,
+            ),
+        };
+
+        let transform = LateInteractionTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        inference::late_interaction::late_interaction(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.inputs,
+            self.task_state.projection.as_ref(),
+            keep_padding,
+            !is_query && config.mask_punctuation,
+            encodings,
+        )
+    }
+}
@@ -1,10 +1,23 @@
+mod audio_classification;
+mod audio_embedding;
+mod audio_preprocessing;
 mod embedding;
 mod image_classification;
+mod image_embedding;
+mod image_preprocessing;
+mod image_segmentation;
 mod inference;
+mod late_interaction;
 mod model_metadata;
+mod multimodal_embedding;
+mod object_detection;
 mod sentence_embedding;
 mod sequence_classification;
+mod sparse_embedding;
 mod token_classification;
+mod tokenize;
 
+pub use audio_preprocessing::AudioFeatures;
 pub use inference::Inference;
 pub use model_metadata::Metadata;
+pub use tokenize::TextInput;
@@ -2,10 +2,15 @@ use std::collections::HashMap;
 
 use crate::{
     common::{
-        GetModelMetadataResponse,
+        Calibration, GetModelMetadataResponse, InferenceOptions, TruncationMode,
         model_type::{ModelType, ModelTypeSpec},
     },
-    runtime::{AppState, ClassifierState, FeatureExtractorState, InputType, TaskType},
+    runtime::{
+        AppState, AudioInputState, ClassifierState, FeatureExtractorState, ImageEmbeddingState,
+        ImageInputState, InputType, LateInteractionState, MultimodalEmbeddingState,
+        MultimodalInputState, ObjectDetectionState, SentenceEmbeddingState, TaskType,
+        TextInputState,
+    },
 };
 
 pub trait Metadata {
@@ -14,6 +19,13 @@ pub trait Metadata {
             model_id: self.model_id(),
             model_type: self.model_type(),
             id2label: self.id2label(),
+            default_options: self.default_options(),
+            dimensions: self.dimensions(),
+            prompts: self.prompts(),
+            default_prompt_name: self.default_prompt_name(),
+            calibration: self.calibration(),
+            label_descriptions: self.label_descriptions(),
+            label_thresholds: self.label_thresholds(),
         }
     }
 
@@ -22,10 +34,137 @@ pub trait Metadata {
     fn model_type(&self) -> ModelType;
 
     fn id2label(&self) -> Option<HashMap<u32, String>>;
+
+    fn default_options(&self) -> Option<InferenceOptions>;
+
+    fn dimensions(&self) -> Option<Vec<usize>>;
+
+    fn prompts(&self) -> Option<HashMap<String, String>>;
+
+    fn default_prompt_name(&self) -> Option<String>;
+
+    fn calibration(&self) -> Option<Calibration>;
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>>;
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>>;
+}
+
+trait InputStateMetadata {
+    /// The tokenizer's truncation length, for text inputs.
+    fn max_length(&self) -> Option<usize>;
+
+    /// The Matryoshka dimensions allowed at build time.
+    fn dimensions(&self) -> Option<Vec<usize>>;
+
+    /// The named prompts set at build time.
+    fn prompts(&self) -> Option<HashMap<String, String>>;
+
+    fn default_prompt_name(&self) -> Option<String>;
+
+    /// The truncation mode set at build time, for text inputs.
+    fn truncation(&self) -> Option<TruncationMode>;
+}
+
+impl InputStateMetadata for TextInputState {
+    fn max_length(&self) -> Option<usize> {
+        Some(self.tokenizer.max_length())
+    }
+
+    fn dimensions(&self) -> Option<Vec<usize>> {
+        self.model_config.matryoshka_dimensions.clone()
+    }
+
+    fn prompts(&self) -> Option<HashMap<String, String>> {
+        self.model_config.prompts.clone()
+    }
+
+    fn default_prompt_name(&self) -> Option<String> {
+        self.model_config.default_prompt_name.clone()
+    }
+
+    fn truncation(&self) -> Option<TruncationMode> {
+        Some(self.model_config.truncation(None))
+    }
+}
+
+impl InputStateMetadata for ImageInputState {
+    fn max_length(&self) -> Option<usize> {
+        None
+    }
+
+    fn dimensions(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    fn prompts(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn default_prompt_name(&self) -> Option<String> {
+        None
+    }
+
+    fn truncation(&self) -> Option<TruncationMode> {
+        None
+    }
+}
+
+impl InputStateMetadata for AudioInputState {
+    fn max_length(&self) -> Option<usize> {
+        None
+    }
+
+    fn dimensions(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    fn prompts(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn default_prompt_name(&self) -> Option<String> {
+        None
+    }
+
+    fn truncation(&self) -> Option<TruncationMode> {
+        None
+    }
+}
+
+impl InputStateMetadata for MultimodalInputState {
+    fn max_length(&self) -> Option<usize> {
+        Some(self.text.tokenizer.max_length())
+    }
+
+    fn dimensions(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    fn prompts(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn default_prompt_name(&self) -> Option<String> {
+        None
+    }
+
+    fn truncation(&self) -> Option<TruncationMode> {
+        None
+    }
 }
 
 trait TaskStateMetadata {
     fn id2label(&self) -> Option<HashMap<u32, String>>;
+
+    /// The score calibration set at build time, for classifiers.
+    fn calibration(&self) -> Option<Calibration>;
+
+    /// The label descriptions set at build time, for classifiers.
+    fn label_descriptions(&self) -> Option<HashMap<String, String>>;
+
+    /// The label thresholds set at build time, for multi-label classifiers.
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>>;
 }
 
 impl TaskStateMetadata for ClassifierState {
@@ -33,16 +172,141 @@ impl TaskStateMetadata for ClassifierState {
         println!("ClassifierState: {:?}", self);
         self.id2label.clone()
     }
+
+    fn calibration(&self) -> Option<Calibration> {
+        self.calibration.clone()
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        self.label_descriptions.clone()
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        let thresholds = self.label_thresholds.as_ref()?;
+
+        Some(
+            thresholds
+                .iter()
+                .enumerate()
+                .filter_map(|(id, threshold)| {
+                    Some((self.id2label(id as u32)?.to_string(), (*threshold)?))
+                })
+                .collect(),
+        )
+    }
 }
 
 impl TaskStateMetadata for FeatureExtractorState {
     fn id2label(&self) -> Option<HashMap<u32, String>> {
         None
     }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
+}
+
+impl TaskStateMetadata for SentenceEmbeddingState {
+    fn id2label(&self) -> Option<HashMap<u32, String>> {
+        None
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
+}
+
+impl TaskStateMetadata for LateInteractionState {
+    fn id2label(&self) -> Option<HashMap<u32, String>> {
+        None
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
+}
+
+impl TaskStateMetadata for ImageEmbeddingState {
+    fn id2label(&self) -> Option<HashMap<u32, String>> {
+        None
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
+}
+
+impl TaskStateMetadata for MultimodalEmbeddingState {
+    fn id2label(&self) -> Option<HashMap<u32, String>> {
+        None
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
+}
+
+impl TaskStateMetadata for ObjectDetectionState {
+    fn id2label(&self) -> Option<HashMap<u32, String>> {
+        self.labels.id2label.clone()
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        None
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        None
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        None
+    }
 }
 
 impl<T: ModelTypeSpec + InputType + TaskType> Metadata for AppState<T>
 where
+    <T as InputType>::State: InputStateMetadata,
     <T as TaskType>::State: TaskStateMetadata,
 {
     fn model_id(&self) -> String {
@@ -56,4 +320,39 @@ where
     fn id2label(&self) -> Option<HashMap<u32, String>> {
         self.task_state.id2label()
     }
+
+    fn default_options(&self) -> Option<InferenceOptions> {
+        let mut options =
+            InferenceOptions::defaults(&T::enum_val(), self.model_input_state.max_length()?)?;
+
+        if options.truncation.is_some() {
+            options.truncation = self.model_input_state.truncation();
+        }
+
+        Some(options)
+    }
+
+    fn dimensions(&self) -> Option<Vec<usize>> {
+        self.model_input_state.dimensions()
+    }
+
+    fn prompts(&self) -> Option<HashMap<String, String>> {
+        self.model_input_state.prompts()
+    }
+
+    fn default_prompt_name(&self) -> Option<String> {
+        self.model_input_state.default_prompt_name()
+    }
+
+    fn calibration(&self) -> Option<Calibration> {
+        self.task_state.calibration()
+    }
+
+    fn label_descriptions(&self) -> Option<HashMap<String, String>> {
+        self.task_state.label_descriptions()
+    }
+
+    fn label_thresholds(&self) -> Option<HashMap<String, f32>> {
+        self.task_state.label_thresholds()
+    }
 }
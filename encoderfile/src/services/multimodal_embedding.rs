@@ -0,0 +1,128 @@
+use ndarray::Array2;
+
+use crate::{
+    common::{
+        ImageInfo, MultimodalEmbeddingResponse, MultimodalImageEmbeddingRequest,
+        MultimodalSimilarityRequest, MultimodalSimilarityResponse, MultimodalTextEmbeddingRequest,
+        model_type,
+    },
+    error::ApiError,
+    inference,
+    runtime::{AppState, EncoderfileState},
+    transforms::MultimodalEmbeddingTransform,
+};
+
+use super::image_preprocessing::{DEFAULT_PREPROCESS, preprocess_images};
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::MultimodalEmbedding> {
+    type Input = MultimodalSimilarityRequest;
+    type Output = MultimodalSimilarityResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        self.similarity(request)
+    }
+}
+
+impl EncoderfileState<model_type::MultimodalEmbedding> {
+    /// Scores each image against the request labels (zero-shot image classification).
+    pub fn similarity(
+        &self,
+        request: impl Into<MultimodalSimilarityRequest>,
+    ) -> Result<MultimodalSimilarityResponse, ApiError> {
+        let request = request.into();
+
+        if request.labels.is_empty() {
+            return Err(ApiError::InputError("At least one label is required"));
+        }
+
+        let image_embeds = self.embed_images(&request.images)?;
+        let text_embeds = self.embed_texts(request.labels.clone())?;
+
+        let results = inference::multimodal_embedding::similarity(
+            &image_embeds,
+            &text_embeds,
+            &request.labels,
+            self.task_state.config.logit_scale,
+        )?;
+
+        Ok(MultimodalSimilarityResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    pub fn embed_text(
+        &self,
+        request: impl Into<MultimodalTextEmbeddingRequest>,
+    ) -> Result<MultimodalEmbeddingResponse, ApiError> {
+        let request = request.into();
+
+        let embeddings = self.embed_texts(request.inputs)?;
+
+        Ok(MultimodalEmbeddingResponse {
+            results: inference::multimodal_embedding::postprocess(embeddings),
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    pub fn embed_image(
+        &self,
+        request: impl Into<MultimodalImageEmbeddingRequest>,
+    ) -> Result<MultimodalEmbeddingResponse, ApiError> {
+        let request = request.into();
+
+        let embeddings = self.embed_images(&request.images)?;
+
+        Ok(MultimodalEmbeddingResponse {
+            results: inference::multimodal_embedding::postprocess(embeddings),
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    fn embed_texts(&self, inputs: Vec<String>) -> Result<Array2<f32>, ApiError> {
+        let encodings = self.model_input_state.text.tokenizer.encode_text(inputs)?;
+
+        let transform =
+            MultimodalEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        inference::multimodal_embedding::text_embeddings(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.inputs,
+            encodings,
+        )
+    }
+
+    fn embed_images(&self, images: &[ImageInfo]) -> Result<Array2<f32>, ApiError> {
+        if images.is_empty() {
+            return Err(ApiError::InputError("Cannot embed empty image list"));
+        }
+
+        let vision_session = self
+            .vision_session
+            .as_ref()
+            .ok_or(ApiError::InternalError("Model has no vision tower"))?;
+
+        let preprocessor = MultimodalEmbeddingTransform::new(
+            self.lua_libs.clone(),
+            Some(DEFAULT_PREPROCESS.to_string()),
+        )?;
+
+        let images = preprocess_images(&preprocessor, &self.model_input_state.image, images)?;
+
+        let transform =
+            MultimodalEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        inference::multimodal_embedding::image_embeddings(
+            vision_session.lock(),
+            &transform,
+            &self.outputs,
+            images,
+        )
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    common::{ObjectDetectionRequest, ObjectDetectionResponse, model_type},
+    error::ApiError,
+    inference::{self, object_detection::ImageGeometry},
+    runtime::AppState,
+    transforms::ObjectDetectionTransform,
+};
+
+use super::image_preprocessing::{PaddedImages, image_dimensions, preprocess_images_padded};
+use super::inference::Inference;
+
+// Fixed `height`/`width` sizes stretch the image. `shortest_edge` sizes (DETR) keep the
+// aspect ratio, with the longest edge capped at `longest_edge`; the batch is then padded.
+const DETECTION_PREPROCESS: &str = r##"
+function Preprocess(img)
+    local height = input_config.size_height
+    local width = input_config.size_width
+    local shortest_edge = input_config.size_shortest_edge
+
+    if not (height and width) and shortest_edge then
+        local h, w = img:height(), img:width()
+        local size = shortest_edge
+        local longest_edge = input_config.size_longest_edge
+        if longest_edge and math.max(h, w) / math.min(h, w) * size > longest_edge then
+            size = math.floor(longest_edge * math.min(h, w) / math.max(h, w) + 0.5)
+        end
+        if w < h then
+            height, width = math.floor(size * h / w), size
+        else
+            height, width = size, math.floor(size * w / h)
+        end
+    end
+
+    height = height or input_config.image_size or 800
+    width = width or input_config.image_size or 800
+    return img:resize(height, width):to_array(input_config.num_channels)
+end
+"##;
+
+impl Inference for AppState<model_type::ObjectDetection> {
+    type Input = ObjectDetectionRequest;
+    type Output = ObjectDetectionResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+        if request.images.is_empty() {
+            return Err(ApiError::InputError(
+                "Cannot detect objects in empty image list",
+            ));
+        }
+
+        let image_sizes = image_dimensions(&request.images)?;
+
+        let preprocessor = ObjectDetectionTransform::new(
+            self.lua_libs.clone(),
+            Some(DETECTION_PREPROCESS.to_string()),
+        )?;
+
+        let PaddedImages { images, sizes } =
+            preprocess_images_padded(&preprocessor, &self.model_input_state, &request.images)?;
+        let padded = (images.dim().3, images.dim().2);
+        let geometry: Vec<ImageGeometry> = image_sizes
+            .into_iter()
+            .zip(sizes)
+            .map(|(original, resized)| ImageGeometry {
+                original,
+                resized,
+                padded,
+            })
+            .collect();
+
+        let labels = self.task_state.labels.labels_by_id();
+
+        let transform = ObjectDetectionTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let results = inference::object_detection::object_detection(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.task_state.config,
+            images,
+            &geometry,
+            &labels,
+        )?;
+
+        Ok(ObjectDetectionResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{ImageConfig, ImageInputState, ImagePreprocessing, ImageSize};
+    use crate::transforms::{DEFAULT_LIBS, Image, Preprocessor};
+
+    fn preprocessed_shape(width: u32, height: u32, size: ImageSize) -> Vec<usize> {
+        let preprocessor = ObjectDetectionTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(DETECTION_PREPROCESS.to_string()),
+        )
+        .expect("Failed to create preprocessor");
+
+        let input_state = ImageInputState {
+            config: ImageConfig {
+                num_channels: 3,
+                image_size: None,
+            },
+            preprocessing: ImagePreprocessing {
+                rescale_factor: None,
+                image_mean: None,
+                image_std: None,
+                do_normalize: None,
+                do_rescale: None,
+                do_resize: None,
+                image_processor_type: None,
+                size: Some(size),
+            },
+        };
+
+        let image = Image(image::DynamicImage::new_rgb8(width, height));
+
+        preprocessor
+            .preprocess((image, input_state))
+            .expect("Failed to preprocess image")
+            .into_inner()
+            .shape()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_preprocess_keeps_aspect_ratio() {
+        let size = |longest_edge| ImageSize {
+            height: None,
+            width: None,
+            shortest_edge: Some(800),
+            longest_edge,
+        };
+
+        assert_eq!(preprocessed_shape(640, 480, size(None)), vec![3, 800, 1066]);
+        assert_eq!(preprocessed_shape(480, 640, size(None)), vec![3, 1066, 800]);
+        // the longest edge is capped
+        assert_eq!(
+            preprocessed_shape(640, 480, size(Some(1000))),
+            vec![3, 750, 1000]
+        );
+    }
+
+    #[test]
+    fn test_preprocess_fixed_size() {
+        let size = ImageSize {
+            height: Some(640),
+            width: Some(640),
+            shortest_edge: None,
+            longest_edge: None,
+        };
+
+        assert_eq!(preprocessed_shape(640, 480, size), vec![3, 640, 640]);
+    }
+}
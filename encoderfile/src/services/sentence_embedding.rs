@@ -1,8 +1,15 @@
+use ndarray::Array2;
+use tokenizers::Encoding;
+
 use crate::{
-    common::{SentenceEmbeddingRequest, SentenceEmbeddingResponse, model_type},
+    common::{
+        DEFAULT_DEDUPE_THRESHOLD, DEFAULT_SEARCH_TOP_K, DedupeRequest, DedupeResponse,
+        InferenceOptions, SearchRequest, SearchResponse, SearchResult, SentenceEmbeddingRequest,
+        SentenceEmbeddingResponse, SimilarityRequest, SimilarityResponse, model_type,
+    },
     error::ApiError,
     inference,
-    runtime::AppState,
+    runtime::{AppState, EncoderfileState},
     transforms::SentenceEmbeddingTransform,
 };
 
@@ -15,10 +22,7 @@ impl Inference for AppState<model_type::SentenceEmbedding> {
     fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
         let request = request.into();
 
-        let encodings = self
-            .model_input_state
-            .tokenizer
-            .encode_text(request.inputs)?;
+        let encodings = self.encode(request.inputs, &request.options)?;
 
         let transform =
             SentenceEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
@@ -26,7 +30,15 @@ impl Inference for AppState<model_type::SentenceEmbedding> {
         let results = inference::sentence_embedding::sentence_embedding(
             self.session.lock(),
             &transform,
+            &self.outputs,
+            &self.inputs,
             encodings,
+            &request.options,
+            self.model_input_state.model_config.pooling.as_ref(),
+            self.model_input_state
+                .model_config
+                .embedding_quantization
+                .as_ref(),
         )?;
 
         Ok(SentenceEmbeddingResponse {
@@ -36,3 +48,167 @@ impl Inference for AppState<model_type::SentenceEmbedding> {
         })
     }
 }
+
+impl EncoderfileState<model_type::SentenceEmbedding> {
+    /// Cosine similarity matrix of sources against targets, or of sources against themselves.
+    pub fn similarity(
+        &self,
+        request: impl Into<SimilarityRequest>,
+    ) -> Result<SimilarityResponse, ApiError> {
+        let request = request.into();
+
+        let sources = self.embed(request.sources, &request.options)?;
+        let similarities = match request.targets {
+            Some(targets) => {
+                let targets = self.embed(targets, &request.options)?;
+                inference::sentence_embedding::similarity(sources, targets)?
+            }
+            None => inference::sentence_embedding::similarity(sources.clone(), sources)?,
+        };
+
+        Ok(SimilarityResponse {
+            similarities: similarities.outer_iter().map(|row| row.to_vec()).collect(),
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    /// Clusters near-duplicate inputs.
+    pub fn dedupe(&self, request: impl Into<DedupeRequest>) -> Result<DedupeResponse, ApiError> {
+        let request = request.into();
+
+        let threshold = request.threshold.unwrap_or(DEFAULT_DEDUPE_THRESHOLD);
+        if !(-1.0..=1.0).contains(&threshold) {
+            return Err(ApiError::InputError(
+                "threshold must be between -1.0 and 1.0",
+            ));
+        }
+
+        let embeddings = self.embed(request.inputs, &request.options)?;
+        let similarities =
+            inference::sentence_embedding::similarity(embeddings.clone(), embeddings)?;
+
+        let assignments = inference::sentence_embedding::dedupe(&similarities, threshold);
+
+        let n_clusters = assignments.iter().max().map_or(0, |max| max + 1);
+        let mut clusters = vec![Vec::new(); n_clusters];
+        for (i, cluster) in assignments.iter().enumerate() {
+            clusters[*cluster].push(i);
+        }
+
+        Ok(DedupeResponse {
+            assignments,
+            clusters,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    /// Whether the model was built with a corpus to search.
+    pub fn has_corpus(&self) -> bool {
+        self.task_state.corpus.is_some()
+    }
+
+    /// Top-`top_k` documents of the build-time corpus most similar to the query.
+    pub fn search(&self, request: impl Into<SearchRequest>) -> Result<SearchResponse, ApiError> {
+        let request = request.into();
+
+        let corpus = self
+            .task_state
+            .corpus
+            .as_ref()
+            .ok_or(ApiError::InputError("Model was built without a corpus"))?;
+
+        let top_k = request.top_k.unwrap_or(DEFAULT_SEARCH_TOP_K);
+        if top_k == 0 {
+            return Err(ApiError::InputError("top_k must be greater than 0"));
+        }
+
+        // the corpus is embedded at full size and normalized at build time
+        if request.options.dimensions.is_some() || request.options.normalize.is_some() {
+            return Err(ApiError::InputError(
+                "dimensions and normalize cannot be set when searching the corpus",
+            ));
+        }
+
+        let query = self.embed(vec![request.query], &request.options)?;
+
+        let results = inference::corpus::search(corpus, query.row(0), top_k)?
+            .into_iter()
+            .map(|(i, score)| {
+                let document = &corpus.documents[i];
+                SearchResult {
+                    id: document.id.clone(),
+                    text: document.text.clone(),
+                    score,
+                    metadata: document.metadata.clone(),
+                }
+            })
+            .collect();
+
+        Ok(SearchResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    /// Float embeddings, for comparing inputs on the server.
+    fn embed(
+        &self,
+        inputs: Vec<String>,
+        options: &InferenceOptions,
+    ) -> Result<Array2<f32>, ApiError> {
+        if options.encoding_format.is_some() || options.base64.is_some() {
+            return Err(ApiError::InputError(
+                "encoding_format and base64 only apply to returned embeddings",
+            ));
+        }
+
+        let encodings = self.encode(inputs, options)?;
+
+        let transform =
+            SentenceEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        inference::sentence_embedding::embeddings(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.inputs,
+            encodings,
+            options,
+            self.model_input_state.model_config.pooling.as_ref(),
+        )
+    }
+
+    fn encode(
+        &self,
+        inputs: Vec<String>,
+        options: &InferenceOptions,
+    ) -> Result<Vec<Encoding>, ApiError> {
+        self.validate_options(options)?;
+        options
+            .validate_dimensions(
+                self.model_input_state
+                    .model_config
+                    .matryoshka_dimensions
+                    .as_deref(),
+            )
+            .map_err(ApiError::InputError)?;
+
+        let prompt = self
+            .model_input_state
+            .model_config
+            .prompt(options.prompt_name.as_deref())
+            .map_err(ApiError::InputError)?;
+
+        self.model_input_state.tokenizer.encode_text_with_prompt(
+            inputs,
+            prompt,
+            options.truncate,
+            self.model_input_state
+                .model_config
+                .truncation(options.truncation_mode()),
+        )
+    }
+}
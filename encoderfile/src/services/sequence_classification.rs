@@ -1,5 +1,8 @@
 use crate::{
-    common::{SequenceClassificationRequest, SequenceClassificationResponse, model_type},
+    common::{
+        DEFAULT_MULTI_LABEL_THRESHOLD, SequenceClassificationRequest,
+        SequenceClassificationResponse, model_type,
+    },
     error::ApiError,
     inference,
     runtime::AppState,
@@ -15,24 +18,44 @@ impl Inference for AppState<model_type::SequenceClassification> {
     fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
         let request = request.into();
 
+        self.validate_options(&request.options)?;
+
+        let threshold = self.task_state.threshold(request.threshold)?;
+
         let encodings = self
             .model_input_state
             .tokenizer
-            .encode_text(request.inputs)?;
+            .encode_text_with_truncation(
+                request.inputs,
+                request.options.truncate,
+                self.model_input_state
+                    .model_config
+                    .truncation(request.options.truncation_mode()),
+            )?;
 
         let transform =
             SequenceClassificationTransform::new(self.lua_libs.clone(), self.transform_str())?;
 
-        let results = inference::sequence_classification::sequence_classification(
+        let mut results = inference::sequence_classification::sequence_classification(
             self.session.lock(),
             &transform,
+            &self.outputs,
+            &self.inputs,
             &self.task_state,
+            threshold.unwrap_or(DEFAULT_MULTI_LABEL_THRESHOLD),
             encodings,
         )?;
 
+        inference::sequence_classification::apply_options(
+            &mut results,
+            &self.task_state,
+            &request.options,
+        );
+
         Ok(SequenceClassificationResponse {
             results,
             model_id: self.config.name.clone(),
+            threshold,
             metadata: request.metadata,
         })
     }
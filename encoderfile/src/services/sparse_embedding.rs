@@ -0,0 +1,71 @@
+use crate::{
+    common::{SparseEmbeddingRequest, SparseEmbeddingResponse, model_type},
+    error::ApiError,
+    inference,
+    runtime::AppState,
+    transforms::SparseEmbeddingTransform,
+};
+
+use super::inference::Inference;
+
+impl Inference for AppState<model_type::SparseEmbedding> {
+    type Input = SparseEmbeddingRequest;
+    type Output = SparseEmbeddingResponse;
+
+    fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        let request = request.into();
+
+        self.validate_options(&request.options)?;
+
+        let prompt = self
+            .model_input_state
+            .model_config
+            .prompt(request.options.prompt_name.as_deref())
+            .map_err(ApiError::InputError)?;
+
+        let encodings = self.model_input_state.tokenizer.encode_text_with_prompt(
+            request.inputs,
+            prompt,
+            request.options.truncate,
+            self.model_input_state
+                .model_config
+                .truncation(request.options.truncation_mode()),
+        )?;
+
+        let transform = SparseEmbeddingTransform::new(self.lua_libs.clone(), self.transform_str())?;
+
+        let mut results = inference::sparse_embedding::sparse_embedding(
+            self.session.lock(),
+            &transform,
+            &self.outputs,
+            &self.inputs,
+            request.top_k,
+            request.threshold.unwrap_or(0.0),
+            encodings,
+        )?;
+
+        if request.return_tokens {
+            let tokenizer = &self.model_input_state.tokenizer;
+
+            for result in results.iter_mut() {
+                let tokens = result
+                    .indices
+                    .iter()
+                    .map(|idx| {
+                        tokenizer
+                            .id_to_token(*idx)
+                            .ok_or(ApiError::InternalError("Sparse index out of vocabulary"))
+                    })
+                    .collect::<Result<Vec<String>, ApiError>>()?;
+
+                result.tokens = Some(tokens);
+            }
+        }
+
+        Ok(SparseEmbeddingResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+}
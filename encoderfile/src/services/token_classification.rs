@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
 use crate::{
-    common::{TokenClassificationRequest, TokenClassificationResponse, model_type},
+    common::{
+        AggregationStrategy, DEFAULT_IGNORE_LABELS, InferenceOptions, RedactRequest,
+        RedactResponse, TokenClassificationRequest, TokenClassificationResponse, model_type,
+    },
     error::ApiError,
     inference,
-    runtime::AppState,
+    runtime::{AppState, EncoderfileState},
     transforms::TokenClassificationTransform,
 };
 
@@ -13,25 +18,205 @@ impl Inference for AppState<model_type::TokenClassification> {
     type Output = TokenClassificationResponse;
 
     fn inference(&self, request: impl Into<Self::Input>) -> Result<Self::Output, ApiError> {
+        self.classify(request.into())
+    }
+}
+
+impl EncoderfileState<model_type::TokenClassification> {
+    /// Replaces the entities detected in each input with a label placeholder, a mask or a
+    /// pseudonym.
+    pub fn redact(&self, request: impl Into<RedactRequest>) -> Result<RedactResponse, ApiError> {
         let request = request.into();
 
+        let mode = request.mode.unwrap_or_default();
+        let mask_char = match request.mask_char.as_deref() {
+            None => '*',
+            Some(mask_char) => {
+                let mut chars = mask_char.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(ApiError::InputError("mask_char must be a single character"));
+                    }
+                }
+            }
+        };
+        let threshold = request.threshold.unwrap_or(0.0);
+        let offset_mapping = request.options.offset_mapping();
+
+        // `none` returns no entities to redact
+        let strategy = request
+            .aggregation_strategy
+            .or(self.model_input_state.model_config.aggregation_strategy)
+            .filter(|strategy| *strategy != AggregationStrategy::None)
+            .unwrap_or(AggregationStrategy::Simple);
+
+        let response = self.classify(TokenClassificationRequest {
+            inputs: request.inputs.clone(),
+            words: None,
+            aggregation_strategy: Some(strategy),
+            ignore_labels: request.ignore_labels,
+            // entities are replaced by their byte offsets, and converted afterwards
+            options: InferenceOptions {
+                offset_mapping: None,
+                ..request.options
+            },
+            metadata: None,
+        })?;
+
+        let mut pseudonyms = HashMap::new();
+
+        let results = response
+            .results
+            .into_iter()
+            .zip(request.inputs.iter())
+            .map(|(result, text)| {
+                let entities = result
+                    .entities
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|entity| entity.score >= threshold)
+                    .filter(|entity| {
+                        request
+                            .labels
+                            .as_ref()
+                            .is_none_or(|labels| labels.contains(&entity.entity_group))
+                    })
+                    .collect();
+
+                let mut redaction = inference::token_classification::redact(
+                    text,
+                    entities,
+                    mode,
+                    mask_char,
+                    &mut pseudonyms,
+                );
+
+                let converter = offset_mapping.converter(text);
+                for span in redaction.spans.iter_mut() {
+                    span.start = converter.convert(span.start);
+                    span.end = converter.convert(span.end);
+                }
+
+                redaction
+            })
+            .collect();
+
+        Ok(RedactResponse {
+            results,
+            model_id: self.config.name.clone(),
+            metadata: request.metadata,
+        })
+    }
+
+    fn classify(
+        &self,
+        request: TokenClassificationRequest,
+    ) -> Result<TokenClassificationResponse, ApiError> {
+        self.validate_options(&request.options)?;
+
         let session = self.session.lock();
 
-        let encodings = self
-            .model_input_state
-            .tokenizer
-            .encode_text(request.inputs)?;
+        let model_config = &self.model_input_state.model_config;
+
+        let strategy = request
+            .aggregation_strategy
+            .or(model_config.aggregation_strategy)
+            .unwrap_or_default();
+
+        let ignore_labels = request
+            .ignore_labels
+            .or_else(|| model_config.ignore_labels.clone())
+            .unwrap_or_else(|| {
+                DEFAULT_IGNORE_LABELS
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect()
+            });
+
+        let tokenizer = &self.model_input_state.tokenizer;
+        let truncation = model_config.truncation(request.options.truncation_mode());
+
+        // pre-tokenized words are classified as if joined with single spaces
+        let (texts, encodings) = match &request.words {
+            Some(_) if !request.inputs.is_empty() => {
+                return Err(ApiError::InputError("Set either inputs or words, not both"));
+            }
+            Some(words) => (
+                words.iter().map(|w| w.join(" ")).collect(),
+                tokenizer.encode_words(words.clone(), request.options.truncate, truncation)?,
+            ),
+            None => (
+                request.inputs.clone(),
+                tokenizer.encode_text_with_truncation(
+                    request.inputs,
+                    request.options.truncate,
+                    truncation,
+                )?,
+            ),
+        };
 
         let transform =
             TokenClassificationTransform::new(self.lua_libs.clone(), self.transform_str())?;
 
-        let results = inference::token_classification::token_classification(
+        let mut results = inference::token_classification::token_classification(
             session,
             &transform,
+            &self.outputs,
+            &self.inputs,
             &self.task_state,
-            encodings,
+            encodings.clone(),
+            &request.options,
         )?;
 
+        if strategy != AggregationStrategy::None {
+            for ((result, encoding), text) in
+                results.iter_mut().zip(encodings.iter()).zip(texts.iter())
+            {
+                let entities = inference::token_classification::aggregate_entities(
+                    result,
+                    encoding,
+                    text,
+                    &self.task_state,
+                    strategy,
+                    &ignore_labels,
+                );
+                result.entities = Some(entities);
+            }
+        }
+
+        if let Some(words) = &request.words {
+            for ((result, encoding), words) in
+                results.iter_mut().zip(encodings.iter()).zip(words.iter())
+            {
+                let words = inference::token_classification::aggregate_words(
+                    result,
+                    encoding,
+                    words,
+                    &self.task_state,
+                    strategy,
+                );
+                result.words = Some(words);
+            }
+        }
+
+        // entities are sliced from the input with byte offsets, so they are converted last
+        for (result, text) in results.iter_mut().zip(texts.iter()) {
+            inference::token_classification::convert_offsets(
+                result,
+                text,
+                request.options.offset_mapping(),
+            );
+        }
+
+        // entities are averaged from the logits, so they are only dropped afterwards
+        if !request.options.return_logits() {
+            results
+                .iter_mut()
+                .flat_map(|result| result.tokens.iter_mut())
+                .for_each(|token| token.scores.clear());
+        }
+
         Ok(TokenClassificationResponse {
             results,
             model_id: self.config.name.clone(),
@@ -0,0 +1,139 @@
+use crate::{
+    common::{
+        CountTokensRequest, CountTokensResponse, DetokenizeRequest, DetokenizeResponse,
+        OffsetMapping, TokenizeRequest, TokenizeResponse, model_type::ModelTypeSpec,
+    },
+    error::ApiError,
+    runtime::{
+        AudioInputState, EncoderfileState, ImageInputState, InputType, MultimodalInputState,
+        TaskType, TextInputState,
+    },
+};
+
+/// Input states that can be tokenized without running the model.
+pub trait TextInput {
+    /// `None` for model types that do not take text.
+    fn text_input(&self) -> Option<&TextInputState>;
+}
+
+impl TextInput for TextInputState {
+    fn text_input(&self) -> Option<&TextInputState> {
+        Some(self)
+    }
+}
+
+impl TextInput for ImageInputState {
+    fn text_input(&self) -> Option<&TextInputState> {
+        None
+    }
+}
+
+impl TextInput for AudioInputState {
+    fn text_input(&self) -> Option<&TextInputState> {
+        None
+    }
+}
+
+impl TextInput for MultimodalInputState {
+    fn text_input(&self) -> Option<&TextInputState> {
+        None
+    }
+}
+
+impl TextInputState {
+    /// Tokens of each input, as the model sees them.
+    pub fn tokenize(
+        &self,
+        model_id: String,
+        request: impl Into<TokenizeRequest>,
+    ) -> Result<TokenizeResponse, ApiError> {
+        let request = request.into();
+
+        let mapping = request.offset_mapping.unwrap_or_default();
+        let mut results = self
+            .tokenizer
+            .tokenize(request.inputs.clone(), request.truncate)?;
+
+        if mapping != OffsetMapping::Byte {
+            for (result, text) in results.iter_mut().zip(&request.inputs) {
+                let converter = mapping.converter(text);
+
+                for offset in result.offsets.iter_mut().flatten() {
+                    *offset = converter.convert(*offset);
+                }
+            }
+        }
+
+        Ok(TokenizeResponse {
+            results,
+            model_id,
+            metadata: request.metadata,
+        })
+    }
+
+    /// Text of each list of token ids.
+    pub fn detokenize(
+        &self,
+        model_id: String,
+        request: impl Into<DetokenizeRequest>,
+    ) -> Result<DetokenizeResponse, ApiError> {
+        let request = request.into();
+
+        let results = self
+            .tokenizer
+            .decode(request.inputs, request.skip_special_tokens.unwrap_or(true))?;
+
+        Ok(DetokenizeResponse {
+            results,
+            model_id,
+            metadata: request.metadata,
+        })
+    }
+
+    /// Number of tokens of each input.
+    pub fn count_tokens(
+        &self,
+        model_id: String,
+        request: impl Into<CountTokensRequest>,
+    ) -> Result<CountTokensResponse, ApiError> {
+        let request = request.into();
+
+        let results = self.tokenizer.count_tokens(request.inputs)?;
+
+        Ok(CountTokensResponse {
+            results,
+            max_length: self.tokenizer.max_length(),
+            model_id,
+            metadata: request.metadata,
+        })
+    }
+}
+
+impl<T> EncoderfileState<T>
+where
+    T: ModelTypeSpec + InputType<State = TextInputState> + TaskType,
+{
+    pub fn tokenize(
+        &self,
+        request: impl Into<TokenizeRequest>,
+    ) -> Result<TokenizeResponse, ApiError> {
+        self.model_input_state
+            .tokenize(self.config.name.clone(), request)
+    }
+
+    pub fn detokenize(
+        &self,
+        request: impl Into<DetokenizeRequest>,
+    ) -> Result<DetokenizeResponse, ApiError> {
+        self.model_input_state
+            .detokenize(self.config.name.clone(), request)
+    }
+
+    pub fn count_tokens(
+        &self,
+        request: impl Into<CountTokensRequest>,
+    ) -> Result<CountTokensResponse, ApiError> {
+        self.model_input_state
+            .count_tokens(self.config.name.clone(), request)
+    }
+}
@@ -196,6 +196,7 @@ mod tests {
                     height: Some(224),
                     width: Some(224),
                     shortest_edge: None,
+                    longest_edge: None,
                 }),
             },
         };
@@ -0,0 +1,160 @@
+use crate::{common::model_type, error::ApiError, runtime::ImageInputState};
+
+use super::{super::image::Image, super::tensor::Tensor, Postprocessor, Preprocessor, Transform};
+use ndarray::{Array2, Ix2};
+
+impl Postprocessor for Transform<model_type::ImageEmbedding> {
+    type Input = Array2<f32>;
+    type Output = Array2<f32>;
+
+    fn postprocess(&self, data: Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.postprocessor() {
+            Some(p) => p,
+            None => return Ok(data),
+        };
+
+        let batch_size = data.shape()[0];
+
+        let tensor = Tensor(data.into_dyn());
+
+        let result = func
+            .call::<Tensor>(tensor)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?
+            .into_inner()
+            .into_dimensionality::<Ix2>().map_err(|e| {
+                tracing::error!("Failed to cast array into Ix2: {e}. Check your lua transform to make sure it returns a tensor of shape [batch_size, *]");
+                ApiError::LuaError("Error postprocessing image embeddings".to_string())
+            })?;
+
+        let result_shape = result.shape();
+
+        if batch_size != result_shape[0] {
+            tracing::error!(
+                "Transform error: expected tensor of shape [{}, *], got tensor of shape {:?}",
+                batch_size,
+                result_shape
+            );
+
+            return Err(ApiError::LuaError(
+                "Error postprocessing image embeddings".to_string(),
+            ));
+        }
+
+        Ok(result)
+    }
+}
+
+impl Preprocessor for Transform<model_type::ImageEmbedding> {
+    type Input = (Image, ImageInputState);
+    type Output = Tensor;
+
+    fn preprocess(&self, (image, config): Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.preprocessor() {
+            Some(p) => p,
+            None => {
+                return Err(ApiError::InternalError(
+                    "No preprocessor defined for this model",
+                ));
+            }
+        };
+
+        self.lua
+            .globals()
+            .set("input_config", config)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+
+        func.call::<Tensor>(image)
+            .map_err(|e| ApiError::LuaError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::DEFAULT_LIBS;
+
+    #[test]
+    fn test_image_embedding_no_transform() {
+        let engine = Transform::<model_type::ImageEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("".to_string()),
+        )
+        .expect("Failed to create Transform");
+
+        let arr = ndarray::Array2::<f32>::from_elem((8, 16), 2.0);
+
+        let result = engine.postprocess(arr.clone()).expect("Failed");
+
+        assert_eq!(arr, result);
+    }
+
+    #[test]
+    fn test_image_embedding_truncate_transform() {
+        let engine = Transform::<model_type::ImageEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return arr:truncate_axis(2, 4)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array2::<f32>::from_elem((8, 16), 2.0);
+
+        let result = engine.postprocess(arr).expect("Failed");
+
+        assert_eq!(result.shape(), [8, 4]);
+    }
+
+    #[test]
+    fn test_image_embedding_transform_bad_fn() {
+        let engine = Transform::<model_type::ImageEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return 1
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array2::<f32>::from_elem((8, 16), 2.0);
+
+        let result = engine.postprocess(arr);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn test_image_embedding_bad_dimensionality() {
+        let engine = Transform::<model_type::ImageEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(x)
+            return x:sum_axis(1)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .unwrap();
+
+        let arr = ndarray::Array2::<f32>::from_elem((3, 3), 2.0);
+        let result = engine.postprocess(arr);
+
+        match result {
+            Err(ApiError::LuaError(s)) => {
+                assert!(s.contains("Error postprocessing image embeddings"))
+            }
+            _ => panic!("Didn't return lua error"),
+        }
+    }
+}
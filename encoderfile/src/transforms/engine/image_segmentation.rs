@@ -0,0 +1,138 @@
+use crate::{common::model_type, error::ApiError, runtime::ImageInputState};
+
+use super::{super::image::Image, super::tensor::Tensor, Postprocessor, Preprocessor, Transform};
+use ndarray::{Array4, Ix4};
+
+impl Postprocessor for Transform<model_type::ImageSegmentation> {
+    type Input = Array4<f32>;
+    type Output = Array4<f32>;
+
+    fn postprocess(&self, data: Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.postprocessor() {
+            Some(p) => p,
+            None => return Ok(data),
+        };
+
+        let expected_shape = data.shape().to_owned();
+
+        let tensor = Tensor(data.into_dyn());
+
+        let result = func
+            .call::<Tensor>(tensor)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?
+            .into_inner()
+            .into_dimensionality::<Ix4>().map_err(|e| {
+                tracing::error!("Failed to cast array into Ix4: {e}. Check your lua transform to make sure it returns a tensor of shape [batch_size, num_labels, height, width]");
+                ApiError::LuaError("Error postprocessing image segmentations".to_string())
+            })?;
+
+        let result_shape = result.shape();
+
+        if expected_shape.as_slice() != result_shape {
+            tracing::error!(
+                "Transform error: expected tensor of shape {:?}, got tensor of shape {:?}",
+                expected_shape.as_slice(),
+                result_shape
+            );
+
+            return Err(ApiError::LuaError(
+                "Error postprocessing image segmentations".to_string(),
+            ));
+        }
+
+        Ok(result)
+    }
+}
+
+impl Preprocessor for Transform<model_type::ImageSegmentation> {
+    type Input = (Image, ImageInputState);
+    type Output = Tensor;
+
+    fn preprocess(&self, (image, config): Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.preprocessor() {
+            Some(p) => p,
+            None => {
+                return Err(ApiError::InternalError(
+                    "No preprocessor defined for this model",
+                ));
+            }
+        };
+
+        self.lua
+            .globals()
+            .set("input_config", config)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+
+        func.call::<Tensor>(image)
+            .map_err(|e| ApiError::LuaError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::DEFAULT_LIBS;
+
+    #[test]
+    fn test_image_segmentation_no_transform() {
+        let engine = Transform::<model_type::ImageSegmentation>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("".to_string()),
+        )
+        .expect("Failed to create Transform");
+
+        let arr = ndarray::Array4::<f32>::from_elem((2, 3, 8, 8), 2.0);
+
+        let result = engine.postprocess(arr.clone()).expect("Failed");
+
+        assert_eq!(arr, result);
+    }
+
+    #[test]
+    fn test_image_segmentation_softmax_transform() {
+        let engine = Transform::<model_type::ImageSegmentation>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return arr:softmax(2)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array4::<f32>::from_elem((2, 4, 8, 8), 2.0);
+
+        let result = engine.postprocess(arr).expect("Failed");
+
+        assert_eq!(result.shape(), [2, 4, 8, 8]);
+        assert!(result.iter().all(|x| (x - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_image_segmentation_bad_shape() {
+        let engine = Transform::<model_type::ImageSegmentation>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return arr:sum_axis(2)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array4::<f32>::from_elem((2, 4, 8, 8), 2.0);
+
+        match engine.postprocess(arr) {
+            Err(ApiError::LuaError(s)) => {
+                assert!(s.contains("Error postprocessing image segmentations"))
+            }
+            _ => panic!("Didn't return lua error"),
+        }
+    }
+}
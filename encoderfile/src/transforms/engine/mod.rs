@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
     common::{
@@ -12,10 +12,18 @@ use crate::{
 use super::tensor::Tensor;
 use mlua::prelude::*;
 
+mod audio_classification;
+mod audio_embedding;
 mod embedding;
 mod image_classification;
+mod image_embedding;
+mod image_segmentation;
+mod late_interaction;
+mod multimodal_embedding;
+mod object_detection;
 mod sentence_embedding;
 mod sequence_classification;
+mod sparse_embedding;
 mod token_classification;
 
 impl From<&LuaLibs> for Vec<mlua::StdLib> {
@@ -87,7 +95,15 @@ transform!(EmbeddingTransform, Embedding);
 transform!(SequenceClassificationTransform, SequenceClassification);
 transform!(TokenClassificationTransform, TokenClassification);
 transform!(SentenceEmbeddingTransform, SentenceEmbedding);
+transform!(SparseEmbeddingTransform, SparseEmbedding);
+transform!(LateInteractionTransform, LateInteraction);
 transform!(ImageClassificationTransform, ImageClassification);
+transform!(ImageEmbeddingTransform, ImageEmbedding);
+transform!(ObjectDetectionTransform, ObjectDetection);
+transform!(ImageSegmentationTransform, ImageSegmentation);
+transform!(AudioClassificationTransform, AudioClassification);
+transform!(AudioEmbeddingTransform, AudioEmbedding);
+transform!(MultimodalEmbeddingTransform, MultimodalEmbedding);
 
 pub trait TransformSpec {
     fn has_postprocessor(&self) -> bool;
@@ -110,7 +126,6 @@ pub trait Preprocessor: TransformSpec {
 
 #[derive(Debug)]
 pub struct Transform<T: ModelTypeSpec> {
-    #[allow(dead_code)]
     lua: Lua,
     preprocessor: Option<LuaFunction>,
     postprocessor: Option<LuaFunction>,
@@ -151,6 +166,29 @@ impl<T: ModelTypeSpec> Transform<T> {
             _marker: PhantomData,
         })
     }
+
+    /// Exposes extra model outputs to `Postprocess` as the global `Outputs` table, keyed by
+    /// output name.
+    pub fn set_outputs(
+        &self,
+        outputs: &HashMap<String, ndarray::ArrayD<f32>>,
+    ) -> Result<(), ApiError> {
+        let table = self
+            .lua
+            .create_table()
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+
+        for (name, output) in outputs.iter() {
+            table
+                .set(name.as_str(), Tensor(output.clone()))
+                .map_err(|e| ApiError::LuaError(e.to_string()))?;
+        }
+
+        self.lua
+            .globals()
+            .set("Outputs", table)
+            .map_err(|e| ApiError::LuaError(e.to_string()))
+    }
 }
 
 impl<T: ModelTypeSpec> TransformSpec for Transform<T> {
@@ -191,6 +229,15 @@ fn new_lua(libs: Vec<mlua::StdLib>) -> Result<Lua, ApiError> {
             ApiError::InternalError("Failed to create new Lua tensor library")
         })?;
 
+    // extra model outputs, filled per batch by `set_outputs`
+    globals
+        .set(
+            "Outputs",
+            lua.create_table()
+                .map_err(|_| ApiError::InternalError("Failed to create Lua outputs table"))?,
+        )
+        .map_err(|_| ApiError::InternalError("Failed to create Lua outputs table"))?;
+
     Ok(lua)
 }
 
@@ -224,6 +271,36 @@ mod tests {
         assert!(function.call::<Tensor>(()).is_ok())
     }
 
+    #[test]
+    fn test_set_outputs() {
+        let transform = crate::transforms::SequenceClassificationTransform::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r#"
+                function Postprocess(arr)
+                    if Outputs.pooler_output == nil then
+                        return arr
+                    end
+                    return arr + Outputs.pooler_output:sum()
+                end
+                "#
+                .to_string(),
+            ),
+        )
+        .unwrap();
+
+        let logits = ndarray::Array2::<f32>::zeros((2, 3));
+        assert_eq!(transform.postprocess(logits.clone()).unwrap(), logits);
+
+        transform
+            .set_outputs(&HashMap::from([(
+                "pooler_output".to_string(),
+                ndarray::ArrayD::<f32>::ones(ndarray::IxDyn(&[2, 4])),
+            )]))
+            .unwrap();
+        assert_eq!(transform.postprocess(logits.clone()).unwrap(), logits + 8.0);
+    }
+
     #[test]
     fn test_no_unsafe_stdlibs_loaded() {
         let engine = new_test_lua();
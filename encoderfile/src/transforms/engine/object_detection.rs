@@ -0,0 +1,147 @@
+use crate::{common::model_type, error::ApiError, runtime::ImageInputState};
+
+use super::{super::image::Image, super::tensor::Tensor, Postprocessor, Preprocessor, Transform};
+use ndarray::{Array3, Ix3};
+
+impl Postprocessor for Transform<model_type::ObjectDetection> {
+    type Input = Array3<f32>;
+    type Output = Array3<f32>;
+
+    /// Turns `logits` of shape `[batch_size, num_queries, num_classes]` into class scores.
+    /// Without a `Postprocess` hook, scores are the softmax over the class axis.
+    fn postprocess(&self, data: Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.postprocessor() {
+            Some(p) => p,
+            None => {
+                return Tensor(data.into_dyn())
+                    .softmax(3)
+                    .map_err(|e| ApiError::LuaError(e.to_string()))?
+                    .into_inner()
+                    .into_dimensionality::<Ix3>()
+                    .map_err(|_| ApiError::InternalError("Failed to compute detection scores"));
+            }
+        };
+
+        let expected_shape = data.shape().to_owned();
+
+        let tensor = Tensor(data.into_dyn());
+
+        let result = func
+            .call::<Tensor>(tensor)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?
+            .into_inner()
+            .into_dimensionality::<Ix3>().map_err(|e| {
+                tracing::error!("Failed to cast array into Ix3: {e}. Check your lua transform to make sure it returns a tensor of shape [batch_size, num_queries, num_classes]");
+                ApiError::LuaError("Error postprocessing object detections".to_string())
+            })?;
+
+        let result_shape = result.shape();
+
+        if expected_shape.as_slice() != result_shape {
+            tracing::error!(
+                "Transform error: expected tensor of shape {:?}, got tensor of shape {:?}",
+                expected_shape.as_slice(),
+                result_shape
+            );
+
+            return Err(ApiError::LuaError(
+                "Error postprocessing object detections".to_string(),
+            ));
+        }
+
+        Ok(result)
+    }
+}
+
+impl Preprocessor for Transform<model_type::ObjectDetection> {
+    type Input = (Image, ImageInputState);
+    type Output = Tensor;
+
+    fn preprocess(&self, (image, config): Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match self.preprocessor() {
+            Some(p) => p,
+            None => {
+                return Err(ApiError::InternalError(
+                    "No preprocessor defined for this model",
+                ));
+            }
+        };
+
+        self.lua
+            .globals()
+            .set("input_config", config)
+            .map_err(|e| ApiError::LuaError(e.to_string()))?;
+
+        func.call::<Tensor>(image)
+            .map_err(|e| ApiError::LuaError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::DEFAULT_LIBS;
+
+    #[test]
+    fn test_object_detection_no_transform_softmax() {
+        let engine = Transform::<model_type::ObjectDetection>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("".to_string()),
+        )
+        .expect("Failed to create Transform");
+
+        let arr = ndarray::Array3::<f32>::from_elem((2, 10, 4), 1.0);
+
+        let result = engine.postprocess(arr).expect("Failed");
+
+        assert_eq!(result.shape(), [2, 10, 4]);
+        assert!(result.iter().all(|x| (x - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_object_detection_identity_transform() {
+        let engine = Transform::<model_type::ObjectDetection>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return arr
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array3::<f32>::from_elem((2, 10, 4), 2.0);
+
+        let result = engine.postprocess(arr.clone()).expect("Failed");
+
+        assert_eq!(arr, result);
+    }
+
+    #[test]
+    fn test_object_detection_bad_shape() {
+        let engine = Transform::<model_type::ObjectDetection>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr)
+            return arr:truncate_axis(3, 2)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array3::<f32>::from_elem((2, 10, 4), 2.0);
+
+        match engine.postprocess(arr) {
+            Err(ApiError::LuaError(s)) => {
+                assert!(s.contains("Error postprocessing object detections"))
+            }
+            _ => panic!("Didn't return lua error"),
+        }
+    }
+}
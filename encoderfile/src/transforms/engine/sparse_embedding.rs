@@ -0,0 +1,137 @@
+use crate::{common::model_type, error::ApiError};
+
+use super::{super::tensor::Tensor, Postprocessor, Transform};
+use ndarray::{Array2, Array3, Ix2};
+
+impl Postprocessor for Transform<model_type::SparseEmbedding> {
+    type Input = (Array3<f32>, Array2<f32>);
+    type Output = Array2<f32>;
+
+    fn postprocess(&self, (data, mask): Self::Input) -> Result<Self::Output, ApiError> {
+        let func = match &self.postprocessor {
+            Some(p) => p,
+            None => {
+                let Tensor(pooled) = Tensor(data.into_dyn())
+                    .splade_pool(Tensor(mask.into_dyn()))
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to splade pool. This should not happen. More details: {:?}",
+                            e
+                        );
+                        ApiError::InternalError("Failed to postprocess embeddings")
+                    })?;
+
+                return pooled.into_dimensionality::<Ix2>()
+                    .map_err(|e| {
+                        tracing::error!("Failed to cast splade pool results into Ix2. This should not happen. More details: {:?}", e);
+                        ApiError::InternalError("Failed to postprocess embeddings")
+                    });
+            }
+        };
+
+        let batch_size = data.shape()[0];
+
+        let tensor = Tensor(data.into_dyn());
+
+        let result = func
+            .call::<Tensor>((tensor, Tensor(mask.into_dyn())))
+            .map_err(|e| ApiError::LuaError(e.to_string()))?
+            .into_inner()
+            .into_dimensionality::<Ix2>().map_err(|e| {
+                tracing::error!("Failed to cast array into Ix2: {e}. Check your lua transform to make sure it returns a tensor of shape [batch_size, vocab_size]");
+                ApiError::LuaError("Error postprocessing embeddings".to_string())
+            })?;
+
+        let result_shape = result.shape();
+
+        if batch_size != result_shape[0] {
+            tracing::error!(
+                "Transform error: expected tensor of shape [{}, *], got tensor of shape {:?}",
+                batch_size,
+                result_shape
+            );
+
+            return Err(ApiError::LuaError(
+                "Error postprocessing embeddings".to_string(),
+            ));
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::DEFAULT_LIBS;
+
+    #[test]
+    fn test_default_splade_pool() {
+        let engine = Transform::<model_type::SparseEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some("".to_string()),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array3::<f32>::from_elem((16, 32, 128), 2.0);
+        let mask = ndarray::Array2::<f32>::from_elem((16, 32), 1.0);
+
+        let result = engine
+            .postprocess((arr, mask))
+            .expect("Failed to compute pool");
+
+        assert_eq!(result.shape(), [16, 128]);
+        assert!(result.iter().all(|v| *v == 2.0f32.ln_1p()));
+    }
+
+    #[test]
+    fn test_successful_pool() {
+        let engine = Transform::<model_type::SparseEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr, mask)
+            return arr:splade_pool(mask)
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .expect("Failed to create engine");
+
+        let arr = ndarray::Array3::<f32>::from_elem((16, 32, 128), -1.0);
+        let mask = ndarray::Array2::<f32>::from_elem((16, 32), 1.0);
+
+        let result = engine
+            .postprocess((arr, mask))
+            .expect("Failed to compute pool");
+
+        assert_eq!(result.shape(), [16, 128]);
+        assert!(result.iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_bad_dimensionality_transform_postprocessing() {
+        let engine = Transform::<model_type::SparseEmbedding>::new(
+            DEFAULT_LIBS.to_vec(),
+            Some(
+                r##"
+        function Postprocess(arr, mask)
+            return arr
+        end
+        "##
+                .to_string(),
+            ),
+        )
+        .unwrap();
+
+        let arr = ndarray::Array3::<f32>::from_elem((3, 3, 3), 2.0);
+        let mask = ndarray::Array2::<f32>::from_elem((3, 3), 1.0);
+        let result = engine.postprocess((arr, mask));
+
+        match result {
+            Err(ApiError::LuaError(s)) => assert!(s.contains("Error postprocessing embeddings")),
+            _ => panic!("Didn't return lua error"),
+        }
+    }
+}
@@ -28,7 +28,7 @@ impl FromLua for Image {
 fn dyn_image_to_array3(image: &DynamicImage, num_channels: u32) -> Array3<f32> {
     // TODO num_channels is tied to the format we convert to
     let raw = image.to_rgb8().into_raw();
-    let (h_us, w_us) = image.dimensions();
+    let (w_us, h_us) = image.dimensions();
     let h_us: usize = h_us as usize;
     let w_us: usize = w_us as usize;
     let nc_us: usize = num_channels as usize;
@@ -54,6 +54,8 @@ impl LuaUserData for Image {
         methods.add_method("resize", |_, this, (height, width)| {
             Ok(Image(resize_image(this.into_inner(), height, width)))
         });
+        methods.add_method("width", |_, this, ()| Ok(this.into_inner().width()));
+        methods.add_method("height", |_, this, ()| Ok(this.into_inner().height()));
     }
 }
 
@@ -86,3 +88,22 @@ fn test_image_to_array() {
         .unwrap();
     assert_eq!(array.into_inner().shape(), &[3, 224, 224]);
 }
+
+#[test]
+fn test_non_square_image_to_array() {
+    let img = image::open("../test-pictures/yoga02.jpg").expect("Failed to open test image");
+    let lua = load_env();
+    lua.globals().set("img", Image(img)).unwrap();
+    let (width, height): (u32, u32) = lua
+        .load("local resized = img:resize(20, 30) return resized:width(), resized:height()")
+        .eval()
+        .unwrap();
+    assert_eq!((width, height), (30, 20));
+
+    // arrays are [num_channels, height, width]
+    let array: Tensor = lua
+        .load("return img:resize(20,30):to_array(3)")
+        .eval()
+        .unwrap();
+    assert_eq!(array.into_inner().shape(), &[3, 20, 30]);
+}
@@ -66,6 +66,7 @@ impl LuaUserData for Tensor {
         methods.add_method("ndim", |_, this, _: ()| this.ndim());
         methods.add_method("softmax", |_, this, axis: isize| this.softmax(axis));
         methods.add_method("transpose", |_, this, _: ()| this.transpose());
+        methods.add_method("matmul", |_, this, other: Tensor| this.matmul(&other));
         methods.add_method("lp_normalize", |_, this, (p, axis)| {
             this.lp_normalize(p, axis)
         });
@@ -82,6 +83,7 @@ impl LuaUserData for Tensor {
             this.fold_axis(axis, acc, func)
         });
         methods.add_method("mean_pool", |_, this, mask| this.mean_pool(mask));
+        methods.add_method("splade_pool", |_, this, mask| this.splade_pool(mask));
         methods.add_method("clamp", |_, this, (min, max)| this.clamp(min, max));
         methods.add_method("layer_norm", |_, this, (axis, eps)| {
             this.layer_norm(axis, eps)
@@ -0,0 +1,56 @@
+use super::Tensor;
+use mlua::prelude::*;
+use ndarray::Ix2;
+
+impl Tensor {
+    #[tracing::instrument(skip_all)]
+    pub fn matmul(&self, other: &Tensor) -> Result<Self, LuaError> {
+        let lhs = self
+            .0
+            .view()
+            .into_dimensionality::<Ix2>()
+            .map_err(|_| LuaError::external("matmul requires 2D tensors"))?;
+        let rhs = other
+            .0
+            .view()
+            .into_dimensionality::<Ix2>()
+            .map_err(|_| LuaError::external("matmul requires 2D tensors"))?;
+
+        if lhs.ncols() != rhs.nrows() {
+            return Err(LuaError::external(format!(
+                "Cannot multiply tensors of shapes {:?} and {:?}",
+                lhs.shape(),
+                rhs.shape()
+            )));
+        }
+
+        Ok(Self(lhs.dot(&rhs).into_dyn()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matmul() {
+        let a = Tensor(ndarray::array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]].into_dyn());
+        let b = Tensor(ndarray::array![[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]].into_dyn());
+
+        let expected = ndarray::array![[1.0, 2.0, 3.0], [3.0, 4.0, 7.0], [5.0, 6.0, 11.0]];
+
+        assert_eq!(a.matmul(&b).unwrap().0, expected.into_dyn());
+    }
+
+    #[test]
+    fn test_matmul_shape_mismatch() {
+        let a = Tensor(ndarray::array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+        let b = Tensor(ndarray::array![[1.0, 2.0, 3.0]].into_dyn());
+
+        assert!(a.matmul(&b).is_err());
+        assert!(
+            a.matmul(&Tensor(ndarray::array![1.0, 2.0].into_dyn()))
+                .is_err()
+        );
+    }
+}
@@ -7,9 +7,11 @@ pub mod fold_axis;
 pub mod layer_norm;
 pub mod lp_normalize;
 pub mod map_axis;
+pub mod matmul;
 pub mod mean_pool;
 pub mod properties;
 pub mod softmax;
+pub mod splade_pool;
 pub mod sum_axis;
 pub mod transpose;
 pub mod truncate_axis;
@@ -0,0 +1,71 @@
+use super::Tensor;
+use mlua::prelude::*;
+use ndarray::Axis;
+
+impl Tensor {
+    /// SPLADE pooling: `max(log(1 + relu(x)))` over the sequence axis, ignoring masked tokens.
+    #[tracing::instrument(skip_all)]
+    pub fn splade_pool(&self, Tensor(mask): Tensor) -> Result<Self, LuaError> {
+        if self.0.ndim() != 3 || mask.ndim() != 2 {
+            return Err(LuaError::external(format!(
+                "splade_pool expects a tensor of shape [batch_size, seq_len, vocab_size] and a mask of shape [batch_size, seq_len], got {:?} and {:?}",
+                self.0.shape(),
+                mask.shape()
+            )));
+        }
+
+        let mask_expanded = mask.insert_axis(Axis(2));
+
+        let mask_broadcast = mask_expanded
+            .broadcast(self.0.shape())
+            .ok_or(LuaError::external(format!(
+                "cannot broadcast shape {:?} to {:?}",
+                mask_expanded.shape(),
+                self.0.shape()
+            )))?;
+
+        // log(1 + relu(x)) is non-negative, so zeroing masked tokens excludes them from the max
+        let activated = self.0.mapv(|x| x.max(0.0).ln_1p()) * &mask_broadcast;
+
+        Ok(Self(
+            activated.fold_axis(Axis(1), 0.0, |acc, x| acc.max(*x)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splade_pool_max_over_tokens() {
+        // shape: (1, 2, 3)
+        let x = Tensor(ndarray::array![[[1.0, -2.0, 0.0], [3.0, 0.5, -1.0]]].into_dyn());
+        let mask = Tensor(ndarray::array![[1.0, 1.0]].into_dyn());
+
+        let pooled = x.splade_pool(mask).unwrap();
+        let expected = ndarray::array![[3.0f32.ln_1p(), 0.5f32.ln_1p(), 0.0]].into_dyn();
+
+        assert_eq!(pooled.0, expected);
+    }
+
+    #[test]
+    fn splade_pool_ignores_masked_tokens() {
+        // shape: (1, 2, 2)
+        let x = Tensor(ndarray::array![[[1.0, 2.0], [10.0, 10.0]]].into_dyn());
+        let mask = Tensor(ndarray::array![[1.0, 0.0]].into_dyn());
+
+        let pooled = x.splade_pool(mask).unwrap();
+        let expected = ndarray::array![[1.0f32.ln_1p(), 2.0f32.ln_1p()]].into_dyn();
+
+        assert_eq!(pooled.0, expected);
+    }
+
+    #[test]
+    fn splade_pool_bad_shape() {
+        let x = Tensor(ndarray::array![[1.0, 2.0]].into_dyn());
+        let mask = Tensor(ndarray::array![[1.0, 1.0]].into_dyn());
+
+        assert!(x.splade_pool(mask).is_err());
+    }
+}
@@ -1,10 +1,17 @@
 use crate::{
     common::{
-        FromCliInput,
+        AudioClassificationResponse, AudioEmbeddingResponse, CountTokensRequest, DetokenizeRequest,
+        EmbeddingResponse, FromCliInput, ImageClassificationResponse, ImageEmbeddingResponse,
+        ImageSegmentationResponse, LateInteractionResponse, MultimodalSimilarityResponse,
+        ObjectDetectionResponse, SentenceEmbeddingResponse, SequenceClassificationResponse,
+        SparseEmbeddingResponse, TokenClassificationResponse, TokenizeRequest,
         model_type::{self, ModelType, ModelTypeSpec},
     },
-    runtime::{EncoderfileLoader, EncoderfileState, InputType, ORTExecutionProvider, TaskType},
-    services::{Inference, Metadata},
+    runtime::{
+        EncoderfileLoader, EncoderfileState, InputType, ORTExecutionProvider, TaskType,
+        TextInputState,
+    },
+    services::{Inference, Metadata, TextInput},
     transport::{
         grpc::GrpcRouter,
         http::HttpRouter,
@@ -20,38 +27,106 @@ use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::{
     fmt::{Debug, Display},
     io::{Read, Seek, Write},
+    path::Path,
     sync::Arc,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-pub trait CliRoute: Inference {
+pub trait CliRoute: Inference<Output: CliOutput> {
     fn cli_route(
         &self,
         inputs: Vec<String>,
         format: Format,
         out_dir: Option<String>,
+        mask_dir: Option<String>,
     ) -> Result<()> {
         let input = Self::Input::from_cli_input(inputs);
 
         let result = self.inference(input)?;
 
-        let serialized = match format {
-            Format::Json => serde_json::to_string_pretty(&result)?,
-        };
+        if let Some(mask_dir) = mask_dir {
+            std::fs::create_dir_all(&mask_dir)?;
+            result.write_masks(Path::new(&mask_dir))?;
+        }
 
-        match out_dir {
-            Some(o) => {
-                let mut file = std::fs::File::create(o)?;
-                file.write_all(serialized.as_bytes())?;
-            }
-            None => println!("{}", serialized),
-        };
+        write_output(&result, format, out_dir)
+    }
+}
+
+/// Writes `result` to `out_dir`, or to stdout.
+fn write_output(
+    result: &impl serde::Serialize,
+    format: Format,
+    out_dir: Option<String>,
+) -> Result<()> {
+    let serialized = match format {
+        Format::Json => serde_json::to_string_pretty(result)?,
+    };
+
+    match out_dir {
+        Some(o) => {
+            let mut file = std::fs::File::create(o)?;
+            file.write_all(serialized.as_bytes())?;
+        }
+        None => println!("{}", serialized),
+    };
+
+    Ok(())
+}
+
+impl<T: Inference<Output: CliOutput>> CliRoute for T {}
 
+pub trait CliOutput {
+    /// Writes masks, if the output has any, as PNG files to `dir`.
+    fn write_masks(&self, _dir: &Path) -> Result<()> {
         Ok(())
     }
 }
 
-impl<T: Inference> CliRoute for T {}
+macro_rules! cli_output_impl {
+    [ $( $x:ident ),* $(,)? ] => {
+        $(
+            impl CliOutput for $x {}
+        )*
+    };
+}
+
+cli_output_impl![
+    EmbeddingResponse,
+    SequenceClassificationResponse,
+    TokenClassificationResponse,
+    SentenceEmbeddingResponse,
+    SparseEmbeddingResponse,
+    LateInteractionResponse,
+    ImageClassificationResponse,
+    ImageEmbeddingResponse,
+    ObjectDetectionResponse,
+    AudioClassificationResponse,
+    AudioEmbeddingResponse,
+    MultimodalSimilarityResponse,
+];
+
+impl CliOutput for ImageSegmentationResponse {
+    /// Writes one `{image_index}_{label}.png` file per segment.
+    fn write_masks(&self, dir: &Path) -> Result<()> {
+        for (i, result) in self.results.iter().enumerate() {
+            for segment in &result.segments {
+                let label: String = segment
+                    .label
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                    .collect();
+
+                segment.mask.to_image().save_with_format(
+                    dir.join(format!("{i}_{label}.png")),
+                    image::ImageFormat::Png,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Parser)]
 pub struct Cli {
@@ -92,9 +167,40 @@ pub enum Commands {
         format: Format,
         #[arg(short)]
         out_dir: Option<String>,
+        #[arg(long, help = "Directory to write segmentation masks to as PNG files.")]
+        mask_dir: Option<String>,
         #[command(flatten)]
         onnx_args: ONNXArgs,
     },
+    /// Tokenizes inputs with the model's tokenizer, without running the model.
+    Tokenize {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        #[arg(short, long, default_value_t = Format::Json)]
+        format: Format,
+        #[arg(short)]
+        out_dir: Option<String>,
+    },
+    /// Decodes token ids back into text.
+    Detokenize {
+        #[arg(required = true)]
+        ids: Vec<u32>,
+        #[arg(long, default_value_t = false)]
+        keep_special_tokens: bool,
+        #[arg(short, long, default_value_t = Format::Json)]
+        format: Format,
+        #[arg(short)]
+        out_dir: Option<String>,
+    },
+    /// Counts the tokens of inputs, without truncating them.
+    CountTokens {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        #[arg(short, long, default_value_t = Format::Json)]
+        format: Format,
+        #[arg(short)]
+        out_dir: Option<String>,
+    },
     Mcp {
         #[arg(long, default_value = "0.0.0.0")]
         hostname: String,
@@ -131,10 +237,42 @@ impl Commands {
                 self.execute_from_loader::<R, model_type::SentenceEmbedding>(loader)
                     .await
             }
+            ModelType::SparseEmbedding => {
+                self.execute_from_loader::<R, model_type::SparseEmbedding>(loader)
+                    .await
+            }
+            ModelType::LateInteraction => {
+                self.execute_from_loader::<R, model_type::LateInteraction>(loader)
+                    .await
+            }
             ModelType::ImageClassification => {
                 self.execute_from_loader::<R, model_type::ImageClassification>(loader)
                     .await
             }
+            ModelType::ImageEmbedding => {
+                self.execute_from_loader::<R, model_type::ImageEmbedding>(loader)
+                    .await
+            }
+            ModelType::ObjectDetection => {
+                self.execute_from_loader::<R, model_type::ObjectDetection>(loader)
+                    .await
+            }
+            ModelType::ImageSegmentation => {
+                self.execute_from_loader::<R, model_type::ImageSegmentation>(loader)
+                    .await
+            }
+            ModelType::AudioClassification => {
+                self.execute_from_loader::<R, model_type::AudioClassification>(loader)
+                    .await
+            }
+            ModelType::AudioEmbedding => {
+                self.execute_from_loader::<R, model_type::AudioEmbedding>(loader)
+                    .await
+            }
+            ModelType::MultimodalEmbedding => {
+                self.execute_from_loader::<R, model_type::MultimodalEmbedding>(loader)
+                    .await
+            }
         }
     }
     pub async fn execute_from_loader<
@@ -147,7 +285,7 @@ impl Commands {
     ) -> Result<()>
     where
         Arc<EncoderfileState<T>>: Inference + GrpcRouter + HttpRouter + McpRouter + CliRoute,
-        <T as InputType>::State: Debug,
+        <T as InputType>::State: Debug + TextInput,
         <T as TaskType>::State: Debug,
         for<'b> <T as InputType>::State:
             TryFrom<&'b mut EncoderfileLoader<'loader, R>, Error = anyhow::Error>,
@@ -168,6 +306,11 @@ impl Commands {
                 key_file,
                 onnx_args,
             } => {
+                let vision_session = loader.vision_session(
+                    onnx_args.to_provider(),
+                    onnx_args.enable_cpu_fallback(),
+                    onnx_args.clone().graph_optimization_level(),
+                )?;
                 let session = loader
                     .session(
                         onnx_args.to_provider(),
@@ -176,15 +319,22 @@ impl Commands {
                     )?
                     .into();
                 let config = loader.encoderfile_config()?;
-
-                let state = Arc::new(EncoderfileState::<T>::new(
-                    config,
-                    session,
-                    <T as InputType>::State::try_from(loader)
-                        .expect("could not load model input state from file"),
-                    <T as TaskType>::State::try_from(loader)
-                        .expect("could not load model task state from file"),
-                ));
+                let outputs = loader.model_outputs()?;
+                let model_inputs = loader.model_inputs()?;
+
+                let state = Arc::new(
+                    EncoderfileState::<T>::new(
+                        config,
+                        session,
+                        <T as InputType>::State::try_from(loader)
+                            .expect("could not load model input state from file"),
+                        <T as TaskType>::State::try_from(loader)
+                            .expect("could not load model task state from file"),
+                    )
+                    .with_vision_session(vision_session)
+                    .with_outputs(outputs)
+                    .with_inputs(&model_inputs)?,
+                );
 
                 let banner = crate::get_banner(state.model_id().as_str());
 
@@ -229,8 +379,14 @@ impl Commands {
                 inputs,
                 format,
                 out_dir,
+                mask_dir,
                 onnx_args,
             } => {
+                let vision_session = loader.vision_session(
+                    onnx_args.to_provider(),
+                    onnx_args.enable_cpu_fallback(),
+                    onnx_args.clone().graph_optimization_level(),
+                )?;
                 let session = loader
                     .session(
                         onnx_args.to_provider(),
@@ -240,19 +396,70 @@ impl Commands {
                     .into();
 
                 let config = loader.encoderfile_config()?;
-
-                let state = Arc::new(EncoderfileState::<T>::new(
-                    config,
-                    session,
-                    <T as InputType>::State::try_from(loader)
-                        .expect("could not load model input state from file"),
-                    <T as TaskType>::State::try_from(loader)
-                        .expect("could not load model task state from file"),
-                ));
+                let outputs = loader.model_outputs()?;
+                let model_inputs = loader.model_inputs()?;
+
+                let state = Arc::new(
+                    EncoderfileState::<T>::new(
+                        config,
+                        session,
+                        <T as InputType>::State::try_from(loader)
+                            .expect("could not load model input state from file"),
+                        <T as TaskType>::State::try_from(loader)
+                            .expect("could not load model task state from file"),
+                    )
+                    .with_vision_session(vision_session)
+                    .with_outputs(outputs)
+                    .with_inputs(&model_inputs)?,
+                );
 
                 setup_tracing(None)?;
 
-                state.cli_route(inputs, format, out_dir)?
+                state.cli_route(inputs, format, out_dir, mask_dir)?
+            }
+            Commands::Tokenize {
+                inputs,
+                format,
+                out_dir,
+            } => {
+                let model_id = loader.encoderfile_config()?.name;
+                let input_state = <T as InputType>::State::try_from(loader)?;
+
+                let result = text_input(&input_state)?
+                    .tokenize(model_id, TokenizeRequest::from_cli_input(inputs))?;
+
+                write_output(&result, format, out_dir)?
+            }
+            Commands::Detokenize {
+                ids,
+                keep_special_tokens,
+                format,
+                out_dir,
+            } => {
+                let model_id = loader.encoderfile_config()?.name;
+                let input_state = <T as InputType>::State::try_from(loader)?;
+
+                let request = DetokenizeRequest {
+                    inputs: vec![ids],
+                    skip_special_tokens: Some(!keep_special_tokens),
+                    metadata: None,
+                };
+                let result = text_input(&input_state)?.detokenize(model_id, request)?;
+
+                write_output(&result, format, out_dir)?
+            }
+            Commands::CountTokens {
+                inputs,
+                format,
+                out_dir,
+            } => {
+                let model_id = loader.encoderfile_config()?.name;
+                let input_state = <T as InputType>::State::try_from(loader)?;
+
+                let result = text_input(&input_state)?
+                    .count_tokens(model_id, CountTokensRequest::from_cli_input(inputs))?;
+
+                write_output(&result, format, out_dir)?
             }
             Commands::Mcp {
                 hostname,
@@ -261,6 +468,11 @@ impl Commands {
                 key_file,
                 onnx_args,
             } => {
+                let vision_session = loader.vision_session(
+                    onnx_args.to_provider(),
+                    onnx_args.enable_cpu_fallback(),
+                    onnx_args.clone().graph_optimization_level(),
+                )?;
                 let session = loader
                     .session(
                         onnx_args.to_provider(),
@@ -270,15 +482,22 @@ impl Commands {
                     .into();
 
                 let config = loader.encoderfile_config()?;
-
-                let state = Arc::new(EncoderfileState::<T>::new(
-                    config,
-                    session,
-                    <T as InputType>::State::try_from(loader)
-                        .expect("could not load model input state from file"),
-                    <T as TaskType>::State::try_from(loader)
-                        .expect("could not load model input state from file"),
-                ));
+                let outputs = loader.model_outputs()?;
+                let model_inputs = loader.model_inputs()?;
+
+                let state = Arc::new(
+                    EncoderfileState::<T>::new(
+                        config,
+                        session,
+                        <T as InputType>::State::try_from(loader)
+                            .expect("could not load model input state from file"),
+                        <T as TaskType>::State::try_from(loader)
+                            .expect("could not load model input state from file"),
+                    )
+                    .with_vision_session(vision_session)
+                    .with_outputs(outputs)
+                    .with_inputs(&model_inputs)?,
+                );
 
                 let banner = crate::get_banner(state.model_id().as_str());
                 let mcp_process = tokio::spawn(run_mcp(hostname, port, cert_file, key_file, state));
@@ -290,6 +509,12 @@ impl Commands {
     }
 }
 
+fn text_input(input_state: &impl TextInput) -> Result<&TextInputState> {
+    input_state.text_input().ok_or_else(|| {
+        crate::error::ApiError::ConfigError("Model type does not take text inputs").into()
+    })
+}
+
 #[derive(Clone, Args)]
 pub struct ONNXArgs {
     #[arg(long, default_value_t = false)]
@@ -7,6 +7,7 @@ impl crate::error::ApiError {
             Self::InternalError(s) => Status::internal(*s),
             Self::ConfigError(s) => Status::internal(*s),
             Self::LuaError(s) => Status::internal(s),
+            Self::InputTooLong { .. } => Status::invalid_argument(self.to_string()),
         }
     }
 }
@@ -1,8 +1,9 @@
 use crate::{
     common::model_type,
     generated::{
-        embedding, image_classification, sentence_embedding, sequence_classification,
-        token_classification,
+        audio_classification, audio_embedding, embedding, image_classification, image_embedding,
+        image_segmentation, late_interaction, multimodal_embedding, object_detection,
+        sentence_embedding, sequence_classification, sparse_embedding, token_classification,
     },
     runtime::AppState,
     services::{Inference, Metadata},
@@ -36,6 +37,28 @@ macro_rules! generate_grpc_server {
         $response_path:ident,
         $trait_path:ident,
         $server_type:ident
+    ) => {
+        generate_grpc_server!(
+            $model_type,
+            $generated_mod,
+            $server_mod,
+            $request_path,
+            $response_path,
+            $trait_path,
+            $server_type,
+            {}
+        );
+    };
+    // additional RPCs, each backed by a method of the same name on the model state
+    (
+        $model_type:ident,
+        $generated_mod:ident,
+        $server_mod:ident,
+        $request_path:ident,
+        $response_path:ident,
+        $trait_path:ident,
+        $server_type:ident,
+        { $($rpc:ident($rpc_request:ident, $rpc_response:ident)),* $(,)? }
     ) => {
         impl GrpcRouter for AppState<model_type::$model_type> {
             fn grpc_router(self) -> axum::Router {
@@ -77,6 +100,23 @@ macro_rules! generate_grpc_server {
                 println!("And the metadata is...: {:?}", self.state.metadata());
                 Ok(tonic::Response::new(self.state.metadata().into()))
             }
+
+            $(
+                async fn $rpc(
+                    &self,
+                    request: tonic::Request<$crate::generated::$generated_mod::$rpc_request>,
+                ) -> Result<
+                    tonic::Response<$crate::generated::$generated_mod::$rpc_response>,
+                    tonic::Status,
+                > {
+                    Ok(tonic::Response::new(
+                        self.state
+                            .$rpc(request.into_inner())
+                            .map_err(|e| e.to_tonic_status())?
+                            .into(),
+                    ))
+                }
+            )*
         }
     };
 }
@@ -88,7 +128,12 @@ generate_grpc_server!(
     EmbeddingRequest,
     EmbeddingResponse,
     EmbeddingInference,
-    EmbeddingInferenceServer
+    EmbeddingInferenceServer,
+    {
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
 );
 
 generate_grpc_server!(
@@ -98,7 +143,12 @@ generate_grpc_server!(
     SequenceClassificationRequest,
     SequenceClassificationResponse,
     SequenceClassificationInference,
-    SequenceClassificationInferenceServer
+    SequenceClassificationInferenceServer,
+    {
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
 );
 
 generate_grpc_server!(
@@ -108,7 +158,13 @@ generate_grpc_server!(
     TokenClassificationRequest,
     TokenClassificationResponse,
     TokenClassificationInference,
-    TokenClassificationInferenceServer
+    TokenClassificationInferenceServer,
+    {
+        redact(RedactRequest, RedactResponse),
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
 );
 
 generate_grpc_server!(
@@ -118,7 +174,46 @@ generate_grpc_server!(
     SentenceEmbeddingRequest,
     SentenceEmbeddingResponse,
     SentenceEmbeddingInference,
-    SentenceEmbeddingInferenceServer
+    SentenceEmbeddingInferenceServer,
+    {
+        similarity(SimilarityRequest, SimilarityResponse),
+        dedupe(DedupeRequest, DedupeResponse),
+        search(SearchRequest, SearchResponse),
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
+);
+
+generate_grpc_server!(
+    SparseEmbedding,
+    sparse_embedding,
+    sparse_embedding_inference_server,
+    SparseEmbeddingRequest,
+    SparseEmbeddingResponse,
+    SparseEmbeddingInference,
+    SparseEmbeddingInferenceServer,
+    {
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
+);
+
+generate_grpc_server!(
+    LateInteraction,
+    late_interaction,
+    late_interaction_inference_server,
+    LateInteractionRequest,
+    LateInteractionResponse,
+    LateInteractionInference,
+    LateInteractionInferenceServer,
+    {
+        score(LateInteractionScoreRequest, LateInteractionScoreResponse),
+        tokenize(TokenizeRequest, TokenizeResponse),
+        detokenize(DetokenizeRequest, DetokenizeResponse),
+        count_tokens(CountTokensRequest, CountTokensResponse),
+    }
 );
 
 generate_grpc_server!(
@@ -130,3 +225,67 @@ generate_grpc_server!(
     ImageClassificationInference,
     ImageClassificationInferenceServer
 );
+
+generate_grpc_server!(
+    ImageEmbedding,
+    image_embedding,
+    image_embedding_inference_server,
+    ImageEmbeddingRequest,
+    ImageEmbeddingResponse,
+    ImageEmbeddingInference,
+    ImageEmbeddingInferenceServer
+);
+
+generate_grpc_server!(
+    ObjectDetection,
+    object_detection,
+    object_detection_inference_server,
+    ObjectDetectionRequest,
+    ObjectDetectionResponse,
+    ObjectDetectionInference,
+    ObjectDetectionInferenceServer
+);
+
+generate_grpc_server!(
+    ImageSegmentation,
+    image_segmentation,
+    image_segmentation_inference_server,
+    ImageSegmentationRequest,
+    ImageSegmentationResponse,
+    ImageSegmentationInference,
+    ImageSegmentationInferenceServer
+);
+
+generate_grpc_server!(
+    AudioClassification,
+    audio_classification,
+    audio_classification_inference_server,
+    AudioClassificationRequest,
+    AudioClassificationResponse,
+    AudioClassificationInference,
+    AudioClassificationInferenceServer
+);
+
+generate_grpc_server!(
+    AudioEmbedding,
+    audio_embedding,
+    audio_embedding_inference_server,
+    AudioEmbeddingRequest,
+    AudioEmbeddingResponse,
+    AudioEmbeddingInference,
+    AudioEmbeddingInferenceServer
+);
+
+generate_grpc_server!(
+    MultimodalEmbedding,
+    multimodal_embedding,
+    multimodal_embedding_inference_server,
+    MultimodalSimilarityRequest,
+    MultimodalSimilarityResponse,
+    MultimodalEmbeddingInference,
+    MultimodalEmbeddingInferenceServer,
+    {
+        embed_text(MultimodalTextEmbeddingRequest, MultimodalEmbeddingResponse),
+        embed_image(MultimodalImageEmbeddingRequest, MultimodalEmbeddingResponse),
+    }
+);
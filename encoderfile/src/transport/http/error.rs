@@ -8,6 +8,10 @@ impl crate::error::ApiError {
             Self::InternalError(s) => (StatusCode::INTERNAL_SERVER_ERROR, Cow::Borrowed(*s)),
             Self::ConfigError(s) => (StatusCode::INTERNAL_SERVER_ERROR, Cow::Borrowed(*s)),
             Self::LuaError(s) => (StatusCode::INTERNAL_SERVER_ERROR, Cow::Owned(s.to_string())),
+            Self::InputTooLong { .. } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Cow::Owned(self.to_string()),
+            ),
         }
     }
 }
@@ -11,6 +11,10 @@ where
 
 macro_rules! predict_endpoint {
     ($mod_name:ident, $model_type:ident) => {
+        predict_endpoint!($mod_name, $model_type, {});
+    };
+    // additional POST endpoints, each backed by a method of the same name on the model state
+    ($mod_name:ident, $model_type:ident, { $($path:literal => $handler:ident($request:ident, $response:ident)),* $(,)? }) => {
         mod $mod_name {
             use super::base;
             use crate::{runtime::AppState, services::Inference};
@@ -23,11 +27,12 @@ macro_rules! predict_endpoint {
 
             #[derive(Debug, utoipa::OpenApi)]
             #[openapi(
-                paths(predict, base::health, base::get_model_metadata, openapi),
+                paths(predict, base::health, base::get_model_metadata, openapi $(, $handler)*),
                 components(schemas(
                     PredictInput,
                     PredictOutput,
                     crate::common::GetModelMetadataResponse,
+                    $(crate::common::$request, crate::common::$response,)*
                 ))
             )]
             pub struct ApiDoc;
@@ -58,6 +63,26 @@ macro_rules! predict_endpoint {
                 super::base::predict(State(state), Json(req)).await
             }
 
+            $(
+                #[utoipa::path(
+                                                    post,
+                                                    path = $path,
+                                                    request_body = crate::common::$request,
+                                                    responses(
+                                                        (status = 200, response = crate::common::$response)
+                                                    ),
+                                                )]
+                pub async fn $handler(
+                    State(state): State<AppState<ModelType>>,
+                    Json(req): Json<crate::common::$request>,
+                ) -> impl IntoResponse {
+                    state
+                        .$handler(req)
+                        .map(Json)
+                        .map_err(|e| e.to_axum_status())
+                }
+            )*
+
             impl super::HttpRouter for AppState<ModelType> {
                 fn http_router(self) -> axum::Router {
                     axum::Router::new()
@@ -67,6 +92,7 @@ macro_rules! predict_endpoint {
                             axum::routing::get(base::get_model_metadata::<AppState<ModelType>>),
                         )
                         .route("/predict", axum::routing::post(predict))
+                        $(.route($path, axum::routing::post($handler)))*
                         .route("/openapi.json", axum::routing::get(openapi))
                         .with_state(self)
                 }
@@ -75,7 +101,38 @@ macro_rules! predict_endpoint {
     };
 }
 
-predict_endpoint!(embedding, Embedding);
-predict_endpoint!(sequence_classification, SequenceClassification);
-predict_endpoint!(token_classification, TokenClassification);
-predict_endpoint!(sentence_embedding, SentenceEmbedding);
+predict_endpoint!(embedding, Embedding, {
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
+predict_endpoint!(sequence_classification, SequenceClassification, {
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
+predict_endpoint!(token_classification, TokenClassification, {
+    "/redact" => redact(RedactRequest, RedactResponse),
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
+predict_endpoint!(sentence_embedding, SentenceEmbedding, {
+    "/similarity" => similarity(SimilarityRequest, SimilarityResponse),
+    "/dedupe" => dedupe(DedupeRequest, DedupeResponse),
+    "/search" => search(SearchRequest, SearchResponse),
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
+predict_endpoint!(sparse_embedding, SparseEmbedding, {
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
+predict_endpoint!(late_interaction, LateInteraction, {
+    "/score" => score(LateInteractionScoreRequest, LateInteractionScoreResponse),
+    "/tokenize" => tokenize(TokenizeRequest, TokenizeResponse),
+    "/detokenize" => detokenize(DetokenizeRequest, DetokenizeResponse),
+    "/count_tokens" => count_tokens(CountTokensRequest, CountTokensResponse),
+});
@@ -1,6 +1,10 @@
-use crate::common::model_type::ImageClassification;
-use crate::common::{ImageClassificationRequest, ImageClassificationResponse, ImageInfo};
-use crate::runtime::AppState;
+use crate::common::model_type::ModelTypeSpec;
+use crate::common::{
+    AudioClassificationRequest, AudioEmbeddingRequest, AudioInfo, ImageClassificationRequest,
+    ImageEmbeddingRequest, ImageInfo, ImageSegmentationRequest, MultimodalImageEmbeddingRequest,
+    MultimodalSimilarityRequest, ObjectDetectionRequest,
+};
+use crate::runtime::{AppState, InputType, TaskType};
 use crate::services::Inference;
 use axum::{
     Json,
@@ -10,7 +14,6 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use utoipa::OpenApi;
 
 pub const MULTIPART_PREDICT_ENDPOINT: &str = "/predict/multipart";
 pub const MULTIPART_OPENAPI_ENDPOINT: &str = "/predict/multipart/openapi.json";
@@ -61,143 +64,171 @@ pub trait FromMultipart: Sized {
     /// Construct an instance from a JSON payload and list of attachment bytes.
     fn from_multipart(
         payload: serde_json::Value,
-        attachments: Vec<(Option<String>, Option<String>, bytes::Bytes)>,
+        attachments: Vec<Attachment>,
     ) -> Result<Self, MultipartApiError>;
 }
 
+/// A multipart attachment as `(file_name, content_type, bytes)`.
+pub type Attachment = (Option<String>, Option<String>, bytes::Bytes);
+
+fn images_from_attachments(
+    attachments: Vec<Attachment>,
+) -> Result<Vec<ImageInfo>, MultipartApiError> {
+    attachments
+        .into_iter()
+        .map(|(_file_name, _content_type, image_bytes)| {
+            let format = image::guess_format(&image_bytes).map_err(|e| {
+                MultipartApiError::RequestConstruction(format!(
+                    "Failed to detect image format: {}",
+                    e
+                ))
+            })?;
+            Ok(ImageInfo {
+                image_bytes,
+                image_format: format,
+            })
+        })
+        .collect()
+}
+
+fn audios_from_attachments(attachments: Vec<Attachment>) -> Vec<AudioInfo> {
+    attachments
+        .into_iter()
+        .map(|(_file_name, _content_type, audio_bytes)| AudioInfo { audio_bytes })
+        .collect()
+}
+
+fn metadata_from_payload(payload: serde_json::Value) -> Option<HashMap<String, String>> {
+    if payload.is_null() || payload == serde_json::json!({}) {
+        Some(HashMap::default())
+    } else {
+        serde_json::from_value(payload)
+            .ok()
+            .or(Some(HashMap::default()))
+    }
+}
+
 impl FromMultipart for ImageClassificationRequest {
     fn from_multipart(
         payload: serde_json::Value,
-        attachments: Vec<(Option<String>, Option<String>, bytes::Bytes)>,
+        attachments: Vec<Attachment>,
     ) -> Result<Self, MultipartApiError> {
-        let images = attachments
-            .into_iter()
-            .map(|(_file_name, _content_type, image_bytes)| {
-                let format = image::guess_format(&image_bytes).map_err(|e| {
-                    MultipartApiError::RequestConstruction(format!(
-                        "Failed to detect image format: {}",
-                        e
-                    ))
-                })?;
-                Ok(ImageInfo {
-                    image_bytes,
-                    image_format: format,
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let metadata = if payload.is_null() || payload == serde_json::json!({}) {
-            Some(HashMap::default())
-        } else {
-            serde_json::from_value(payload)
-                .ok()
-                .or(Some(HashMap::default()))
-        };
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            metadata: metadata_from_payload(payload),
+        })
+    }
+}
 
-        Ok(Self { images, metadata })
+impl FromMultipart for ImageEmbeddingRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            metadata: metadata_from_payload(payload),
+        })
     }
 }
 
-#[derive(Debug, utoipa::OpenApi)]
-#[openapi(
-    paths(post_multipart),
-    components(schemas(MultipartPredictBody, ImageClassificationResponse, ParsedAttachment))
-)]
-pub struct MultipartApiDoc;
-
-#[utoipa::path(
-    get,
-    path = MULTIPART_OPENAPI_ENDPOINT,
-    responses(
-        (status = 200, description = "Successful")
-    )
-)]
-pub async fn openapi() -> impl IntoResponse {
-    Json(MultipartApiDoc::openapi())
+impl FromMultipart for ObjectDetectionRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            metadata: metadata_from_payload(payload),
+        })
+    }
 }
 
-#[utoipa::path(
-    post,
-    path = MULTIPART_PREDICT_ENDPOINT,
-    request_body(
-        content = MultipartPredictBody,
-        content_type = "multipart/form-data",
-        description = "Multipart payload with a JSON part named 'payload' and 0..N binary parts named 'files'"
-    ),
-    responses(
-        (status = 200, body = ImageClassificationResponse),
-        (status = 422, description = "Missing or invalid payload JSON"),
-        (status = 400, description = "Invalid multipart body")
-    )
-)]
-pub async fn post_multipart(
-    state: State<AppState<ImageClassification>>,
-    mut multipart: Multipart,
-) -> Result<Json<ImageClassificationResponse>, MultipartApiError> {
-    parse_multipart(state, &mut multipart).await
+impl FromMultipart for ImageSegmentationRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            metadata: metadata_from_payload(payload),
+        })
+    }
 }
 
-/// Generic multipart parser that extracts payload and attachments.
-pub async fn parse_multipart(
-    State(state): State<AppState<ImageClassification>>,
-    multipart: &mut Multipart,
-) -> Result<Json<ImageClassificationResponse>, MultipartApiError> {
-    let mut payload: Option<serde_json::Value> = None;
-    let mut attachments = Vec::new();
-    let mut attachment_metadata = Vec::new();
+impl FromMultipart for AudioClassificationRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        let threshold = payload
+            .get("threshold")
+            .and_then(|t| t.as_f64())
+            .map(|t| t as f32);
 
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|e| MultipartApiError::Multipart(e.to_string()))?
-    {
-        let name = field.name().map(ToOwned::to_owned);
-        let file_name = field.file_name().map(ToOwned::to_owned);
-        let content_type = field.content_type().map(ToOwned::to_owned);
-        let bytes = field
-            .bytes()
-            .await
-            .map_err(|e| MultipartApiError::Multipart(e.to_string()))?;
+        Ok(Self {
+            audios: audios_from_attachments(attachments),
+            threshold,
+            metadata: metadata_from_payload(payload),
+        })
+    }
+}
 
-        match name.as_deref() {
-            Some("payload") => {
-                payload = Some(
-                    serde_json::from_slice(&bytes)
-                        .map_err(|_| MultipartApiError::InvalidPayload)?,
-                );
-            }
-            Some("files") => {
-                attachment_metadata.push(ParsedAttachment {
-                    file_name: file_name.clone(),
-                    content_type: content_type.clone(),
-                    size_bytes: bytes.len(),
-                });
-                attachments.push((file_name, content_type, bytes));
-            }
-            _ => {}
-        }
+impl FromMultipart for AudioEmbeddingRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        Ok(Self {
+            audios: audios_from_attachments(attachments),
+            metadata: metadata_from_payload(payload),
+        })
     }
+}
 
-    let payload = payload.ok_or(MultipartApiError::MissingPayload)?;
+impl FromMultipart for MultimodalImageEmbeddingRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            metadata: metadata_from_payload(payload),
+        })
+    }
+}
 
-    // Convert to typed request
-    let request = ImageClassificationRequest::from_multipart(payload.clone(), attachments)?;
-    let result = state
-        .inference(request)
-        .map(Json)
-        .map_err(|e| MultipartApiError::RequestConstruction(format!("Inference error: {}", e)))?;
+impl FromMultipart for MultimodalSimilarityRequest {
+    fn from_multipart(
+        payload: serde_json::Value,
+        attachments: Vec<Attachment>,
+    ) -> Result<Self, MultipartApiError> {
+        let labels = payload
+            .get("labels")
+            .cloned()
+            .map(serde_json::from_value::<Vec<String>>)
+            .transpose()
+            .map_err(|_| {
+                MultipartApiError::RequestConstruction(
+                    "'labels' must be an array of strings".to_string(),
+                )
+            })?
+            .unwrap_or_default();
 
-    Ok(result)
+        Ok(Self {
+            images: images_from_attachments(attachments)?,
+            labels,
+            metadata: metadata_from_payload(payload),
+        })
+    }
 }
 
-/// Generic handler that converts multipart request into typed request.
-pub async fn post_multipart_typed<R: FromMultipart>(
-    State(state): State<AppState<ImageClassification>>,
-    mut multipart: Multipart,
-) -> Result<Json<ImageClassificationResponse>, MultipartApiError> {
+/// Generic multipart parser that extracts payload and attachments.
+pub async fn parse_multipart(
+    multipart: &mut Multipart,
+) -> Result<(serde_json::Value, Vec<Attachment>), MultipartApiError> {
     let mut payload: Option<serde_json::Value> = None;
     let mut attachments = Vec::new();
-    let mut attachment_metadata = Vec::new();
 
     while let Some(field) = multipart
         .next_field()
@@ -220,11 +251,6 @@ pub async fn post_multipart_typed<R: FromMultipart>(
                 );
             }
             Some("files") => {
-                attachment_metadata.push(ParsedAttachment {
-                    file_name: file_name.clone(),
-                    content_type: content_type.clone(),
-                    size_bytes: bytes.len(),
-                });
                 attachments.push((file_name, content_type, bytes));
             }
             _ => {}
@@ -233,8 +259,24 @@ pub async fn post_multipart_typed<R: FromMultipart>(
 
     let payload = payload.ok_or(MultipartApiError::MissingPayload)?;
 
+    Ok((payload, attachments))
+}
+
+/// Generic handler that converts multipart request into typed request.
+pub async fn post_multipart_typed<T>(
+    State(state): State<AppState<T>>,
+    mut multipart: Multipart,
+) -> Result<Json<<AppState<T> as Inference>::Output>, MultipartApiError>
+where
+    T: ModelTypeSpec + InputType + TaskType,
+    AppState<T>: Inference,
+    <AppState<T> as Inference>::Input: FromMultipart,
+{
+    let (payload, attachments) = parse_multipart(&mut multipart).await?;
+
     // Convert to typed request
-    let request = ImageClassificationRequest::from_multipart(payload.clone(), attachments)?;
+    let request =
+        <<AppState<T> as Inference>::Input as FromMultipart>::from_multipart(payload, attachments)?;
     let result = state
         .inference(request)
         .map(Json)
@@ -243,73 +285,218 @@ pub async fn post_multipart_typed<R: FromMultipart>(
     Ok(result)
 }
 
-/// HttpRouter implementation for ImageClassification model type.
+/// HttpRouter for image and audio model types.
 /// Combines standard model serving endpoints with multipart file upload capability.
-impl super::HttpRouter for crate::runtime::AppState<ImageClassification> {
-    fn http_router(self) -> axum::Router {
-        axum::Router::new()
-            .route("/health", axum::routing::get(super::base::health))
-            .route(
-                "/model",
-                axum::routing::get(super::base::get_model_metadata::<Self>),
-            )
-            .route("/predict", axum::routing::post(predict_handler))
-            .route("/openapi.json", axum::routing::get(standard_openapi))
-            .route(
-                MULTIPART_PREDICT_ENDPOINT,
-                axum::routing::post(post_multipart_image_classification),
-            )
-            .route(MULTIPART_OPENAPI_ENDPOINT, axum::routing::get(openapi))
-            .with_state(self)
-    }
-}
+macro_rules! multipart_router {
+    ($mod_name:ident, $model_type:ident) => {
+        multipart_router!($mod_name, $model_type, { json {}, multipart {} });
+    };
+    // additional JSON and multipart POST endpoints, each backed by a method of the same name on
+    // the model state
+    (
+        $mod_name:ident,
+        $model_type:ident,
+        {
+            json { $($json_path:literal => $json_handler:ident($json_request:ident, $json_response:ident)),* $(,)? },
+            multipart { $($path:literal => $handler:ident($request:ident, $response:ident)),* $(,)? } $(,)?
+        }
+    ) => {
+        mod $mod_name {
+            use super::{
+                MULTIPART_OPENAPI_ENDPOINT, MULTIPART_PREDICT_ENDPOINT, MultipartApiError,
+                MultipartPredictBody, ParsedAttachment,
+            };
+            use crate::{runtime::AppState, services::Inference};
+            use axum::{
+                Json,
+                extract::{Multipart, State},
+                response::IntoResponse,
+            };
+            use utoipa::OpenApi;
 
-/// Multipart handler specialized for ImageClassificationRequest.
-async fn post_multipart_image_classification(
-    state: State<AppState<ImageClassification>>,
-    multipart: Multipart,
-) -> Result<Json<ImageClassificationResponse>, MultipartApiError> {
-    post_multipart_typed::<crate::common::ImageClassificationRequest>(state, multipart).await
-}
+            type ModelType = crate::common::model_type::$model_type;
+            type PredictInput = <AppState<ModelType> as Inference>::Input;
+            type PredictOutput = <AppState<ModelType> as Inference>::Output;
 
-/// Standard predict endpoint for ImageClassification.
-async fn predict_handler(
-    State(state): State<AppState<ImageClassification>>,
-    Json(req): Json<<AppState<ImageClassification> as crate::services::Inference>::Input>,
-) -> impl IntoResponse {
-    super::base::predict(State(state), Json(req)).await
-}
+            #[derive(Debug, utoipa::OpenApi)]
+            #[openapi(
+                paths(post_multipart $(, $handler)*),
+                components(schemas(
+                    MultipartPredictBody,
+                    PredictOutput,
+                    ParsedAttachment,
+                    $(crate::common::$response,)*
+                ))
+            )]
+            pub struct MultipartApiDoc;
 
-/// Standard OpenAPI endpoint for ImageClassification model service (without multipart).
-async fn standard_openapi() -> impl IntoResponse {
-    Json(serde_json::json!({
-        "openapi": "3.0.0",
-        "info": {
-            "title": "ImageClassification Model API",
-            "version": "1.0.0"
-        },
-        "paths": {
-            "/health": {
-                "get": {
-                    "responses": {
-                        "200": { "description": "Successful" }
-                    }
+            #[utoipa::path(
+                get,
+                path = MULTIPART_OPENAPI_ENDPOINT,
+                responses(
+                    (status = 200, description = "Successful")
+                )
+            )]
+            pub async fn openapi() -> impl IntoResponse {
+                Json(MultipartApiDoc::openapi())
+            }
+
+            #[utoipa::path(
+                post,
+                path = MULTIPART_PREDICT_ENDPOINT,
+                request_body(
+                    content = MultipartPredictBody,
+                    content_type = "multipart/form-data",
+                    description = "Multipart payload with a JSON part named 'payload' and 0..N binary parts named 'files'"
+                ),
+                responses(
+                    (status = 200, response = PredictOutput),
+                    (status = 422, description = "Missing or invalid payload JSON"),
+                    (status = 400, description = "Invalid multipart body")
+                )
+            )]
+            pub async fn post_multipart(
+                state: State<AppState<ModelType>>,
+                multipart: Multipart,
+            ) -> Result<Json<PredictOutput>, MultipartApiError> {
+                super::post_multipart_typed::<ModelType>(state, multipart).await
+            }
+
+            $(
+                #[utoipa::path(
+                    post,
+                    path = $path,
+                    request_body(
+                        content = MultipartPredictBody,
+                        content_type = "multipart/form-data",
+                        description = "Multipart payload with a JSON part named 'payload' and 0..N binary parts named 'files'"
+                    ),
+                    responses(
+                        (status = 200, response = crate::common::$response),
+                        (status = 422, description = "Missing or invalid payload JSON"),
+                        (status = 400, description = "Invalid multipart body")
+                    )
+                )]
+                pub async fn $handler(
+                    State(state): State<AppState<ModelType>>,
+                    mut multipart: Multipart,
+                ) -> Result<Json<crate::common::$response>, MultipartApiError> {
+                    let (payload, attachments) = super::parse_multipart(&mut multipart).await?;
+                    let request = <crate::common::$request as super::FromMultipart>::from_multipart(
+                        payload,
+                        attachments,
+                    )?;
+
+                    state.$handler(request).map(Json).map_err(|e| {
+                        MultipartApiError::RequestConstruction(format!("Inference error: {}", e))
+                    })
                 }
-            },
-            "/model": {
-                "get": {
-                    "responses": {
-                        "200": { "description": "Successful" }
-                    }
+            )*
+
+            $(
+                async fn $json_handler(
+                    State(state): State<AppState<ModelType>>,
+                    Json(req): Json<crate::common::$json_request>,
+                ) -> Result<
+                    Json<crate::common::$json_response>,
+                    (axum::http::StatusCode, std::borrow::Cow<'static, str>),
+                > {
+                    state
+                        .$json_handler(req)
+                        .map(Json)
+                        .map_err(|e| e.to_axum_status())
                 }
-            },
-            "/predict": {
-                "post": {
-                    "responses": {
-                        "200": { "description": "Successful" }
+            )*
+
+            /// Standard predict endpoint.
+            async fn predict_handler(
+                State(state): State<AppState<ModelType>>,
+                Json(req): Json<PredictInput>,
+            ) -> impl IntoResponse {
+                crate::transport::http::base::predict(State(state), Json(req)).await
+            }
+
+            /// Standard OpenAPI endpoint for the model service (without multipart).
+            async fn standard_openapi() -> impl IntoResponse {
+                Json(serde_json::json!({
+                    "openapi": "3.0.0",
+                    "info": {
+                        "title": concat!(stringify!($model_type), " Model API"),
+                        "version": "1.0.0"
+                    },
+                    "paths": {
+                        "/health": {
+                            "get": {
+                                "responses": {
+                                    "200": { "description": "Successful" }
+                                }
+                            }
+                        },
+                        "/model": {
+                            "get": {
+                                "responses": {
+                                    "200": { "description": "Successful" }
+                                }
+                            }
+                        },
+                        "/predict": {
+                            "post": {
+                                "responses": {
+                                    "200": { "description": "Successful" }
+                                }
+                            }
+                        }
+                        $(,
+                        $json_path: {
+                            "post": {
+                                "responses": {
+                                    "200": { "description": "Successful" }
+                                }
+                            }
+                        }
+                        )*
                     }
+                }))
+            }
+
+            impl crate::transport::http::HttpRouter for AppState<ModelType> {
+                fn http_router(self) -> axum::Router {
+                    axum::Router::new()
+                        .route(
+                            "/health",
+                            axum::routing::get(crate::transport::http::base::health),
+                        )
+                        .route(
+                            "/model",
+                            axum::routing::get(
+                                crate::transport::http::base::get_model_metadata::<Self>,
+                            ),
+                        )
+                        .route("/predict", axum::routing::post(predict_handler))
+                        .route("/openapi.json", axum::routing::get(standard_openapi))
+                        .route(MULTIPART_PREDICT_ENDPOINT, axum::routing::post(post_multipart))
+                        .route(MULTIPART_OPENAPI_ENDPOINT, axum::routing::get(openapi))
+                        $(.route($json_path, axum::routing::post($json_handler)))*
+                        $(.route($path, axum::routing::post($handler)))*
+                        .with_state(self)
                 }
             }
         }
-    }))
+    };
 }
+
+multipart_router!(image_classification, ImageClassification);
+multipart_router!(image_embedding, ImageEmbedding);
+multipart_router!(object_detection, ObjectDetection);
+multipart_router!(image_segmentation, ImageSegmentation);
+multipart_router!(audio_classification, AudioClassification);
+multipart_router!(audio_embedding, AudioEmbedding);
+multipart_router!(multimodal_embedding, MultimodalEmbedding, {
+    json {
+        "/embed/text" => embed_text(MultimodalTextEmbeddingRequest, MultimodalEmbeddingResponse),
+    },
+    multipart {
+        "/embed/image" => embed_image(MultimodalImageEmbeddingRequest, MultimodalEmbeddingResponse),
+        "/similarity" => similarity(MultimodalSimilarityRequest, MultimodalSimilarityResponse),
+    },
+});
@@ -25,6 +25,11 @@ impl From<ApiError> for McpError {
                 message: std::borrow::Cow::Owned(str),
                 data: None,
             },
+            ApiError::InputTooLong { .. } => McpError {
+                code: ErrorCode::INVALID_REQUEST,
+                message: std::borrow::Cow::Owned(api_error.to_string()),
+                data: None,
+            },
         }
     }
 }
@@ -1,7 +1,12 @@
+use std::{collections::HashMap, sync::Arc};
+
 use rmcp::ServerHandler;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::model::JsonObject;
 use rmcp::transport::streamable_http_server::{
     StreamableHttpService, session::local::LocalSessionManager,
 };
+use serde_json::Value;
 
 use crate::common::model_type;
 use crate::runtime::AppState;
@@ -30,6 +35,74 @@ where
     }
 }
 
+/// Restricts the `prompt_name` option in the input schema of each tool to the model's prompts.
+fn restrict_prompt_names<S>(
+    tool_router: &mut ToolRouter<S>,
+    prompts: Option<HashMap<String, String>>,
+) {
+    let Some(prompts) = prompts else {
+        return;
+    };
+
+    let mut names: Vec<String> = prompts.into_keys().collect();
+    names.sort();
+
+    for route in tool_router.map.values_mut() {
+        let mut schema = route.attr.input_schema.as_ref().clone();
+
+        if restrict_schema_prompt_names(&mut schema, &names) {
+            route.attr.input_schema = Arc::new(schema);
+        }
+    }
+}
+
+fn restrict_schema_prompt_names(schema: &mut JsonObject, names: &[String]) -> bool {
+    // draft 2020-12 schemas keep subschemas in `$defs`, draft 7 ones in `definitions`
+    let defs = match schema.contains_key("$defs") {
+        true => "$defs",
+        false => "definitions",
+    };
+
+    let prompt_name = schema
+        .get_mut(defs)
+        .and_then(|d| d.pointer_mut("/InferenceOptions/properties/prompt_name"))
+        .and_then(Value::as_object_mut);
+
+    match prompt_name {
+        Some(prompt_name) => {
+            let mut values: Vec<Value> = names.iter().map(|n| Value::from(n.as_str())).collect();
+            values.push(Value::Null);
+            prompt_name.insert("enum".to_string(), Value::Array(values));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Server instructions of a tool, followed by the label descriptions set at build time, in
+/// label id order, so that agents know what each label means.
+fn instructions(
+    description: &str,
+    id2label: Option<HashMap<u32, String>>,
+    label_descriptions: Option<HashMap<String, String>>,
+) -> String {
+    let Some(label_descriptions) = label_descriptions else {
+        return description.to_string();
+    };
+
+    let mut labels: Vec<(u32, String)> = id2label.unwrap_or_default().into_iter().collect();
+    labels.sort();
+
+    let mut instructions = format!("{description}\n\nLabels:");
+    for (_, label) in labels {
+        if let Some(label_description) = label_descriptions.get(&label) {
+            instructions.push_str(&format!("\n- {label}: {label_description}"));
+        }
+    }
+
+    instructions
+}
+
 pub struct DummyTool {}
 
 impl ServerHandler for DummyTool {
@@ -65,10 +138,75 @@ impl McpRouter for AppState<model_type::ImageClassification> {
     }
 }
 
+impl McpRouter for AppState<model_type::ImageEmbedding> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for ImageEmbedding model type",
+        ))
+    }
+}
+
+impl McpRouter for AppState<model_type::ObjectDetection> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for ObjectDetection model type",
+        ))
+    }
+}
+
+impl McpRouter for AppState<model_type::ImageSegmentation> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for ImageSegmentation model type",
+        ))
+    }
+}
+
+impl McpRouter for AppState<model_type::AudioClassification> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for AudioClassification model type",
+        ))
+    }
+}
+
+impl McpRouter for AppState<model_type::AudioEmbedding> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for AudioEmbedding model type",
+        ))
+    }
+}
+
+impl McpRouter for AppState<model_type::MultimodalEmbedding> {
+    type Tool = DummyTool;
+    const NEW_TOOL: fn(Self) -> Self::Tool = |_state| Self::Tool {};
+    fn mcp_router(self) -> Result<axum::Router, crate::error::ApiError> {
+        Err(crate::error::ApiError::InternalError(
+            "MCP not implemented for MultimodalEmbedding model type",
+        ))
+    }
+}
+
 macro_rules! generate_mcp {
     ($model_type:ident, $tool_name:ident, $fn_name:ident, $request_body:ident, $return_model:ident, $short_desc:literal, $long_desc:literal) => {
+        generate_mcp!($model_type, $tool_name, $fn_name, $request_body, $return_model, $short_desc, $long_desc, {});
+    };
+    // additional tools, each backed by a method on the model state, and only registered if
+    // the optional `if` method of the state returns true
+    ($model_type:ident, $tool_name:ident, $fn_name:ident, $request_body:ident, $return_model:ident, $short_desc:literal, $long_desc:literal, { $($tool:ident => $method:ident($tool_request:ident, $tool_desc:literal) $(if $available:ident)?),* $(,)? }) => {
         mod $fn_name {
-            use crate::services::Inference;
+            use crate::services::{Inference, Metadata};
             use $crate::common::$request_body;
             use $crate::runtime::AppState;
             use $crate::transport::mcp::error::to_mcp_error;
@@ -107,9 +245,18 @@ macro_rules! generate_mcp {
             #[tool_router]
             impl $tool_name {
                 pub fn new(state: AppState<crate::common::model_type::$model_type>) -> Self {
+                    let mut tool_router = Self::tool_router() + Self::tokenizer_tool_router();
+                    super::restrict_prompt_names(&mut tool_router, state.prompts());
+
+                    $($(
+                        if !state.$available() {
+                            tool_router.remove_route(stringify!($tool));
+                        }
+                    )?)*
+
                     Self {
                         state,
-                        tool_router: Self::tool_router(),
+                        tool_router,
                     }
                 }
 
@@ -119,7 +266,42 @@ macro_rules! generate_mcp {
                     let result = CallToolResult::structured(serde_json::to_value(response).map_err(to_mcp_error)?);
                     Ok(result)
                 }
+
+                $(
+                    #[tool(description = $tool_desc)]
+                    fn $tool(&self, Parameters(object): Parameters<$crate::common::$tool_request>) -> Result<CallToolResult, McpError> {
+                        let response = self.state.$method(object)?;
+                        let result = CallToolResult::structured(serde_json::to_value(response).map_err(to_mcp_error)?);
+                        Ok(result)
+                    }
+                )*
             }
+
+            // every model type served over MCP takes text, so each exposes its tokenizer
+            #[tool_router(router = tokenizer_tool_router)]
+            impl $tool_name {
+                #[tool(description = "Splits texts into the model's tokens, with their ids and offsets.")]
+                fn tokenize(&self, Parameters(object): Parameters<$crate::common::TokenizeRequest>) -> Result<CallToolResult, McpError> {
+                    let response = self.state.tokenize(object)?;
+                    let result = CallToolResult::structured(serde_json::to_value(response).map_err(to_mcp_error)?);
+                    Ok(result)
+                }
+
+                #[tool(description = "Decodes lists of token ids back into text.")]
+                fn detokenize(&self, Parameters(object): Parameters<$crate::common::DetokenizeRequest>) -> Result<CallToolResult, McpError> {
+                    let response = self.state.detokenize(object)?;
+                    let result = CallToolResult::structured(serde_json::to_value(response).map_err(to_mcp_error)?);
+                    Ok(result)
+                }
+
+                #[tool(description = "Counts the tokens of texts and whether they exceed the model's max length.")]
+                fn count_tokens(&self, Parameters(object): Parameters<$crate::common::CountTokensRequest>) -> Result<CallToolResult, McpError> {
+                    let response = self.state.count_tokens(object)?;
+                    let result = CallToolResult::structured(serde_json::to_value(response).map_err(to_mcp_error)?);
+                    Ok(result)
+                }
+            }
+
             #[tool_handler]
             impl ServerHandler for $tool_name {
                 fn get_info(&self) -> ServerInfo {
@@ -129,7 +311,11 @@ macro_rules! generate_mcp {
                             .enable_tools()
                             .build(),
                         server_info: Implementation::from_build_env(),
-                        instructions: Some($long_desc.to_string()),
+                        instructions: Some(super::instructions(
+                            $long_desc,
+                            self.state.id2label(),
+                            self.state.label_descriptions(),
+                        )),
                     }
                 }
 
@@ -177,7 +363,13 @@ generate_mcp!(
     TokenClassificationRequest,
     TokenClassificationResponse,
     "Performs token classification of input text sequences.",
-    "This tool will classify each token of an input text sequence."
+    "This tool will classify each token of an input text sequence.",
+    {
+        redact_text => redact(
+            RedactRequest,
+            "Replaces the entities detected in texts, such as personal data, with placeholders."
+        ),
+    }
 );
 
 generate_mcp!(
@@ -187,7 +379,41 @@ generate_mcp!(
     SentenceEmbeddingRequest,
     SentenceEmbeddingResponse,
     "Performs sentence embedding of input text sequences.",
-    "This tool will embed a sequence of texts."
+    "This tool will embed a sequence of texts, compare texts, find near-duplicate texts and search the model's built-in corpus.",
+    {
+        compare_texts => similarity(
+            SimilarityRequest,
+            "Computes the cosine similarity of source texts against target texts."
+        ),
+        find_duplicates => dedupe(
+            DedupeRequest,
+            "Groups near-duplicate texts whose cosine similarity is above a threshold."
+        ),
+        search_documents => search(
+            SearchRequest,
+            "Finds the documents of the model's built-in corpus most similar to a query."
+        ) if has_corpus,
+    }
+);
+
+generate_mcp!(
+    SparseEmbedding,
+    SparseEmbeddingTool,
+    sparse_embedding,
+    SparseEmbeddingRequest,
+    SparseEmbeddingResponse,
+    "Performs sparse (SPLADE) embedding of input text sequences.",
+    "This tool will embed a sequence of texts into sparse vectors over the model vocabulary."
+);
+
+generate_mcp!(
+    LateInteraction,
+    LateInteractionTool,
+    late_interaction,
+    LateInteractionRequest,
+    LateInteractionResponse,
+    "Performs late interaction (ColBERT) embedding of input text sequences.",
+    "This tool will embed a sequence of texts into matrices of token vectors."
 );
 
 // Doesn't use a json schema, see how we can go around this limitation
@@ -202,3 +428,48 @@ generate_mcp!(
     "This tool will classify input images."
 );
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::EmbeddingRequest;
+
+    #[test]
+    fn test_restrict_schema_prompt_names() {
+        let mut schema: JsonObject = serde_json::to_value(schemars::schema_for!(EmbeddingRequest))
+            .unwrap()
+            .as_object()
+            .cloned()
+            .unwrap();
+
+        let names = vec!["passage".to_string(), "query".to_string()];
+        assert!(restrict_schema_prompt_names(&mut schema, &names));
+
+        let prompt_name = Value::Object(schema)
+            .pointer("/$defs/InferenceOptions/properties/prompt_name/enum")
+            .cloned();
+        assert_eq!(
+            prompt_name,
+            Some(serde_json::json!(["passage", "query", null]))
+        );
+    }
+
+    #[test]
+    fn test_instructions() {
+        assert_eq!(
+            instructions("Classifies text.", None, None),
+            "Classifies text."
+        );
+
+        let id2label = HashMap::from([(0, "spam".to_string()), (1, "ham".to_string())]);
+        let label_descriptions = HashMap::from([
+            ("ham".to_string(), "A legitimate message.".to_string()),
+            ("spam".to_string(), "Unsolicited bulk mail.".to_string()),
+        ]);
+
+        assert_eq!(
+            instructions("Classifies text.", Some(id2label), Some(label_descriptions)),
+            "Classifies text.\n\nLabels:\n- spam: Unsolicited bulk mail.\n- ham: A legitimate message."
+        );
+    }
+}
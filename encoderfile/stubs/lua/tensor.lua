@@ -57,6 +57,11 @@ function Tensor:softmax(axis) end
 ---@return Tensor
 function Tensor:transpose() end
 
+---Computes the matrix product of two 2D tensors.
+---@param other Tensor Tensor of shape [k, m], for a tensor of shape [n, k]
+---@return Tensor Tensor of shape [n, m]
+function Tensor:matmul(other) end
+
 ---Normalizes values along an axis using the Lp norm.
 ---Each slice is divided by its Lp norm so that its magnitude becomes 1.
 ---@param lp number Norm order (e.g., 1 or 2)
@@ -105,6 +110,12 @@ function Tensor:fold_axis(axis, func) end
 ---@return Tensor
 function Tensor:mean_pool(mask) end
 
+---SPLADE pooling: max over the sequence of log(1 + relu(x)), ignoring masked tokens.
+---Expects a tensor of shape [batch_size, seq_len, vocab_size] and a mask of shape [batch_size, seq_len].
+---@param mask Tensor Mask tensor
+---@return Tensor 2-D tensor of shape [batch_size, vocab_size]
+function Tensor:splade_pool(mask) end
+
 ---Elementwise equality comparison.
 ---@param other number|Tensor
 ---@return boolean
@@ -0,0 +1,13 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess audio classification logits
+---Must return 2D tensor of shape [batch_size, n_labels]
+---Single-label models: return probabilities, e.g. arr:softmax(2)
+---Multi-label models: return logits, a sigmoid is applied afterwards
+---@input Tensor 2D tensor of shape [batch_size, n_labels]
+---@return Tensor
+function Postprocess(arr)
+    ---Replace with your logic here
+    return arr
+end
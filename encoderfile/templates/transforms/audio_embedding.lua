@@ -0,0 +1,12 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess audio embeddings
+---Runs after pooling and before L2 normalization
+---Must return 2D tensor of shape [batch_size, *]
+---@input Tensor 2D tensor of shape [batch_size, hidden_dim]
+---@return Tensor
+function Postprocess(arr)
+    ---Replace with your logic here
+    return arr
+end
@@ -0,0 +1,12 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess image segmentation logits
+---Runs before upsampling and the per-pixel argmax
+---Must return 4D tensor with the same shape as the input
+---@input Tensor 4D tensor of shape [batch_size, num_labels, height, width]
+---@return Tensor
+function Postprocess(arr)
+    ---Replace with your logic here
+    return arr
+end
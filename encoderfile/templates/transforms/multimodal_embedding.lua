@@ -0,0 +1,13 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess text or image embeddings
+---Applied to both towers; both must return the same embedding dim
+---Runs before L2 normalization
+---Must return 2D tensor of shape [batch_size, *]
+---@input Tensor 2D tensor of shape [batch_size, embed_dim]
+---@return Tensor
+function Postprocess(arr)
+    ---Replace with your logic here
+    return arr
+end
@@ -0,0 +1,12 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess object detection logits into class scores
+---Runs before thresholding and non-maximum suppression
+---Must return 3D tensor with the same shape as the input
+---@input Tensor 3D tensor of shape [batch_size, num_queries, num_classes]
+---@return Tensor
+function Postprocess(arr)
+    ---Replace with your logic here
+    return arr:softmax(3)
+end
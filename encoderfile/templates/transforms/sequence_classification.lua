@@ -3,6 +3,9 @@
 
 ---Postprocess sequence classification logits
 ---Must return 2D tensor of shape [batch_size, n_labels]
+---Single-label models: return probabilities, e.g. arr:softmax(2)
+---Multi-label models: return logits, a sigmoid is applied afterwards
+---Regression models: return the scores as-is
 ---@input Tensor 2D tensor of shape [batch_size, n_labels]
 ---@return Tensor
 function Postprocess(arr)
@@ -0,0 +1,12 @@
+---Generated by Encoderfile ❤️
+---Remember: Lua is 1-indexed!
+
+---Postprocess MLM logits into sparse vectors
+---Must return 2D tensor of shape [batch_size, vocab_size] with non-negative weights
+---@input Tensor 3D tensor of shape [batch_size, seq_len, vocab_size]
+---@input mask Attention mask of shape [batch_size, seq_len]
+---@return Tensor
+function Postprocess(arr, mask)
+    ---Replace with your logic here
+    return arr:splade_pool(mask)
+end
@@ -12,8 +12,6 @@ use tempfile::tempdir;
 
 use encoderfile::common;
 
-tonic::include_proto!("encoderfile.metadata");
-
 use encoderfile::generated::token_classification;
 
 #[cfg(target_os = "windows")]
@@ -210,7 +208,11 @@ async fn send_http_inference(sample_text: &str, http_port: String) -> Result<()>
     let client = reqwest::Client::new();
     let req = common::TokenClassificationRequest {
         inputs: vec![sample_text.to_owned()],
+        words: None,
+        aggregation_strategy: None,
+        ignore_labels: None,
         metadata: None,
+        options: Default::default(),
     };
     let res = client
         .post(format!("http://localhost:{http_port}/predict"))
@@ -229,7 +231,11 @@ async fn send_grpc_inference(sample_text: &str, grpc_port: String) -> Result<()>
     let mut client = token_classification::token_classification_inference_client::TokenClassificationInferenceClient::connect(format!("http://[::1]:{grpc_port}/predict")).await?;
     let req = token_classification::TokenClassificationRequest {
         inputs: vec![sample_text.to_owned()],
+        words: vec![],
         metadata: std::collections::HashMap::new(),
+        aggregation_strategy: 0,
+        ignore_labels: vec![],
+        options: None,
     };
     client.predict(req).await?;
     Ok(())
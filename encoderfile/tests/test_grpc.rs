@@ -6,17 +6,22 @@ use encoderfile::{
     dev_utils::*,
     generated::{
         embedding::{
-            EmbeddingRequest, EmbeddingResponse, embedding_inference_server::EmbeddingInference,
+            CountTokensRequest, DetokenizeRequest, EmbeddingRequest, EmbeddingResponse,
+            TokenizeRequest, embedding_inference_server::EmbeddingInference,
         },
         image_classification::{
             ImageClassificationRequest, ImageClassificationResponse,
             image_classification_inference_server::ImageClassificationInference,
         },
         image_types::ImageInput,
+        late_interaction::{
+            LateInteractionRequest, LateInteractionResponse, LateInteractionScoreRequest,
+            late_interaction_inference_server::LateInteractionInference,
+        },
         metadata::{GetModelMetadataRequest, GetModelMetadataResponse},
         sentence_embedding::{
-            SentenceEmbeddingRequest, SentenceEmbeddingResponse,
-            sentence_embedding_inference_server::SentenceEmbeddingInference,
+            DedupeRequest, SearchRequest, SentenceEmbeddingRequest, SentenceEmbeddingResponse,
+            SimilarityRequest, sentence_embedding_inference_server::SentenceEmbeddingInference,
         },
         sequence_classification::{
             SequenceClassificationRequest, SequenceClassificationResponse,
@@ -26,6 +31,7 @@ use encoderfile::{
             TokenClassificationRequest, TokenClassificationResponse,
             token_classification_inference_server::TokenClassificationInference,
         },
+        tokenize::TokenIds,
     },
     transport::grpc::GrpcService,
 };
@@ -109,6 +115,7 @@ test_grpc_service!(
     EmbeddingRequest {
         inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
         metadata: HashMap::new(),
+        options: Default::default(),
     },
     EmbeddingResponse
 );
@@ -120,6 +127,8 @@ test_grpc_service!(
     SequenceClassificationRequest {
         inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
         metadata: HashMap::new(),
+        threshold: None,
+        options: Default::default(),
     },
     SequenceClassificationResponse
 );
@@ -131,6 +140,10 @@ test_grpc_service!(
     TokenClassificationRequest {
         inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
         metadata: HashMap::new(),
+        aggregation_strategy: 0,
+        ignore_labels: vec![],
+        options: Default::default(),
+        words: vec![],
     },
     TokenClassificationResponse
 );
@@ -142,10 +155,134 @@ test_grpc_service!(
     SentenceEmbeddingRequest {
         inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
         metadata: HashMap::new(),
+        options: Default::default(),
     },
     SentenceEmbeddingResponse
 );
 
+test_grpc_service!(
+    late_interaction_tests,
+    { GrpcService::new(late_interaction_state()) },
+    false,
+    LateInteractionRequest {
+        inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
+        metadata: HashMap::new(),
+        is_query: false,
+        options: Default::default(),
+    },
+    LateInteractionResponse
+);
+
+#[tokio::test]
+async fn test_late_interaction_score() {
+    let service = GrpcService::new(late_interaction_state());
+    let request = tonic::Request::new(LateInteractionScoreRequest {
+        query: "hello world".to_string(),
+        documents: vec!["hello world".to_string(), "the quick brown fox".to_string()],
+        metadata: HashMap::new(),
+        options: Default::default(),
+    });
+
+    let response = service.score(request).await.unwrap().into_inner();
+
+    assert_eq!(response.scores.len(), 2);
+}
+
+#[tokio::test]
+async fn test_sentence_embedding_similarity() {
+    let service = GrpcService::new(sentence_embedding_state());
+    let request = tonic::Request::new(SimilarityRequest {
+        sources: vec!["hello world".to_string()],
+        targets: vec!["hello world".to_string(), "the quick brown fox".to_string()],
+        metadata: HashMap::new(),
+        options: None,
+    });
+
+    let response = service.similarity(request).await.unwrap().into_inner();
+
+    assert_eq!(response.rows.len(), 1);
+    assert_eq!(response.rows[0].similarities.len(), 2);
+}
+
+#[tokio::test]
+async fn test_sentence_embedding_dedupe() {
+    let service = GrpcService::new(sentence_embedding_state());
+    let request = tonic::Request::new(DedupeRequest {
+        inputs: vec![
+            "hello world".to_string(),
+            "the quick brown fox".to_string(),
+            "hello world".to_string(),
+        ],
+        threshold: None,
+        metadata: HashMap::new(),
+        options: None,
+    });
+
+    let response = service.dedupe(request).await.unwrap().into_inner();
+
+    assert_eq!(response.assignments, vec![0, 1, 0]);
+    assert_eq!(response.clusters[0].members, vec![0, 2]);
+}
+
+#[tokio::test]
+async fn test_sentence_embedding_search() {
+    let service = GrpcService::new(sentence_embedding_corpus_state());
+    let request = tonic::Request::new(SearchRequest {
+        query: "a cat purring".to_string(),
+        top_k: Some(3),
+        metadata: HashMap::new(),
+        options: None,
+    });
+
+    let response = service.search(request).await.unwrap().into_inner();
+
+    assert_eq!(response.results.len(), 3);
+    assert_eq!(response.results[0].id, "cats");
+}
+
+#[tokio::test]
+async fn test_embedding_tokenize() {
+    let service = GrpcService::new(embedding_state());
+    let request = tonic::Request::new(TokenizeRequest {
+        inputs: vec!["hello world".to_string(), "the quick brown fox".to_string()],
+        truncate: None,
+        metadata: HashMap::new(),
+        offset_mapping: None,
+    });
+
+    let response = service.tokenize(request).await.unwrap().into_inner();
+
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(response.results[0].ids.len(), 4);
+    assert_eq!(response.results[0].offsets.len(), 4);
+
+    let request = tonic::Request::new(DetokenizeRequest {
+        inputs: vec![TokenIds {
+            ids: response.results[0].ids.clone(),
+        }],
+        skip_special_tokens: None,
+        metadata: HashMap::new(),
+    });
+
+    let response = service.detokenize(request).await.unwrap().into_inner();
+
+    assert_eq!(response.results, vec!["hello world".to_string()]);
+}
+
+#[tokio::test]
+async fn test_embedding_count_tokens() {
+    let service = GrpcService::new(embedding_state());
+    let request = tonic::Request::new(CountTokensRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: HashMap::new(),
+    });
+
+    let response = service.count_tokens(request).await.unwrap().into_inner();
+
+    assert_eq!(response.results[0].num_tokens, 4);
+    assert!(!response.results[0].truncated);
+}
+
 const TEST_IMAGE_PATH: &str = "../test-pictures/yoga01.jpg";
 
 fn get_file_bytes(filename: &str) -> Vec<u8> {
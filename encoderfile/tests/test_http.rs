@@ -101,6 +101,7 @@ test_router_mod!(
     EmbeddingRequest {
         inputs: vec!["Test sentence 1".to_string(), "Test sentence 2".to_string()],
         metadata: None,
+        options: Default::default(),
     }
 );
 test_router_mod!(
@@ -109,7 +110,9 @@ test_router_mod!(
     sequence_classification_state,
     SequenceClassificationRequest {
         inputs: vec!["Test sentence 1".to_string(), "Test sentence 2".to_string()],
+        threshold: None,
         metadata: None,
+        options: Default::default(),
     }
 );
 test_router_mod!(
@@ -118,7 +121,11 @@ test_router_mod!(
     token_classification_state,
     TokenClassificationRequest {
         inputs: vec!["Test sentence 1".to_string(), "Test sentence 2".to_string()],
+        words: None,
+        aggregation_strategy: None,
+        ignore_labels: None,
         metadata: None,
+        options: Default::default(),
     }
 );
 test_router_mod!(
@@ -128,9 +135,198 @@ test_router_mod!(
     SentenceEmbeddingRequest {
         inputs: vec!["Test sentence 1".to_string(), "Test sentence 2".to_string()],
         metadata: None,
+        options: Default::default(),
     }
 );
 
+test_router_mod!(
+    LateInteraction,
+    late_interaction_tests,
+    late_interaction_state,
+    LateInteractionRequest {
+        inputs: vec!["Test sentence 1".to_string(), "Test sentence 2".to_string()],
+        is_query: false,
+        metadata: None,
+        options: Default::default(),
+    }
+);
+
+mod late_interaction_score_tests {
+    use axum::http::{Request, StatusCode};
+    use encoderfile::{dev_utils, transport::http::HttpRouter};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_score_route() {
+        let router = dev_utils::late_interaction_state().http_router();
+
+        let body = serde_json::json!({
+            "query": "Test sentence",
+            "documents": ["Test sentence 1", "Test sentence 2"],
+        });
+
+        let request = Request::post("/score")
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let resp = router.oneshot(request).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
+
+mod sentence_embedding_comparison_tests {
+    use axum::http::{Request, StatusCode};
+    use encoderfile::{dev_utils, transport::http::HttpRouter};
+    use tower::ServiceExt;
+
+    async fn post(path: &str, body: serde_json::Value) -> StatusCode {
+        let router = dev_utils::sentence_embedding_state().http_router();
+
+        let request = Request::post(path)
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_similarity_route() {
+        let body = serde_json::json!({
+            "sources": ["Test sentence 1", "Test sentence 2"],
+        });
+
+        assert_eq!(post("/similarity", body).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_route() {
+        let body = serde_json::json!({
+            "inputs": ["Test sentence 1", "Test sentence 2"],
+            "threshold": 0.95,
+        });
+
+        assert_eq!(post("/dedupe", body).await, StatusCode::OK);
+
+        let body = serde_json::json!({
+            "inputs": ["Test sentence 1"],
+            "threshold": 2.0,
+        });
+
+        assert_eq!(
+            post("/dedupe", body).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_route() {
+        let request = Request::post("/search")
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(
+                serde_json::json!({"query": "green tea", "top_k": 1}).to_string(),
+            ))
+            .unwrap();
+
+        let resp = dev_utils::sentence_embedding_corpus_state()
+            .http_router()
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = serde_json::json!({"query": "green tea"});
+
+        assert_eq!(
+            post("/search", body).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
+
+mod redact_tests {
+    use axum::http::{Request, StatusCode};
+    use encoderfile::{dev_utils, transport::http::HttpRouter};
+    use tower::ServiceExt;
+
+    async fn post(body: serde_json::Value) -> StatusCode {
+        let router = dev_utils::token_classification_state().http_router();
+
+        let request = Request::post("/redact")
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_redact_route() {
+        let body = serde_json::json!({
+            "inputs": ["Hugging Face is based in New York City"],
+            "mode": "mask",
+        });
+
+        assert_eq!(post(body).await, StatusCode::OK);
+
+        let body = serde_json::json!({
+            "inputs": ["Hugging Face is based in New York City"],
+            "mode": "mask",
+            "mask_char": "**",
+        });
+
+        assert_eq!(post(body).await, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
+mod tokenize_tests {
+    use axum::http::{Request, StatusCode};
+    use encoderfile::{dev_utils, transport::http::HttpRouter};
+    use tower::ServiceExt;
+
+    async fn post(path: &str, body: serde_json::Value) -> StatusCode {
+        let router = dev_utils::token_classification_state().http_router();
+
+        let request = Request::post(path)
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_tokenize_route() {
+        let body = serde_json::json!({"inputs": ["Test sentence"], "truncate": 4});
+
+        assert_eq!(post("/tokenize", body).await, StatusCode::OK);
+
+        let body = serde_json::json!({"inputs": [""]});
+
+        assert_eq!(
+            post("/tokenize", body).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detokenize_route() {
+        let body = serde_json::json!({"inputs": [[101, 102]]});
+
+        assert_eq!(post("/detokenize", body).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_route() {
+        let body = serde_json::json!({"inputs": ["Test sentence 1", "Test sentence 2"]});
+
+        assert_eq!(post("/count_tokens", body).await, StatusCode::OK);
+    }
+}
+
 mod image_classification_tests {
     use axum::http::{Request, StatusCode};
     use encoderfile::{dev_utils, transport::http::HttpRouter};
@@ -37,6 +37,15 @@ where
 
 macro_rules! test_mcp_server_impl {
     ($mod_name:ident, $state_func:ident, $req_type:ident, $resp_type:ident) => {
+        test_mcp_server_impl!(
+            $mod_name,
+            $state_func,
+            $req_type,
+            $resp_type,
+            ["run_encoder", "tokenize", "detokenize", "count_tokens"]
+        );
+    };
+    ($mod_name:ident, $state_func:ident, $req_type:ident, $resp_type:ident, [$($tool:literal),+]) => {
         pub mod $mod_name {
             use encoderfile::{
                 common::{$req_type, $resp_type},
@@ -98,16 +107,17 @@ macro_rules! test_mcp_server_impl {
                     .expect("list tools failed");
                 tracing::info!("Available tools: {tools:#?}");
 
-                assert_eq!(tools.tools.len(), 1);
-                assert_eq!(tools.tools[0].name, "run_encoder");
+                let mut tool_names: Vec<String> =
+                    tools.tools.iter().map(|tool| tool.name.to_string()).collect();
+                tool_names.sort();
+                let mut expected_tool_names = vec![$($tool.to_string()),+];
+                expected_tool_names.sort();
+                assert_eq!(tool_names, expected_tool_names);
 
-                let test_params = $req_type {
-                    inputs: vec![
-                        "This is a test.".to_string(),
-                        "This is another test.".to_string(),
-                    ],
-                    metadata: None,
-                };
+                let test_params: $req_type = serde_json::from_value(serde_json::json!({
+                    "inputs": ["This is a test.", "This is another test."],
+                }))
+                .expect("failed to build request");
                 let tool_result = client
                     .call_tool(CallToolRequestParam {
                         name: "run_encoder".into(),
@@ -143,14 +153,52 @@ test_mcp_server_impl!(
     test_mcp_sentence_embedding,
     sentence_embedding_state,
     SentenceEmbeddingRequest,
-    SentenceEmbeddingResponse
+    SentenceEmbeddingResponse,
+    [
+        "run_encoder",
+        "compare_texts",
+        "find_duplicates",
+        "tokenize",
+        "detokenize",
+        "count_tokens"
+    ]
+);
+
+test_mcp_server_impl!(
+    test_mcp_sentence_embedding_corpus,
+    sentence_embedding_corpus_state,
+    SentenceEmbeddingRequest,
+    SentenceEmbeddingResponse,
+    [
+        "run_encoder",
+        "compare_texts",
+        "find_duplicates",
+        "search_documents",
+        "tokenize",
+        "detokenize",
+        "count_tokens"
+    ]
+);
+
+test_mcp_server_impl!(
+    test_mcp_late_interaction,
+    late_interaction_state,
+    LateInteractionRequest,
+    LateInteractionResponse
 );
 
 test_mcp_server_impl!(
     test_mcp_token_classification,
     token_classification_state,
     TokenClassificationRequest,
-    TokenClassificationResponse
+    TokenClassificationResponse,
+    [
+        "run_encoder",
+        "redact_text",
+        "tokenize",
+        "detokenize",
+        "count_tokens"
+    ]
 );
 
 test_mcp_server_impl!(
@@ -167,6 +215,10 @@ async fn test_mcp_servers() {
     tracing::info!("Testing embedding");
     self::test_mcp_sentence_embedding::test_mcp_sentence_embedding().await;
     tracing::info!("Testing sentence embedding");
+    self::test_mcp_sentence_embedding_corpus::test_mcp_sentence_embedding_corpus().await;
+    tracing::info!("Testing sentence embedding with a corpus");
+    self::test_mcp_late_interaction::test_mcp_late_interaction().await;
+    tracing::info!("Testing late interaction");
     self::test_mcp_token_classification::test_mcp_token_classification().await;
     tracing::info!("Testing token classification");
     self::test_mcp_sequence_classification::test_mcp_sequence_classification().await;
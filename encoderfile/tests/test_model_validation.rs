@@ -1,15 +1,38 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
-use encoderfile::{builder::model::ModelTypeExt as _, common::model_type::ModelType};
+use encoderfile::{
+    builder::model::ModelTypeExt as _,
+    common::{InputRole, ModelInputs, ModelOutputs, model_type::ModelType},
+};
 
 #[test]
 pub fn test_embedding() {
     let path = PathBuf::from("../models/embedding/model.onnx");
 
-    assert!(ModelType::Embedding.validate_model(&path).is_ok());
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_ok()
+    );
     assert!(
         ModelType::TokenClassification
-            .validate_model(&path)
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_late_interaction() {
+    let path = PathBuf::from("../models/embedding/model.onnx");
+
+    assert!(
+        ModelType::LateInteraction
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_ok()
+    );
+    assert!(
+        ModelType::SequenceClassification
+            .validate_model(&path, &Default::default(), &Default::default())
             .is_err()
     );
 }
@@ -18,18 +41,30 @@ pub fn test_embedding() {
 pub fn test_token_classification() {
     let path = PathBuf::from("../models/token_classification/model.onnx");
 
-    assert!(ModelType::Embedding.validate_model(&path).is_err());
-    assert!(ModelType::TokenClassification.validate_model(&path).is_ok());
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_err()
+    );
+    assert!(
+        ModelType::TokenClassification
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_ok()
+    );
 }
 
 #[test]
 pub fn test_sentence_embedding() {
     let path = PathBuf::from("../models/sentence_embedding/model.onnx");
 
-    assert!(ModelType::SentenceEmbedding.validate_model(&path).is_ok());
+    assert!(
+        ModelType::SentenceEmbedding
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_ok()
+    );
     assert!(
         ModelType::SequenceClassification
-            .validate_model(&path)
+            .validate_model(&path, &Default::default(), &Default::default())
             .is_err()
     );
 }
@@ -38,10 +73,14 @@ pub fn test_sentence_embedding() {
 pub fn test_sequence_classification() {
     let path = PathBuf::from("../models/sequence_classification/model.onnx");
 
-    assert!(ModelType::SentenceEmbedding.validate_model(&path).is_err());
+    assert!(
+        ModelType::SentenceEmbedding
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_err()
+    );
     assert!(
         ModelType::SequenceClassification
-            .validate_model(&path)
+            .validate_model(&path, &Default::default(), &Default::default())
             .is_ok()
     );
 }
@@ -50,10 +89,91 @@ pub fn test_sequence_classification() {
 pub fn test_image_classification() {
     let path = PathBuf::from("../models/image_classification/model.onnx");
 
-    assert!(ModelType::ImageClassification.validate_model(&path).is_ok());
+    assert!(
+        ModelType::ImageClassification
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_ok()
+    );
+    assert!(
+        ModelType::TokenClassification
+            .validate_model(&path, &Default::default(), &Default::default())
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_output_names() {
+    let path = PathBuf::from("../models/embedding/model.onnx");
+
+    let names = ModelOutputs {
+        names: HashMap::from([("logits".to_string(), "last_hidden_state".to_string())]),
+        extra: vec![],
+    };
     assert!(
         ModelType::TokenClassification
-            .validate_model(&path)
+            .validate_model(&path, &names, &Default::default())
+            .is_ok()
+    );
+
+    let names = ModelOutputs {
+        names: HashMap::from([(
+            "last_hidden_state".to_string(),
+            "token_embeddings".to_string(),
+        )]),
+        extra: vec![],
+    };
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &names, &Default::default())
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_extra_outputs() {
+    let path = PathBuf::from("../models/embedding/model.onnx");
+
+    let names = ModelOutputs {
+        names: HashMap::new(),
+        extra: vec!["last_hidden_state".to_string()],
+    };
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &names, &Default::default())
+            .is_ok()
+    );
+
+    let names = ModelOutputs {
+        names: HashMap::new(),
+        extra: vec!["hidden_states.6".to_string()],
+    };
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &names, &Default::default())
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_input_names() {
+    let path = PathBuf::from("../models/embedding/model.onnx");
+
+    let inputs = ModelInputs {
+        names: HashMap::from([(InputRole::AttentionMask, "attention_mask".to_string())]),
+    };
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &Default::default(), &inputs)
+            .is_ok()
+    );
+
+    // input_ids is no longer bound and ids is not a graph input
+    let inputs = ModelInputs {
+        names: HashMap::from([(InputRole::InputIds, "ids".to_string())]),
+    };
+    assert!(
+        ModelType::Embedding
+            .validate_model(&path, &Default::default(), &inputs)
             .is_err()
     );
 }
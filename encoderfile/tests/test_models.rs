@@ -23,8 +23,15 @@ fn test_embedding_model() {
     let transform =
         Transform::new(DEFAULT_LIBS.to_vec(), None).expect("Failed to create_transform");
 
-    let results =
-        embedding(session_lock, &transform, encodings.clone()).expect("Failed to compute results");
+    let results = embedding(
+        session_lock,
+        &transform,
+        &state.outputs,
+        &state.inputs,
+        encodings.clone(),
+        &Default::default(),
+    )
+    .expect("Failed to compute results");
 
     assert!(results.len() == encodings.len());
 }
@@ -48,7 +55,15 @@ fn test_embedding_inference_with_bad_model() {
     let transform =
         Transform::new(DEFAULT_LIBS.to_vec(), None).expect("Failed to create_transform");
 
-    embedding(session_lock, &transform, encodings.clone()).expect("Failed to compute results");
+    embedding(
+        session_lock,
+        &transform,
+        &state.outputs,
+        &state.inputs,
+        encodings.clone(),
+        &Default::default(),
+    )
+    .expect("Failed to compute results");
 }
 
 #[test]
@@ -72,7 +87,10 @@ fn test_sequence_classification_model() {
     let results = sequence_classification(
         session_lock,
         &transform,
+        &state.outputs,
+        &state.inputs,
         &state.task_state,
+        0.5,
         encodings.clone(),
     )
     .expect("Failed to compute results");
@@ -132,8 +150,11 @@ fn test_token_classification_model() {
     let results = token_classification(
         session_lock,
         &transform,
+        &state.outputs,
+        &state.inputs,
         &state.task_state,
         encodings.clone(),
+        &Default::default(),
     )
     .expect("Failed to compute results");
 
@@ -162,8 +183,11 @@ fn test_token_classification_inference_with_bad_model() {
     token_classification(
         session_lock,
         &transform,
+        &state.outputs,
+        &state.inputs,
         &state.task_state,
         encodings.clone(),
+        &Default::default(),
     )
     .expect("Failed to compute results");
 }
@@ -1,11 +1,21 @@
 use encoderfile::{
     common::{
-        EmbeddingRequest, SentenceEmbeddingRequest, SequenceClassificationRequest,
-        TokenClassificationRequest,
+        AggregationStrategy, AudioClassificationRequest, AudioInfo, Calibration,
+        CountTokensRequest, DedupeRequest, DetokenizeRequest, EmbeddingRequest, EmbeddingValues,
+        EncodingFormat, FromCliInput, ImageEmbeddingRequest, ImageSegmentationRequest,
+        InferenceOptions, LabelConfig, LateInteractionRequest, LateInteractionScoreRequest,
+        MultimodalImageEmbeddingRequest, MultimodalSimilarityRequest,
+        MultimodalTextEmbeddingRequest, ObjectDetectionRequest, OffsetMapping, PoolingConfig,
+        PoolingMode, RedactRequest, RedactionMode, SearchRequest, SentenceEmbeddingRequest,
+        SequenceClassificationRequest, SimilarityRequest, SparseEmbeddingRequest,
+        TokenClassificationRequest, TokenizeRequest, Truncate, TruncationMode,
     },
     dev_utils::*,
+    error::ApiError,
+    runtime::ClassifierState,
     services::Inference,
 };
+use std::collections::HashMap;
 
 #[test]
 pub fn test_embedding_service() {
@@ -13,6 +23,7 @@ pub fn test_embedding_service() {
     let request = EmbeddingRequest {
         inputs: vec!["hello world".to_string()],
         metadata: None,
+        options: Default::default(),
     };
 
     let response = state
@@ -26,12 +37,181 @@ pub fn test_embedding_service() {
     );
 }
 
+#[test]
+pub fn test_embedding_service_options() {
+    let state = embedding_state();
+    let request = EmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            truncate: Some(Truncate::MaxLength(3)),
+            include_special_tokens: Some(true),
+            return_offsets: Some(false),
+            ..Default::default()
+        },
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    // [CLS] hello [SEP]
+    let embeddings = &response.results[0].embeddings;
+    assert_eq!(embeddings.len(), 3);
+    assert!(embeddings.iter().all(|e| e.token_info.is_none()));
+    assert_eq!(response.results[0].num_tokens, 3);
+    assert!(response.results[0].truncated);
+}
+
+#[test]
+pub fn test_embedding_service_strict_truncation() {
+    let state = embedding_state();
+    let request = |truncate| EmbeddingRequest {
+        inputs: vec!["hello".to_string(), "hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            truncate: Some(truncate),
+            truncation: Some(TruncationMode::Error),
+            ..Default::default()
+        },
+    };
+
+    let err = state
+        .inference(request(Truncate::MaxLength(3)))
+        .expect_err("Over-length input should be rejected");
+
+    assert!(matches!(
+        err,
+        ApiError::InputTooLong {
+            index: 1,
+            num_tokens: 4,
+            max_length: 3,
+        }
+    ));
+
+    let response = state
+        .inference(request(Truncate::MaxLength(4)))
+        .expect("Failed to compute embeddings");
+
+    assert_eq!(response.results[1].num_tokens, 4);
+    assert!(!response.results[1].truncated);
+}
+
+#[test]
+pub fn test_embedding_service_truncation_limits() {
+    let state = embedding_state();
+    let max_length = state.model_input_state.tokenizer.max_length();
+    let request = |truncate| EmbeddingRequest {
+        inputs: vec!["hello ".repeat(max_length)],
+        metadata: None,
+        options: InferenceOptions {
+            truncate: Some(truncate),
+            ..Default::default()
+        },
+    };
+
+    // lengths past the tokenizer's max_length are capped to it
+    let response = state
+        .inference(request(Truncate::MaxLength(max_length * 2)))
+        .expect("Failed to compute embeddings");
+
+    assert_eq!(response.results[0].num_tokens, max_length);
+    assert!(response.results[0].truncated);
+
+    // without truncation, inputs the model cannot take are rejected
+    let err = state
+        .inference(request(Truncate::Enabled(false)))
+        .expect_err("Over-length inputs should be rejected without truncation");
+
+    assert!(matches!(
+        err,
+        ApiError::InputTooLong { index: 0, max_length: m, .. } if m == max_length
+    ));
+}
+
+#[test]
+pub fn test_embedding_service_prompts() {
+    let state = embedding_prompt_state();
+    let text = "hello world";
+
+    for prompt_name in [None, Some("passage".to_string())] {
+        let request = EmbeddingRequest {
+            inputs: vec![text.to_string()],
+            metadata: None,
+            options: InferenceOptions {
+                prompt_name,
+                ..Default::default()
+            },
+        };
+
+        let response = state
+            .inference(request)
+            .expect("Failed to compute embeddings");
+
+        // prompt tokens are dropped with special tokens and offsets are relative to the input
+        let tokens: Vec<_> = response.results[0]
+            .embeddings
+            .iter()
+            .map(|e| e.token_info.as_ref().expect("Missing token info"))
+            .map(|t| &text[t.start..t.end])
+            .collect();
+        assert_eq!(tokens, vec!["hello", "world"]);
+    }
+
+    let request = EmbeddingRequest {
+        inputs: vec![text.to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            prompt_name: Some("document".to_string()),
+            ..Default::default()
+        },
+    };
+    assert!(state.inference(request).is_err());
+}
+
+#[test]
+pub fn test_embedding_service_prompt_without_trailing_space() {
+    let mut state = std::sync::Arc::into_inner(embedding_prompt_state()).expect("State is shared");
+    state
+        .model_input_state
+        .model_config
+        .prompts
+        .get_or_insert_default()
+        .insert("prefix".to_string(), "pre".to_string());
+    let state = std::sync::Arc::new(state);
+    let text = "fix world";
+
+    let request = EmbeddingRequest {
+        inputs: vec![text.to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            prompt_name: Some("prefix".to_string()),
+            ..Default::default()
+        },
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    // "prefix" straddles the end of the prompt and keeps its part in the input
+    let tokens: Vec<_> = response.results[0]
+        .embeddings
+        .iter()
+        .map(|e| e.token_info.as_ref().expect("Missing token info"))
+        .map(|t| &text[t.start..t.end])
+        .collect();
+    assert_eq!(tokens, vec!["fix", "world"]);
+}
+
 #[test]
 pub fn test_sequence_classification_service() {
     let state = sequence_classification_state();
     let request = SequenceClassificationRequest {
         inputs: vec!["hello world".to_string()],
+        threshold: None,
         metadata: None,
+        options: Default::default(),
     };
 
     let response = state
@@ -43,6 +223,113 @@ pub fn test_sequence_classification_service() {
         response.metadata.is_none(),
         "Metadata should be returned None"
     );
+    assert!(
+        response.threshold.is_none(),
+        "Threshold should only be returned for multi-label models"
+    );
+    assert_eq!(response.results[0].predicted_labels.len(), 1);
+}
+
+#[test]
+pub fn test_sequence_classification_service_rejects_threshold() {
+    let state = sequence_classification_state();
+    let request = SequenceClassificationRequest {
+        inputs: vec!["hello world".to_string()],
+        threshold: Some(0.3),
+        metadata: None,
+        options: Default::default(),
+    };
+
+    assert!(state.inference(request).is_err());
+}
+
+#[test]
+pub fn test_sequence_classification_service_merged_labels() {
+    let merged = LabelConfig {
+        name: Some("any".to_string()),
+        ..Default::default()
+    };
+    let overrides = HashMap::from([
+        ("good".to_string(), merged.clone()),
+        ("bad".to_string(), merged),
+    ]);
+
+    for transform in [
+        None,
+        Some("function Postprocess(arr) return arr:softmax(2) end"),
+    ] {
+        let mut state =
+            std::sync::Arc::into_inner(sequence_classification_state()).expect("State is shared");
+        let mut model_config = state.model_input_state.model_config.clone();
+        model_config.label_overrides = Some(overrides.clone());
+        state.task_state =
+            ClassifierState::from_model_config(&model_config).expect("Invalid label overrides");
+        state.config.transform = transform.map(str::to_string);
+        let state = std::sync::Arc::new(state);
+
+        let request = SequenceClassificationRequest {
+            inputs: vec!["hello world".to_string()],
+            threshold: None,
+            metadata: None,
+            options: Default::default(),
+        };
+
+        let response = state
+            .inference(request)
+            .expect("Failed to classify sequence");
+
+        // merged labels sum the softmax of the logits, whatever the transform returns
+        let result = &response.results[0];
+        assert_eq!(result.predicted_label, Some("any".to_string()));
+        assert_eq!(result.scores.len(), 1);
+        assert!((result.scores[0] - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+pub fn test_sequence_classification_service_calibration() {
+    let request = || SequenceClassificationRequest {
+        inputs: vec!["hello world".to_string()],
+        threshold: None,
+        metadata: None,
+        options: InferenceOptions {
+            return_logits: Some(true),
+            ..Default::default()
+        },
+    };
+
+    let response = sequence_classification_state()
+        .inference(request())
+        .expect("Failed to classify sequence");
+    let logits = response.results[0].logits.clone();
+    let exp: Vec<f32> = logits.iter().map(|l| (l / 2.0).exp()).collect();
+    let sum: f32 = exp.iter().sum();
+
+    for transform in [
+        None,
+        Some("function Postprocess(arr) return arr:softmax(2) end"),
+    ] {
+        let mut state =
+            std::sync::Arc::into_inner(sequence_classification_state()).expect("State is shared");
+        state.task_state.calibration = Some(Calibration::Temperature { temperature: 2.0 });
+        state.config.transform = transform.map(str::to_string);
+        let state = std::sync::Arc::new(state);
+
+        let response = state
+            .inference(request())
+            .expect("Failed to classify sequence");
+
+        // scores are the softmax of the calibrated logits, whatever the transform returns
+        let result = &response.results[0];
+        assert_eq!(result.scores.len(), exp.len());
+        assert!(
+            result
+                .scores
+                .iter()
+                .zip(&exp)
+                .all(|(score, e)| (score - e / sum).abs() < 1e-5)
+        );
+    }
 }
 
 #[test]
@@ -50,7 +337,11 @@ pub fn test_token_classification_service() {
     let state = token_classification_state();
     let request = TokenClassificationRequest {
         inputs: vec!["hello world".to_string()],
+        words: None,
+        aggregation_strategy: None,
+        ignore_labels: None,
         metadata: None,
+        options: Default::default(),
     };
 
     let response = state
@@ -64,12 +355,182 @@ pub fn test_token_classification_service() {
     );
 }
 
+#[test]
+pub fn test_token_classification_service_aggregation() {
+    let state = token_classification_state();
+    let text = "Hugging Face is based in New York City".to_string();
+    let request = TokenClassificationRequest {
+        inputs: vec![text.clone()],
+        words: None,
+        aggregation_strategy: Some(AggregationStrategy::Simple),
+        ignore_labels: None,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute token classifications");
+
+    let entities = response.results[0]
+        .entities
+        .as_ref()
+        .expect("Entities should be returned");
+
+    for entity in entities {
+        assert_ne!(entity.entity_group, "O", "O should be ignored by default");
+        assert_eq!(entity.word, text[entity.start..entity.end]);
+    }
+}
+
+#[test]
+pub fn test_token_classification_service_words() {
+    let state = token_classification_state();
+    let words: Vec<String> = [
+        "Hugging", "Face", "is", "based", "in", "New", "York", "City",
+    ]
+    .map(String::from)
+    .into();
+    let request = |inputs| TokenClassificationRequest {
+        inputs,
+        words: Some(vec![words.clone()]),
+        aggregation_strategy: Some(AggregationStrategy::First),
+        ignore_labels: None,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state
+        .inference(request(Vec::new()))
+        .expect("Failed to compute token classifications");
+
+    let predictions = response.results[0]
+        .words
+        .as_ref()
+        .expect("Words should be returned");
+    assert_eq!(predictions.len(), words.len());
+
+    for (i, prediction) in predictions.iter().enumerate() {
+        assert_eq!(prediction.word_index, i);
+        assert_eq!(prediction.word, words[i]);
+    }
+
+    // offsets are relative to the words joined with spaces
+    let text = words.join(" ");
+    for entity in response.results[0].entities.iter().flatten() {
+        assert_eq!(entity.word, text[entity.start..entity.end]);
+    }
+
+    assert!(
+        state
+            .inference(request(vec!["hello world".to_string()]))
+            .is_err(),
+        "inputs and words should be exclusive"
+    );
+}
+
+#[test]
+pub fn test_token_classification_redact() {
+    let state = token_classification_state();
+    let text = "Hugging Face is based in New York City";
+    let request = |mode, mask_char: Option<&str>| RedactRequest {
+        inputs: vec![text.to_string(), text.to_string()],
+        mode: Some(mode),
+        mask_char: mask_char.map(String::from),
+        labels: None,
+        // redact every group, so that there is something to redact
+        ignore_labels: Some(Vec::new()),
+        threshold: None,
+        aggregation_strategy: None,
+        options: Default::default(),
+        metadata: None,
+    };
+
+    let response = state
+        .redact(request(RedactionMode::Pseudonym, None))
+        .expect("Failed to redact");
+
+    let redaction = &response.results[0];
+    assert!(!redaction.spans.is_empty());
+    assert_eq!(
+        redaction.text, response.results[1].text,
+        "Pseudonyms should be consistent within a request"
+    );
+    for span in redaction.spans.iter() {
+        assert!(redaction.text.contains(&span.replacement));
+    }
+
+    let response = state
+        .redact(request(RedactionMode::Mask, Some("#")))
+        .expect("Failed to redact");
+
+    let redaction = &response.results[0];
+    assert_eq!(redaction.text.chars().count(), text.chars().count());
+    for span in redaction.spans.iter() {
+        assert_eq!(redaction.text[span.start..span.end], span.replacement);
+    }
+
+    assert!(
+        state
+            .redact(request(RedactionMode::Mask, Some("##")))
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_token_classification_service_offset_mapping() {
+    let state = token_classification_state();
+    let text = "Zoë moved from Zürich to São Paulo";
+    let request = |offset_mapping| TokenClassificationRequest {
+        inputs: vec![text.to_string()],
+        words: None,
+        aggregation_strategy: Some(AggregationStrategy::Simple),
+        ignore_labels: Some(Vec::new()),
+        metadata: None,
+        options: InferenceOptions {
+            offset_mapping: Some(offset_mapping),
+            ..Default::default()
+        },
+    };
+
+    let bytes = state
+        .inference(request(OffsetMapping::Byte))
+        .expect("Failed to compute token classifications");
+    let chars = state
+        .inference(request(OffsetMapping::Char))
+        .expect("Failed to compute token classifications");
+
+    let char_offset = |byte_offset: usize| text[..byte_offset].chars().count();
+
+    for (b, c) in bytes.results[0].tokens.iter().zip(&chars.results[0].tokens) {
+        assert_eq!(c.token_info.start, char_offset(b.token_info.start));
+        assert_eq!(c.token_info.end, char_offset(b.token_info.end));
+    }
+
+    let byte_entities = bytes.results[0].entities.as_ref().unwrap();
+    let char_entities = chars.results[0].entities.as_ref().unwrap();
+    assert!(!char_entities.is_empty());
+
+    for (b, c) in byte_entities.iter().zip(char_entities) {
+        assert_eq!(b.word, c.word);
+        assert_eq!(c.start, char_offset(b.start));
+        assert_eq!(c.end, char_offset(b.end));
+    }
+
+    assert_eq!(
+        char_entities.last().unwrap().end,
+        text.chars().count(),
+        "Entities should span the input in characters"
+    );
+}
+
 #[test]
 pub fn test_sentence_embedding_service() {
     let state = sentence_embedding_state();
     let request = SentenceEmbeddingRequest {
         inputs: vec!["hello world".to_string()],
         metadata: None,
+        options: Default::default(),
     };
 
     let response = state
@@ -82,3 +543,749 @@ pub fn test_sentence_embedding_service() {
         "Metadata should be returned None"
     );
 }
+
+#[test]
+pub fn test_sentence_embedding_service_options() {
+    let state = sentence_embedding_state();
+    let request = SentenceEmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            dimensions: Some(8),
+            normalize: Some(true),
+            ..Default::default()
+        },
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    let embedding = response.results[0]
+        .embedding
+        .as_float()
+        .expect("Expected float embedding");
+    assert_eq!(embedding.len(), 8);
+
+    let norm: f32 = embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+}
+
+#[test]
+pub fn test_sentence_embedding_service_matryoshka_dimensions() {
+    let mut state =
+        std::sync::Arc::into_inner(sentence_embedding_state()).expect("State is shared");
+    state.model_input_state.model_config.matryoshka_dimensions = Some(vec![8, 16]);
+    let state = std::sync::Arc::new(state);
+
+    let request = |dimensions| SentenceEmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            dimensions,
+            ..Default::default()
+        },
+    };
+
+    assert!(matches!(
+        state.inference(request(Some(12))),
+        Err(ApiError::InputError(_))
+    ));
+
+    let response = state
+        .inference(request(Some(8)))
+        .expect("Failed to compute embeddings");
+    let embedding = response.results[0]
+        .embedding
+        .as_float()
+        .expect("Expected float embedding");
+    assert_eq!(embedding.len(), 8);
+
+    // truncated embeddings are re-normalized
+    let norm: f32 = embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+
+    let response = state
+        .inference(request(None))
+        .expect("Failed to compute embeddings");
+    let embedding = response.results[0]
+        .embedding
+        .as_float()
+        .expect("Expected float embedding");
+    assert_eq!(embedding.len(), 16);
+}
+
+#[test]
+pub fn test_sentence_embedding_service_pooling() {
+    let options = InferenceOptions {
+        normalize: Some(false),
+        ..Default::default()
+    };
+
+    let response = embedding_state()
+        .inference(EmbeddingRequest {
+            inputs: vec!["hello world".to_string()],
+            metadata: None,
+            options: InferenceOptions {
+                include_special_tokens: Some(true),
+                ..options.clone()
+            },
+        })
+        .expect("Failed to compute embeddings");
+    let cls = response.results[0].embeddings[0].embedding.clone();
+
+    let pooled = |mode, normalize| {
+        let mut state =
+            std::sync::Arc::into_inner(sentence_embedding_state()).expect("State is shared");
+        state.model_input_state.model_config.pooling = Some(PoolingConfig { mode, normalize });
+
+        let response = std::sync::Arc::new(state)
+            .inference(SentenceEmbeddingRequest {
+                inputs: vec!["hello world".to_string()],
+                metadata: None,
+                options: options.clone(),
+            })
+            .expect("Failed to compute embeddings");
+
+        response.results[0]
+            .embedding
+            .as_float()
+            .expect("Expected float embedding")
+            .to_vec()
+    };
+
+    let embedding = pooled(PoolingMode::Cls, false);
+    assert_eq!(embedding.len(), cls.len());
+    assert!(
+        embedding
+            .iter()
+            .zip(&cls)
+            .all(|(a, b)| (a - b).abs() < 1e-5)
+    );
+
+    // the CLS token is one of the pooled tokens
+    let embedding = pooled(PoolingMode::Max, false);
+    assert!(embedding.iter().zip(&cls).all(|(a, b)| a >= b));
+
+    let embedding = pooled(PoolingMode::Mean, true);
+    let norm: f32 = embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+}
+
+#[test]
+pub fn test_sentence_embedding_service_encoding_format() {
+    let state = sentence_embedding_state();
+    let request = SentenceEmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            dimensions: Some(16),
+            encoding_format: Some(EncodingFormat::Ubinary),
+            base64: Some(true),
+            ..Default::default()
+        },
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    match &response.results[0].embedding {
+        EmbeddingValues::Base64(bytes) => assert_eq!(bytes.len(), 2),
+        embedding => panic!("Expected base64 embedding, got {:?}", embedding),
+    }
+}
+
+#[test]
+pub fn test_sentence_embedding_service_rejects_unsupported_options() {
+    let state = sentence_embedding_state();
+    let request = SentenceEmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        metadata: None,
+        options: InferenceOptions {
+            top_k: Some(1),
+            ..Default::default()
+        },
+    };
+
+    assert!(state.inference(request).is_err());
+}
+
+#[test]
+pub fn test_sentence_embedding_similarity() {
+    let state = sentence_embedding_state();
+    let request = SimilarityRequest {
+        sources: vec!["hello world".to_string(), "the quick brown fox".to_string()],
+        targets: None,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state.similarity(request).expect("Failed to compare");
+
+    assert_eq!(response.similarities.len(), 2);
+    assert_eq!(response.similarities[0].len(), 2);
+    assert!((response.similarities[0][0] - 1.0).abs() < 1e-4);
+    assert!(response.similarities[0][1] < response.similarities[0][0]);
+    assert!((response.similarities[0][1] - response.similarities[1][0]).abs() < 1e-5);
+}
+
+#[test]
+pub fn test_sentence_embedding_dedupe() {
+    let state = sentence_embedding_state();
+    let request = DedupeRequest {
+        inputs: vec![
+            "hello world".to_string(),
+            "the quick brown fox".to_string(),
+            "hello world".to_string(),
+        ],
+        threshold: Some(0.99),
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state.dedupe(request).expect("Failed to dedupe");
+
+    assert_eq!(response.assignments, vec![0, 1, 0]);
+    assert_eq!(response.clusters, vec![vec![0, 2], vec![1]]);
+}
+
+#[test]
+pub fn test_sentence_embedding_search() {
+    let state = sentence_embedding_corpus_state();
+    let request = SearchRequest {
+        query: "Which programming language is memory safe?".to_string(),
+        top_k: Some(2),
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state.search(request).expect("Failed to search");
+
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(response.results[0].id, "rust");
+    assert!(response.results[0].score >= response.results[1].score);
+    assert_eq!(
+        response.results[0]
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("source"))
+            .map(String::as_str),
+        Some("test")
+    );
+}
+
+#[test]
+pub fn test_sentence_embedding_search_without_corpus() {
+    let state = sentence_embedding_state();
+    let request = SearchRequest {
+        query: "hello world".to_string(),
+        top_k: None,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    assert!(state.search(request).is_err());
+}
+
+#[test]
+pub fn test_sparse_embedding_service() {
+    let state = sparse_embedding_state();
+    let request = SparseEmbeddingRequest {
+        inputs: vec![
+            "hello world".to_string(),
+            "the stock market fell".to_string(),
+        ],
+        top_k: None,
+        threshold: None,
+        return_tokens: true,
+        options: Default::default(),
+        metadata: None,
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute sparse embeddings");
+
+    assert_eq!(response.results.len(), 2);
+    for result in response.results.iter() {
+        assert_eq!(result.indices.len(), result.values.len());
+        assert!(result.indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(result.values.iter().all(|v| *v > 0.0));
+        assert_eq!(
+            result.tokens.as_ref().map(Vec::len),
+            Some(result.indices.len())
+        );
+    }
+
+    let request = SparseEmbeddingRequest {
+        inputs: vec!["hello world".to_string()],
+        top_k: Some(1),
+        // weights are never negative, so every term passes
+        threshold: Some(-1.0),
+        return_tokens: false,
+        options: Default::default(),
+        metadata: None,
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute sparse embeddings");
+
+    assert_eq!(response.results[0].indices.len(), 1);
+    assert!(response.results[0].tokens.is_none());
+}
+
+#[test]
+pub fn test_late_interaction_service() {
+    let state = late_interaction_state();
+    let request = LateInteractionRequest {
+        inputs: vec!["hello world".to_string(), "hello, world!".to_string()],
+        is_query: false,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    assert!(response.results.len() == 2, "Didn't return two results");
+
+    // punctuation is masked in documents: [CLS] hello world [SEP]
+    for result in response.results.iter() {
+        assert_eq!(result.n_tokens, 4);
+        assert_eq!(result.values.len(), result.n_tokens * result.dim);
+    }
+
+    // token vectors are L2-normalized
+    let norm: f32 = response.results[0].values[..response.results[0].dim]
+        .iter()
+        .map(|x| x * x)
+        .sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+}
+
+#[test]
+pub fn test_late_interaction_query_augmentation() {
+    let mut state = late_interaction_state();
+    std::sync::Arc::get_mut(&mut state)
+        .unwrap()
+        .task_state
+        .config
+        .query_length = Some(16);
+
+    let request = LateInteractionRequest {
+        inputs: vec!["hello world".to_string()],
+        is_query: true,
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute embeddings");
+
+    assert_eq!(response.results[0].n_tokens, 16);
+}
+
+#[test]
+pub fn test_late_interaction_query_truncation() {
+    let mut state = late_interaction_state();
+    std::sync::Arc::get_mut(&mut state)
+        .unwrap()
+        .task_state
+        .config
+        .query_length = Some(4);
+
+    let query = "the stock market fell sharply today".to_string();
+    let mask_token = state.task_state.config.mask_token.clone();
+
+    // truncated queries keep their trailing [SEP] before the mask padding
+    let encodings = state
+        .model_input_state
+        .tokenizer
+        .encode_text_fixed_length(
+            vec![query.clone()],
+            4,
+            &mask_token,
+            Some(Truncate::MaxLength(3)),
+            TruncationMode::Truncate,
+        )
+        .expect("Failed to encode query");
+
+    let tokens = encodings[0].get_tokens();
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(tokens[2], "[SEP]");
+    assert_eq!(tokens[3], mask_token);
+
+    let request = |options| LateInteractionRequest {
+        inputs: vec![query.clone()],
+        is_query: true,
+        metadata: None,
+        options,
+    };
+
+    let response = state
+        .inference(request(InferenceOptions {
+            truncate: Some(Truncate::MaxLength(3)),
+            ..Default::default()
+        }))
+        .expect("Failed to compute embeddings");
+
+    assert_eq!(response.results[0].n_tokens, 4);
+    assert_eq!(response.results[0].num_tokens, 3);
+    assert!(response.results[0].truncated);
+
+    for options in [
+        InferenceOptions {
+            truncation: Some(TruncationMode::Error),
+            ..Default::default()
+        },
+        InferenceOptions {
+            truncate: Some(Truncate::Enabled(false)),
+            ..Default::default()
+        },
+    ] {
+        let err = state
+            .inference(request(options))
+            .expect_err("Over-length queries should be rejected");
+
+        assert!(matches!(
+            err,
+            ApiError::InputTooLong {
+                index: 0,
+                max_length: 4,
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+pub fn test_late_interaction_score() {
+    let state = late_interaction_state();
+    let request = LateInteractionScoreRequest {
+        query: "hello world".to_string(),
+        documents: vec![
+            "hello world".to_string(),
+            "the stock market fell".to_string(),
+        ],
+        metadata: None,
+        options: Default::default(),
+    };
+
+    let response = state.score(request).expect("Failed to score");
+
+    assert_eq!(response.scores.len(), 2);
+    assert!(response.scores[0] > response.scores[1]);
+}
+
+#[test]
+pub fn test_tokenize() {
+    let state = embedding_state();
+    let text = "hello world";
+    let request = TokenizeRequest {
+        inputs: vec![
+            text.to_string(),
+            "a much longer input than the first one".to_string(),
+        ],
+        truncate: None,
+        offset_mapping: None,
+        metadata: None,
+    };
+
+    let response = state.tokenize(request).expect("Failed to tokenize");
+
+    // padding is dropped
+    let tokenization = &response.results[0];
+    assert_eq!(tokenization.ids.len(), 4);
+    assert_eq!(tokenization.tokens.len(), 4);
+    assert_eq!(tokenization.special_tokens_mask, vec![1, 0, 0, 1]);
+    assert_eq!(
+        &text[tokenization.offsets[1][0]..tokenization.offsets[1][1]],
+        "hello"
+    );
+    assert!(!tokenization.truncated);
+
+    let request = TokenizeRequest {
+        inputs: vec![text.to_string()],
+        truncate: Some(Truncate::MaxLength(3)),
+        offset_mapping: None,
+        metadata: None,
+    };
+
+    let response = state.tokenize(request).expect("Failed to tokenize");
+
+    assert_eq!(response.results[0].ids.len(), 3);
+    assert!(response.results[0].truncated);
+}
+
+#[test]
+pub fn test_tokenize_offset_mapping() {
+    let state = embedding_state();
+    let text = "né world";
+    let request = |offset_mapping| TokenizeRequest {
+        inputs: vec![text.to_string()],
+        truncate: None,
+        offset_mapping,
+        metadata: None,
+    };
+
+    // "é" is 2 UTF-8 bytes, but 1 character and 1 UTF-16 code unit
+    for (offset_mapping, expected) in [
+        (None, [4, 9]),
+        (Some(OffsetMapping::Byte), [4, 9]),
+        (Some(OffsetMapping::Char), [3, 8]),
+        (Some(OffsetMapping::Utf16), [3, 8]),
+    ] {
+        let response = state
+            .tokenize(request(offset_mapping))
+            .expect("Failed to tokenize");
+        let tokenization = &response.results[0];
+        let world = tokenization
+            .tokens
+            .iter()
+            .position(|t| t == "world")
+            .expect("Missing token");
+
+        assert_eq!(tokenization.offsets[world], expected);
+        // special tokens keep [0, 0]
+        assert_eq!(tokenization.offsets[0], [0, 0]);
+    }
+}
+
+#[test]
+pub fn test_detokenize() {
+    let state = embedding_state();
+    let tokenized = state
+        .tokenize(TokenizeRequest {
+            inputs: vec!["hello world".to_string()],
+            truncate: None,
+            offset_mapping: None,
+            metadata: None,
+        })
+        .expect("Failed to tokenize");
+    let ids = tokenized.results[0].ids.clone();
+
+    let response = state
+        .detokenize(DetokenizeRequest {
+            inputs: vec![ids.clone()],
+            skip_special_tokens: None,
+            metadata: None,
+        })
+        .expect("Failed to detokenize");
+    assert_eq!(response.results, vec!["hello world".to_string()]);
+
+    let response = state
+        .detokenize(DetokenizeRequest {
+            inputs: vec![ids],
+            skip_special_tokens: Some(false),
+            metadata: None,
+        })
+        .expect("Failed to detokenize");
+    assert_ne!(response.results[0], "hello world");
+
+    let request = DetokenizeRequest {
+        inputs: vec![vec![u32::MAX]],
+        skip_special_tokens: None,
+        metadata: None,
+    };
+    assert!(state.detokenize(request).is_err());
+}
+
+#[test]
+pub fn test_count_tokens() {
+    let state = embedding_state();
+    let request = CountTokensRequest {
+        inputs: vec!["hello world".to_string(), "hello ".repeat(1000)],
+        metadata: None,
+    };
+
+    let response = state.count_tokens(request).expect("Failed to count tokens");
+
+    assert_eq!(response.results[0].num_tokens, 4);
+    assert!(!response.results[0].truncated);
+    assert_eq!(response.results[1].num_tokens, 1002);
+    assert!(response.results[1].truncated);
+    assert!(response.max_length < 1002);
+}
+
+const TEST_IMAGE_PATH: &str = "../test-pictures/yoga01.jpg";
+
+#[test]
+pub fn test_image_embedding_service() {
+    let state = image_embedding_state();
+    let request = ImageEmbeddingRequest::from_cli_input(vec![TEST_IMAGE_PATH.to_string(); 2]);
+
+    let response = state
+        .inference(request)
+        .expect("Failed to compute image embeddings");
+
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(response.results[0].embedding, response.results[1].embedding);
+
+    // embeddings are normalized by default
+    let norm: f32 = response.results[0].embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+}
+
+#[test]
+pub fn test_object_detection_service() {
+    let state = object_detection_state();
+
+    let request = ObjectDetectionRequest::from_cli_input(vec![]);
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+
+    // the image classifier returns [n_batch, n_classes] logits and no boxes
+    let request = ObjectDetectionRequest::from_cli_input(vec![TEST_IMAGE_PATH.to_string()]);
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InternalError(_))
+    ));
+}
+
+#[test]
+pub fn test_image_segmentation_service() {
+    let state = image_segmentation_state();
+
+    let request = ImageSegmentationRequest::from_cli_input(vec![]);
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+
+    // the image classifier returns [n_batch, n_classes] logits rather than a mask per label
+    let request = ImageSegmentationRequest::from_cli_input(vec![TEST_IMAGE_PATH.to_string()]);
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InternalError(_))
+    ));
+}
+
+fn sine(amplitude: f32, num_samples: usize) -> Vec<f32> {
+    (0..num_samples)
+        .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+        .collect()
+}
+
+#[test]
+pub fn test_audio_classification_service() {
+    let state = audio_classification_state();
+    let audio = |amplitude| AudioInfo {
+        audio_bytes: wav_bytes(&sine(amplitude, 16000), 16000, 1),
+    };
+
+    let request = AudioClassificationRequest {
+        audios: vec![audio(0.5), audio(0.1)],
+        threshold: None,
+        metadata: None,
+    };
+
+    let response = state.inference(request).expect("Failed to classify audio");
+
+    // mean squared samples of 0.125 and 0.005
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(
+        response.results[0].predicted_label,
+        Some("loud".to_string())
+    );
+    assert!((response.results[0].logits[0] - 0.075).abs() < 1e-3);
+    assert_eq!(
+        response.results[1].predicted_label,
+        Some("quiet".to_string())
+    );
+    assert!((response.results[1].logits[1] - 0.045).abs() < 1e-3);
+    assert!(response.threshold.is_none());
+}
+
+#[test]
+pub fn test_audio_classification_service_rejects_invalid_requests() {
+    let state = audio_classification_state();
+
+    let request = AudioClassificationRequest {
+        audios: vec![],
+        threshold: None,
+        metadata: None,
+    };
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+
+    // the model is single-label
+    let request = AudioClassificationRequest {
+        audios: vec![AudioInfo {
+            audio_bytes: wav_bytes(&sine(0.5, 1600), 16000, 1),
+        }],
+        threshold: Some(0.5),
+        metadata: None,
+    };
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+
+    let request = AudioClassificationRequest {
+        audios: vec![AudioInfo {
+            audio_bytes: "not audio".into(),
+        }],
+        threshold: None,
+        metadata: None,
+    };
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+}
+
+#[test]
+pub fn test_multimodal_embedding_service() {
+    let state = multimodal_embedding_state();
+
+    let response = state
+        .embed_text(MultimodalTextEmbeddingRequest {
+            inputs: vec!["hello world".to_string(), "goodbye".to_string()],
+            metadata: None,
+        })
+        .expect("Failed to embed texts");
+    assert_eq!(response.results.len(), 2);
+    let norm: f32 = response.results[0].embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+
+    let images = || ImageEmbeddingRequest::from_cli_input(vec![TEST_IMAGE_PATH.to_string()]).images;
+
+    let response = state
+        .embed_image(MultimodalImageEmbeddingRequest {
+            images: images(),
+            metadata: None,
+        })
+        .expect("Failed to embed images");
+    assert_eq!(response.results.len(), 1);
+    let norm: f32 = response.results[0].embedding.iter().map(|x| x * x).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+
+    let request = MultimodalSimilarityRequest {
+        images: images(),
+        labels: vec![],
+        metadata: None,
+    };
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InputError(_))
+    ));
+
+    // the towers embed into 2 and 9 dimensions
+    let request = MultimodalSimilarityRequest {
+        images: images(),
+        labels: vec!["a yoga pose".to_string()],
+        metadata: None,
+    };
+    assert!(matches!(
+        state.inference(request),
+        Err(ApiError::InternalError(_))
+    ));
+}
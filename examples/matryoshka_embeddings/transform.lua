@@ -1,11 +1,11 @@
 ---Generated by Encoderfile ❤️
 ---Remember: Lua is 1-indexed!
 
-MatryoshkaDim = 512
 Eps = 1e-5
 
 ---Postprocessing script follows instructions from the official model repository:
 ---https://huggingface.co/nomic-ai/nomic-embed-text-v1.5
+---Matryoshka truncation is applied per request with the `dimensions` option.
 
 ---Postprocess embeddings
 ---Must return 2D tensor of shape [batch_size, *]
@@ -19,10 +19,7 @@ function Postprocess(arr, mask)
     ---Step 2: layer_norm along 2nd axis (1st axis in PyTorch land)
     embeddings = embeddings:layer_norm(2, Eps)
 
-    ---Step 3: truncate along 2nd axis
-    embeddings = embeddings:truncate_axis(2, MatryoshkaDim)
-
-    ---Step 4: l2 normalize along 2nd axis (1st axis in PyTorch land)
+    ---Step 3: l2 normalize along 2nd axis (1st axis in PyTorch land)
     embeddings = embeddings:lp_normalize(2.0, 2)
 
     return embeddings